@@ -0,0 +1,53 @@
+//! Compares adding 500 rows to a form in a shown window one by one, and inside
+//! `UI::with_batched_layout`, printing how long each took.
+
+extern crate iui;
+use iui::prelude::*;
+use iui::controls::{Entry, Form};
+use std::time::{Duration, Instant};
+
+const ROWS: usize = 500;
+// How many iterations of the event loop are given to the window to lay itself out and redraw.
+const SETTLE_TICKS: usize = 200;
+
+// Shows a window with an empty form, then times adding the rows to it, including the event loop
+// iterations which lay out and draw the window afterwards.
+fn time_rows(ui: &UI, title: &str, batched: bool) -> Duration {
+    let mut window = Window::new(ui, title, 400, 600, WindowType::NoMenubar);
+    let mut form = Form::new(ui);
+    window.set_child(ui, form.clone());
+    window.show(ui);
+    let mut event_loop = ui.event_loop();
+    for _ in 0..SETTLE_TICKS {
+        event_loop.next_tick(ui);
+    }
+
+    let container = form.clone();
+    let start = Instant::now();
+    let mut add_rows = || {
+        for i in 0..ROWS {
+            form.append(ui, &format!("Setting {}", i), Entry::new(ui), LayoutStrategy::Compact);
+        }
+    };
+    if batched {
+        ui.with_batched_layout(container, add_rows);
+    } else {
+        add_rows();
+    }
+    for _ in 0..SETTLE_TICKS {
+        event_loop.next_tick(ui);
+    }
+    let elapsed = start.elapsed();
+
+    unsafe { window.destroy() };
+    elapsed
+}
+
+fn main() {
+    let ui = UI::init().expect("Couldn't initialize UI library");
+
+    let unbatched = time_rows(&ui, "Unbatched", false);
+    let batched = time_rows(&ui, "Batched", true);
+    println!("{} rows one by one:         {:?}", ROWS, unbatched);
+    println!("{} rows with_batched_layout: {:?}", ROWS, batched);
+}
@@ -5,7 +5,6 @@ extern crate iui;
 use iui::prelude::*;
 use iui::controls::{VerticalBox, MultilineEntry, Button};
 use std::io::prelude::*;
-use std::error::Error;
 use std::fs::File;
 
 fn main() {
@@ -30,15 +29,14 @@ fn main() {
     button.on_clicked(&ui, {
         let ui = ui.clone();
         move |_| {
-            if let Some(path) = window.save_file(&ui) {
+            if let Ok(Some(path)) = window.save_file(&ui) {
                 let mut file = match File::create(&path) {
-                    Err(why) => { window.modal_err(&ui, "I/O Error", &format!("Could not open file {}: {}", path.display(), why.description())); return; }
+                    Err(why) => { window.modal_err(&ui, "I/O Error", &format!("Could not open file {}: {}", path.display(), why)); return; }
                     Ok(f) => f
                 };
-                match file.write_all(entry.value(&ui).as_bytes()) {
-                    Err(why) => { window.modal_err(&ui, "I/O Error", &format!("Could not write to file {}: {}", path.display(), why.description())); return; }
-                    Ok(_) => ()
-                };
+                if let Err(why) = file.write_all(entry.value(&ui).as_bytes()) {
+                    window.modal_err(&ui, "I/O Error", &format!("Could not write to file {}: {}", path.display(), why));
+                }
             }    
         }
     });
@@ -0,0 +1,306 @@
+//! Reading and writing text on the system clipboard.
+//!
+//! `libui` has no clipboard functions, so this module talks to the native toolkit directly:
+//! GTK+ on Linux and the BSDs, the Win32 clipboard on Windows, and the general pasteboard on
+//! macOS. It is only built with the `clipboard` feature:
+//!
+//! ```toml
+//! iui = { version = "0.3", features = ["clipboard"] }
+//! ```
+//!
+//! Like the controls, these functions take a `&UI`, as the clipboard may only be used from the
+//! GUI thread. They can be called from any callback, while the event loop runs.
+//!
+//! ```no_run
+//! # use iui::prelude::*;
+//! # use iui::clipboard;
+//! # use iui::controls::Button;
+//! # let ui = UI::init().unwrap();
+//! let mut copy = Button::new(&ui, "Copy result");
+//! copy.on_clicked(&ui, {
+//!     let ui = ui.clone();
+//!     move |_| clipboard::set_text(&ui, "42").unwrap()
+//! });
+//! ```
+
+use error::UIError;
+use ui::UI;
+
+/// Returns the text on the clipboard, or `None` if it holds no text.
+///
+/// With GTK+, this waits for the application owning the clipboard to hand over its text,
+/// running the event loop in the meantime.
+pub fn text(_ctx: &UI) -> Option<String> {
+    unsafe { native::text() }
+}
+
+/// Puts `text` on the clipboard, replacing what it held. As for all text given to this crate,
+/// `text` is cut short at its first NUL byte.
+///
+/// This fails with [`ClipboardUnavailable`](../enum.UIError.html#variant.ClipboardUnavailable)
+/// on Windows if another application has the clipboard open. With GTK+ under X11, the text is
+/// kept by this application, so it is lost when the application exits unless a clipboard
+/// manager is running.
+pub fn set_text(_ctx: &UI, text: &str) -> Result<(), UIError> {
+    unsafe { native::set_text(text) }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod native {
+    use error::UIError;
+    use ffi_tools;
+    use libc::{c_char, c_int, c_void};
+
+    #[link(name = "gtk-3")]
+    #[link(name = "gdk-3")]
+    #[link(name = "glib-2.0")]
+    extern "C" {
+        fn gdk_atom_intern(name: *const c_char, only_if_exists: c_int) -> *mut c_void;
+        fn gtk_clipboard_get(selection: *mut c_void) -> *mut c_void;
+        fn gtk_clipboard_set_text(clipboard: *mut c_void, text: *const c_char, len: c_int);
+        fn gtk_clipboard_wait_for_text(clipboard: *mut c_void) -> *mut c_char;
+        fn g_free(mem: *mut c_void);
+    }
+
+    unsafe fn clipboard() -> *mut c_void {
+        gtk_clipboard_get(gdk_atom_intern(b"CLIPBOARD\0".as_ptr() as *const c_char, 0))
+    }
+
+    pub unsafe fn text() -> Option<String> {
+        let text = gtk_clipboard_wait_for_text(clipboard());
+        if text.is_null() {
+            return None;
+        }
+        let copy = ::std::ffi::CStr::from_ptr(text).to_string_lossy().into_owned();
+        g_free(text as *mut c_void);
+        Some(copy)
+    }
+
+    pub unsafe fn set_text(text: &str) -> Result<(), UIError> {
+        let c_string = ffi_tools::to_c_string(text);
+        gtk_clipboard_set_text(clipboard(), c_string.as_ptr(), -1);
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod native {
+    use error::UIError;
+    use libc::{c_int, c_void};
+    use std::cell::Cell;
+    use std::ptr;
+
+    const CF_UNICODETEXT: u32 = 13;
+    const GMEM_MOVEABLE: u32 = 0x0002;
+    const HWND_MESSAGE: isize = -3;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn CreateWindowExW(
+            ex_style: u32,
+            class_name: *const u16,
+            window_name: *const u16,
+            style: u32,
+            x: c_int,
+            y: c_int,
+            width: c_int,
+            height: c_int,
+            parent: *mut c_void,
+            menu: *mut c_void,
+            instance: *mut c_void,
+            param: *mut c_void,
+        ) -> *mut c_void;
+        fn OpenClipboard(owner: *mut c_void) -> c_int;
+        fn CloseClipboard() -> c_int;
+        fn EmptyClipboard() -> c_int;
+        fn GetClipboardData(format: u32) -> *mut c_void;
+        fn SetClipboardData(format: u32, data: *mut c_void) -> *mut c_void;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GlobalAlloc(flags: u32, bytes: usize) -> *mut c_void;
+        fn GlobalLock(mem: *mut c_void) -> *mut c_void;
+        fn GlobalUnlock(mem: *mut c_void) -> c_int;
+        fn GlobalFree(mem: *mut c_void) -> *mut c_void;
+    }
+
+    thread_local! {
+        // The window the clipboard is opened with. `EmptyClipboard` makes that window the
+        // owner of the clipboard, and `SetClipboardData` fails if there is none, so the
+        // clipboard can't be opened without a window.
+        static OWNER: Cell<*mut c_void> = Cell::new(ptr::null_mut())
+    }
+
+    // Opens the clipboard with a message-only window, which is created the first time and
+    // destroyed by Windows when the thread exits.
+    unsafe fn open() -> bool {
+        let owner = OWNER.with(|owner| {
+            if owner.get().is_null() {
+                let class: Vec<u16> = "STATIC".encode_utf16().chain(Some(0)).collect();
+                owner.set(CreateWindowExW(
+                    0,
+                    class.as_ptr(),
+                    ptr::null(),
+                    0,
+                    0,
+                    0,
+                    0,
+                    0,
+                    HWND_MESSAGE as *mut c_void,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                ));
+            }
+            owner.get()
+        });
+        !owner.is_null() && OpenClipboard(owner) != 0
+    }
+
+    pub unsafe fn text() -> Option<String> {
+        if !open() {
+            return None;
+        }
+        let data = GetClipboardData(CF_UNICODETEXT);
+        let text = if data.is_null() {
+            None
+        } else {
+            let wide = GlobalLock(data) as *const u16;
+            if wide.is_null() {
+                None
+            } else {
+                let mut len = 0;
+                while *wide.add(len) != 0 {
+                    len += 1;
+                }
+                let text = String::from_utf16_lossy(::std::slice::from_raw_parts(wide, len));
+                GlobalUnlock(data);
+                Some(text)
+            }
+        };
+        CloseClipboard();
+        text
+    }
+
+    pub unsafe fn set_text(text: &str) -> Result<(), UIError> {
+        // Cut short at the first NUL, like all text given to this crate.
+        let text = text.split('\0').next().unwrap_or("");
+        let wide: Vec<u16> = text.encode_utf16().chain(Some(0)).collect();
+        if !open() {
+            return Err(UIError::ClipboardUnavailable());
+        }
+        EmptyClipboard();
+        let data = GlobalAlloc(GMEM_MOVEABLE, wide.len() * 2);
+        let mut result = Err(UIError::ClipboardUnavailable());
+        if !data.is_null() {
+            let dest = GlobalLock(data) as *mut u16;
+            if !dest.is_null() {
+                ptr::copy_nonoverlapping(wide.as_ptr(), dest, wide.len());
+                GlobalUnlock(data);
+                // The clipboard owns the memory from now on, unless this fails.
+                if !SetClipboardData(CF_UNICODETEXT, data).is_null() {
+                    result = Ok(());
+                }
+            }
+            if result.is_err() {
+                GlobalFree(data);
+            }
+        }
+        CloseClipboard();
+        result
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod native {
+    use error::UIError;
+    use ffi_tools;
+    use libc::{c_char, c_void};
+    use std::ffi::CStr;
+    use std::mem;
+
+    type Id = *mut c_void;
+    type Sel = *mut c_void;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Sel;
+        fn objc_msgSend();
+    }
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSPasteboardTypeString: Id;
+    }
+
+    unsafe fn class(name: &[u8]) -> Id {
+        objc_getClass(name.as_ptr() as *const c_char)
+    }
+
+    unsafe fn sel(name: &[u8]) -> Sel {
+        sel_registerName(name.as_ptr() as *const c_char)
+    }
+
+    // `objc_msgSend` has to be called through a pointer of the method's own type.
+    unsafe fn send(receiver: Id, selector: &[u8]) -> Id {
+        let send: extern "C" fn(Id, Sel) -> Id = mem::transmute(objc_msgSend as *const c_void);
+        send(receiver, sel(selector))
+    }
+
+    unsafe fn send1(receiver: Id, selector: &[u8], arg: *const c_void) -> Id {
+        let send: extern "C" fn(Id, Sel, *const c_void) -> Id =
+            mem::transmute(objc_msgSend as *const c_void);
+        send(receiver, sel(selector), arg)
+    }
+
+    unsafe fn pasteboard() -> Id {
+        send(class(b"NSPasteboard\0"), b"generalPasteboard\0")
+    }
+
+    pub unsafe fn text() -> Option<String> {
+        let string = send1(pasteboard(), b"stringForType:\0", NSPasteboardTypeString);
+        if string.is_null() {
+            return None;
+        }
+        let utf8 = send(string, b"UTF8String\0") as *const c_char;
+        if utf8.is_null() {
+            return None;
+        }
+        Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+
+    pub unsafe fn set_text(text: &str) -> Result<(), UIError> {
+        let c_string = ffi_tools::to_c_string(text);
+        let string = send1(
+            class(b"NSString\0"),
+            b"stringWithUTF8String:\0",
+            c_string.as_ptr() as *const c_void,
+        );
+        let pasteboard = pasteboard();
+        send(pasteboard, b"clearContents\0");
+        let set: extern "C" fn(Id, Sel, Id, Id) -> i8 = mem::transmute(objc_msgSend as *const c_void);
+        if set(pasteboard, sel(b"setString:forType:\0"), string, NSPasteboardTypeString) != 0 {
+            Ok(())
+        } else {
+            Err(UIError::ClipboardUnavailable())
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod native {
+    use error::UIError;
+
+    pub unsafe fn text() -> Option<String> {
+        None
+    }
+
+    pub unsafe fn set_text(_text: &str) -> Result<(), UIError> {
+        Err(UIError::Unsupported {
+            operation: "using the clipboard on this platform",
+        })
+    }
+}
@@ -0,0 +1,202 @@
+//! Naming controls for screen readers through the native toolkit, as `libui` has no
+//! accessibility functions.
+
+#[cfg(unix)]
+use ffi_tools;
+
+/// The accessible properties which can be set on a control.
+#[derive(Clone, Copy)]
+pub(crate) enum Property {
+    Name,
+    Description,
+}
+
+/// Sets `property` of the native control `handle`, as returned by `uiControlHandle`.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) unsafe fn set_property(handle: usize, property: Property, value: &str) {
+    let value = ffi_tools::to_c_string(value);
+    let object = atk::gtk_widget_get_accessible(handle as *mut _);
+    if object.is_null() {
+        return;
+    }
+    match property {
+        Property::Name => atk::atk_object_set_name(object, value.as_ptr()),
+        Property::Description => atk::atk_object_set_description(object, value.as_ptr()),
+    }
+}
+
+// The properties are annotations on the control's window, served by Windows on its behalf.
+#[cfg(windows)]
+pub(crate) unsafe fn set_property(handle: usize, property: Property, value: &str) {
+    use std::ptr;
+
+    // Cut short at the first NUL, like all text given to this crate.
+    let value = value.split('\0').next().unwrap_or("");
+    let wide: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+    let services = ACC_PROP_SERVICES.with(|services| {
+        if services.get().is_null() {
+            let mut created = ptr::null_mut();
+            let result = win32::CoCreateInstance(
+                &win32::CLSID_ACC_PROP_SERVICES,
+                ptr::null_mut(),
+                win32::CLSCTX_INPROC_SERVER,
+                &win32::IID_IACC_PROP_SERVICES,
+                &mut created,
+            );
+            if result >= 0 {
+                services.set(created as *mut win32::IAccPropServices);
+            }
+        }
+        services.get()
+    });
+    if services.is_null() {
+        return;
+    }
+    let id = match property {
+        Property::Name => &win32::PROPID_ACC_NAME,
+        Property::Description => &win32::PROPID_ACC_DESCRIPTION,
+    };
+    ((*(*services).vtable).SetHwndPropStr)(
+        services,
+        handle as *mut _,
+        win32::OBJID_CLIENT,
+        win32::CHILDID_SELF,
+        id,
+        wide.as_ptr(),
+    );
+}
+
+#[cfg(windows)]
+thread_local! {
+    // Kept for as long as the thread runs, as the properties are set through it.
+    static ACC_PROP_SERVICES: ::std::cell::Cell<*mut win32::IAccPropServices> =
+        ::std::cell::Cell::new(::std::ptr::null_mut())
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn set_property(handle: usize, property: Property, value: &str) {
+    use libc::{c_char, c_void};
+    use std::mem;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    // `objc_msgSend` has to be called through a pointer of the method's own type.
+    let send: extern "C" fn(*mut c_void, *mut c_void, *const c_void) -> *mut c_void =
+        mem::transmute(objc_msgSend as *const c_void);
+    let value = ffi_tools::to_c_string(value);
+    let string = send(
+        objc_getClass(b"NSString\0".as_ptr() as *const c_char),
+        sel_registerName(b"stringWithUTF8String:\0".as_ptr() as *const c_char),
+        value.as_ptr() as *const c_void,
+    );
+    // VoiceOver reads the label of a view as its name, and its help as its description.
+    let selector: &[u8] = match property {
+        Property::Name => b"setAccessibilityLabel:\0",
+        Property::Description => b"setAccessibilityHelp:\0",
+    };
+    send(
+        handle as *mut c_void,
+        sel_registerName(selector.as_ptr() as *const c_char),
+        string,
+    );
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) unsafe fn set_property(_handle: usize, _property: Property, _value: &str) {}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod atk {
+    use libc::{c_char, c_void};
+
+    #[link(name = "gtk-3")]
+    #[link(name = "atk-1.0")]
+    extern "C" {
+        pub fn gtk_widget_get_accessible(widget: *mut c_void) -> *mut c_void;
+        pub fn atk_object_set_name(object: *mut c_void, name: *const c_char);
+        pub fn atk_object_set_description(object: *mut c_void, description: *const c_char);
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod win32 {
+    use libc::{c_long, c_void};
+
+    #[repr(C)]
+    pub struct GUID {
+        pub data1: u32,
+        pub data2: u16,
+        pub data3: u16,
+        pub data4: [u8; 8],
+    }
+
+    pub const CLSID_ACC_PROP_SERVICES: GUID = GUID {
+        data1: 0xb5f8_350b,
+        data2: 0x0548,
+        data3: 0x48b1,
+        data4: [0xa6, 0xee, 0x88, 0xbd, 0x00, 0xb4, 0xa5, 0xe7],
+    };
+    pub const IID_IACC_PROP_SERVICES: GUID = GUID {
+        data1: 0x6e26_e776,
+        data2: 0x04f0,
+        data3: 0x495d,
+        data4: [0x80, 0xe4, 0x33, 0x30, 0x35, 0x2e, 0x31, 0x69],
+    };
+    pub const PROPID_ACC_NAME: GUID = GUID {
+        data1: 0x608d_3df8,
+        data2: 0x8128,
+        data3: 0x4aa7,
+        data4: [0xa4, 0x28, 0xf5, 0x5e, 0x49, 0x26, 0x72, 0x91],
+    };
+    pub const PROPID_ACC_DESCRIPTION: GUID = GUID {
+        data1: 0x4d48_dfe4,
+        data2: 0xbd3f,
+        data3: 0x491f,
+        data4: [0xa6, 0x48, 0x49, 0x2d, 0x6f, 0x20, 0xc5, 0x88],
+    };
+
+    pub const CLSCTX_INPROC_SERVER: u32 = 0x1;
+    pub const OBJID_CLIENT: u32 = 0xFFFF_FFFC;
+    pub const CHILDID_SELF: u32 = 0;
+
+    #[repr(C)]
+    pub struct IAccPropServices {
+        pub vtable: *const IAccPropServicesVtbl,
+    }
+
+    // Only the methods up to the one used are spelled out; the rest of the table is unused.
+    #[repr(C)]
+    pub struct IAccPropServicesVtbl {
+        pub QueryInterface: *const c_void,
+        pub AddRef: *const c_void,
+        pub Release: *const c_void,
+        pub SetPropValue: *const c_void,
+        pub SetPropServer: *const c_void,
+        pub ClearProps: *const c_void,
+        pub SetHwndProp: *const c_void,
+        pub SetHwndPropStr: unsafe extern "system" fn(
+            this: *mut IAccPropServices,
+            hwnd: *mut c_void,
+            id_object: u32,
+            id_child: u32,
+            id_prop: *const GUID,
+            value: *const u16,
+        ) -> c_long,
+    }
+
+    #[link(name = "ole32")]
+    extern "system" {
+        pub fn CoCreateInstance(
+            clsid: *const GUID,
+            outer: *mut c_void,
+            context: u32,
+            iid: *const GUID,
+            object: *mut *mut c_void,
+        ) -> c_long;
+    }
+}
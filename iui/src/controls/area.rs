@@ -1,33 +1,72 @@
 //! Provides a way to allocate an area in the window for custom drawing.
 
-use controls::Control;
+use super::{forget_control, Control};
 use draw;
+use ffi_tools;
 use libc::c_int;
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::mem;
 use ui::UI;
-pub use ui_sys::uiExtKey as ExtKey;
 use ui_sys::{
     self, uiArea, uiAreaDrawParams, uiAreaHandler, uiAreaKeyEvent, uiAreaMouseEvent, uiControl,
+    uiExtKey,
 };
 
+thread_local! {
+    // The areas created with `Area::new_scrolling`, as `libui` cannot tell.
+    static SCROLLING_AREAS: RefCell<HashSet<*mut uiArea>> = RefCell::new(HashSet::new());
+}
+
+/// Forgets whether `control` was a scrolling area, as it is about to be destroyed or was just
+/// created.
+pub(crate) fn forget_area_state(control: *mut uiControl) {
+    SCROLLING_AREAS.with(|areas| areas.borrow_mut().remove(&(control as *mut uiArea)));
+}
+
+/// Forgets which areas scroll. Called when the UI is torn down.
+pub(crate) fn clear_area_state() {
+    SCROLLING_AREAS.with(|areas| areas.borrow_mut().clear());
+}
+
+/// The behaviour of an [`Area`](struct.Area.html): how it is drawn, and how it responds to
+/// input. Every method has a default implementation doing nothing, so implementors only need
+/// to provide the ones they are interested in.
+///
+/// The handler is called on the GUI thread, from the event loop, and is kept alive until the
+/// `UI` is dropped.
 pub trait AreaHandler {
+    /// Called when part of the area needs to be painted, e.g. when it is first shown or after
+    /// [`queue_redraw_all`](struct.Area.html#method.queue_redraw_all). Everything should be
+    /// drawn again on each call: what was drawn before is not kept.
     fn draw(&mut self, _area: &Area, _area_draw_params: &AreaDrawParams) {}
+    /// Called when the mouse moves over the area, or a mouse button is pressed or released.
+    /// While a button is held, events keep coming even when the mouse leaves the area, so that
+    /// drags can be followed to their end.
     fn mouse_event(&mut self, _area: &Area, _area_mouse_event: &AreaMouseEvent) {}
-    fn mouse_crossed(&mut self, _area: &Area, _left: bool) {}
+    /// Called when the mouse enters or leaves the area.
+    fn mouse_crossed(&mut self, _area: &Area, _crossing: Crossing) {}
+    /// Called when a drag is interrupted by the system, e.g. when a window is brought up on
+    /// top of the area; no event for the release of the button will follow.
+    ///
+    /// Not called on every platform: on some, the release is always reported instead.
     fn drag_broken(&mut self, _area: &Area) {}
+    /// Called when a key is pressed or released while the area has focus. Returns whether the
+    /// event was handled; unhandled events are passed on to the window, e.g. for menu
+    /// shortcuts, so this should only return `true` for the keys the area makes use of.
     fn key_event(&mut self, _area: &Area, _area_key_event: &AreaKeyEvent) -> bool {
-        true
+        false
     }
 }
 
 #[repr(C)]
 struct RustAreaHandler {
     ui_area_handler: uiAreaHandler,
-    trait_object: Box<AreaHandler>,
+    trait_object: Box<dyn AreaHandler>,
 }
 
 impl RustAreaHandler {
-    fn new(_ctx: &UI, trait_object: Box<AreaHandler>) -> Box<RustAreaHandler> {
+    fn new(_ctx: &UI, trait_object: Box<dyn AreaHandler>) -> Box<RustAreaHandler> {
         return Box::new(RustAreaHandler {
             ui_area_handler: uiAreaHandler {
                 Draw: draw,
@@ -36,7 +75,7 @@ impl RustAreaHandler {
                 DragBroken: drag_broken,
                 KeyEvent: key_event,
             },
-            trait_object: trait_object,
+            trait_object,
         });
 
         extern "C" fn draw(
@@ -51,7 +90,7 @@ impl RustAreaHandler {
                 (*(ui_area_handler as *mut RustAreaHandler))
                     .trait_object
                     .draw(&area, &area_draw_params);
-                mem::forget(area_draw_params);
+                area_draw_params.context.restore_all();
                 mem::forget(area);
             }
         }
@@ -68,7 +107,6 @@ impl RustAreaHandler {
                 (*(ui_area_handler as *mut RustAreaHandler))
                     .trait_object
                     .mouse_event(&area, &area_mouse_event);
-                mem::forget(area_mouse_event);
                 mem::forget(area);
             }
         }
@@ -82,7 +120,7 @@ impl RustAreaHandler {
                 let area = Area::from_ui_area(ui_area);
                 (*(ui_area_handler as *mut RustAreaHandler))
                     .trait_object
-                    .mouse_crossed(&area, left != 0);
+                    .mouse_crossed(&area, Crossing::from_left(left));
                 mem::forget(area);
             }
         }
@@ -108,7 +146,6 @@ impl RustAreaHandler {
                 let result = (*(ui_area_handler as *mut RustAreaHandler))
                     .trait_object
                     .key_event(&area, &area_key_event);
-                mem::forget(area_key_event);
                 mem::forget(area);
                 result as c_int
             }
@@ -141,54 +178,124 @@ define_control!{
     ///
     /// The internal coordinate system of an Area is points, which are floating-point and
     /// device-independent. For more details, see `AreaHandler`. The size of a scrolling
-    /// Area must be an exact integer number of points
+    /// Area must be an exact integer number of points.
+    ///
+    /// `libui` only draws areas to the screen, through the platform: it has no offscreen
+    /// drawing context, and no way to read back what was drawn, so the content of an area
+    /// can't be captured as pixels. Exporting a drawing as an image therefore needs a separate
+    /// renderer, and tests of drawing code are best done on what the handler asks to draw.
+    ///
+    /// ```no_run
+    /// use iui::prelude::*;
+    /// use iui::controls::{Area, AreaDrawParams, AreaHandler};
+    /// use iui::draw::{Brush, FillMode, Path};
+    /// use iui::Color;
+    ///
+    /// struct Canvas {
+    ///     ui: UI,
+    /// }
+    ///
+    /// impl AreaHandler for Canvas {
+    ///     fn draw(&mut self, _area: &Area, params: &AreaDrawParams) {
+    ///         let path = Path::new(&self.ui, FillMode::Winding);
+    ///         path.add_rectangle(&self.ui, 0.0, 0.0, params.area_width, params.area_height);
+    ///         path.end(&self.ui);
+    ///         let brush = Brush::solid(Color::new(0.2, 0.4, 0.8, 1.0));
+    ///         params.context.fill(&self.ui, &path, &brush);
+    ///     }
+    /// }
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Canvas", 300, 200, WindowType::NoMenubar);
+    /// let area = Area::new(&ui, Box::new(Canvas { ui: ui.clone() }));
+    /// window.set_child(&ui, area);
+    /// window.show(&ui);
+    /// ui.main();
+    /// ```
     rust_type: Area,
     sys_type: uiArea
 }
 
 impl Area {
-    /// Creates a new non-scrolling area.
-    pub fn new(ctx: &UI, area_handler: Box<AreaHandler>) -> Area {
+    /// Creates a new non-scrolling area, drawn and handling input through `area_handler`.
+    pub fn new(ctx: &UI, area_handler: Box<dyn AreaHandler>) -> Area {
+        let mut rust_area_handler = RustAreaHandler::new(ctx, area_handler);
         unsafe {
-            let mut rust_area_handler = RustAreaHandler::new(ctx, area_handler);
             let area = Area::from_raw(ui_sys::uiNewArea(
                 &mut *rust_area_handler as *mut RustAreaHandler as *mut uiAreaHandler,
             ));
-            mem::forget(rust_area_handler);
+            area.keep_handler(rust_area_handler);
             area
         }
     }
 
-    /// Creates a new scrolling area.
+    /// Creates a new scrolling area of the given size in points, drawn and handling input
+    /// through `area_handler`. Only the part of the area shown on screen has to be drawn; it
+    /// is given to the handler as the clipping rectangle of the
+    /// [`AreaDrawParams`](struct.AreaDrawParams.html).
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Area, AreaHandler};
+    /// # struct Timeline;
+    /// # impl AreaHandler for Timeline {}
+    /// # let ui = UI::init().unwrap();
+    /// // A timeline 10000 points long, scrolled to show its end.
+    /// let timeline = Area::new_scrolling(&ui, Box::new(Timeline), 10000, 100);
+    /// timeline.scroll_to(&ui, 9000.0, 0.0, 1000.0, 100.0);
+    ///
+    /// // Later, the timeline gets longer.
+    /// timeline.set_size(&ui, 20000, 100);
+    /// ```
     pub fn new_scrolling(
         ctx: &UI,
-        area_handler: Box<AreaHandler>,
+        area_handler: Box<dyn AreaHandler>,
         width: i64,
         height: i64,
     ) -> Area {
+        let mut rust_area_handler = RustAreaHandler::new(ctx, area_handler);
         unsafe {
-            let mut rust_area_handler = RustAreaHandler::new(ctx, area_handler);
             let area = Area::from_raw(ui_sys::uiNewScrollingArea(
                 &mut *rust_area_handler as *mut RustAreaHandler as *mut uiAreaHandler,
-                width,
-                height,
+                ffi_tools::to_c_int(width),
+                ffi_tools::to_c_int(height),
             ));
-            mem::forget(rust_area_handler);
+            area.keep_handler(rust_area_handler);
+            SCROLLING_AREAS.with(|areas| areas.borrow_mut().insert(area.uiArea));
             area
         }
     }
 
+    // Hand the handler over to the callback registry, which keeps it alive for as long as libUI
+    // may call it. Moving the box does not move the handler libUI points to.
+    fn keep_handler(&self, rust_area_handler: Box<RustAreaHandler>) {
+        forget_control(self.uiArea as *mut uiControl);
+        ffi_tools::register_callback(self.uiArea, "handler", rust_area_handler);
+    }
+
+    /// Creates an `Area` from the raw pointer for it.
+    ///
+    /// # Safety
+    /// The given pointer must point to a valid area or memory unsafety may result.
     pub unsafe fn from_ui_area(ui_area: *mut uiArea) -> Area {
         Area { uiArea: ui_area }
     }
 
-    /// Sets the size of the area in points.
+    /// Returns true if this area was created with [`new_scrolling`](#method.new_scrolling).
+    pub fn scrolling(&self, _ctx: &UI) -> bool {
+        SCROLLING_AREAS.with(|areas| areas.borrow().contains(&self.uiArea))
+    }
+
+    /// Sets the size of a scrolling area in points, i.e. how far it can be scrolled.
     ///
-    /// # Unsafety
-    /// If called on a non-scrolling `Area`, this function's behavior is undefined.
-    pub unsafe fn set_size(&self, _ctx: &UI, width: u64, height: u64) {
-        // TODO: Check if the area is scrolling?
-        unsafe { ui_sys::uiAreaSetSize(self.uiArea, width as i64, height as i64) }
+    /// # Panics
+    /// Panics if the area is not a scrolling one, as non-scrolling areas are sized by their
+    /// container.
+    pub fn set_size(&self, ctx: &UI, width: u64, height: u64) {
+        assert!(self.scrolling(ctx), "set_size called on a non-scrolling Area");
+        let width = ffi_tools::to_c_int(width.min(i64::MAX as u64) as i64);
+        let height = ffi_tools::to_c_int(height.min(i64::MAX as u64) as i64);
+        unsafe { ui_sys::uiAreaSetSize(self.uiArea, width, height) }
     }
 
     /// Queues the entire `Area` to be redrawn. This function returns immediately;
@@ -197,14 +304,14 @@ impl Area {
         unsafe { ui_sys::uiAreaQueueRedrawAll(self.uiArea) }
     }
 
-    /// Scrolls the Area to show the given rectangle. This behavior is somewhat
+    /// Scrolls a scrolling area to show the given rectangle. This behavior is somewhat
     /// implementation defined, but you can assume that as much of the given rectangle
     /// as possible will be visible after this call.
     ///
-    /// # Unsafety
-    /// If called on a non-scrolling `Area`, this function's behavior is undefined.
-    pub unsafe fn scroll_to(&self, _ctx: &UI, x: f64, y: f64, width: f64, height: f64) {
-        // TODO: Make some way to check whether the given area is scrolling or not.
+    /// # Panics
+    /// Panics if the area is not a scrolling one.
+    pub fn scroll_to(&self, ctx: &UI, x: f64, y: f64, width: f64, height: f64) {
+        assert!(self.scrolling(ctx), "scroll_to called on a non-scrolling Area");
         unsafe { ui_sys::uiAreaScrollTo(self.uiArea, x, y, width, height) }
     }
 }
@@ -218,13 +325,37 @@ impl Area {
 /// The clipping rectangle parameters specify the only area in which drawing is allowed.
 /// The system will ensure nothing is drawn outside that area, but drawing is far faster
 /// if the program does not attempt to put things out of bounds.
+///
+/// For a non-scrolling area, the clipping rectangle is the whole area. For a scrolling area,
+/// it is the part of the content which is on screen, in the coordinates of the whole content,
+/// so the parts of a large document or timeline which are scrolled away can be skipped:
+///
+/// ```
+/// # use iui::prelude::*;
+/// # use iui::controls::AreaDrawParams;
+/// # use iui::draw::{Brush, FillMode, Path};
+/// # use iui::Color;
+/// # fn draw(ui: &UI, params: &AreaDrawParams) {
+/// // One marker every 10 points, of which only those in the clipping rectangle are drawn.
+/// let first = (params.clip_x / 10.0).floor() as i64;
+/// let last = ((params.clip_x + params.clip_width) / 10.0).ceil() as i64;
+/// let path = Path::new(ui, FillMode::Winding);
+/// for i in first..last {
+///     path.add_rectangle(ui, i as f64 * 10.0, 0.0, 1.0, 10.0);
+/// }
+/// path.end(ui);
+/// params.context.fill(ui, &path, &Brush::solid(Color::new(0.0, 0.0, 0.0, 1.0)));
+/// # }
+/// ```
 pub struct AreaDrawParams {
     /// The `DrawContext` on which to draw. See `DrawContext` for how to draw.
     pub context: draw::DrawContext,
 
-    /// The width of the `Area`, for non-scrolling `Area`s.
+    /// The width of the `Area`, for non-scrolling `Area`s. It is not meaningful for scrolling
+    /// ones, whose size is the one given to them.
     pub area_width: f64,
-    /// The height of the `Area`, for non-scrolling `Area`s.
+    /// The height of the `Area`, for non-scrolling `Area`s. It is not meaningful for scrolling
+    /// ones, whose size is the one given to them.
     pub area_height: f64,
 
     /// Leftmost position of the clipping rectangle.
@@ -253,34 +384,73 @@ impl AreaDrawParams {
 }
 
 bitflags! {
-    pub flags Modifiers: u8 {
-        const MODIFIER_CTRL = 1 << 0,
-        const MODIFIER_ALT = 1 << 1,
-        const MODIFIER_SHIFT = 1 << 2,
-        const MODIFIER_SUPER = 1 << 3,
+    /// The modifier keys held during an `Area` input event.
+    pub struct Modifiers: u8 {
+        const CTRL = 1 << 0;
+        const ALT = 1 << 1;
+        const SHIFT = 1 << 2;
+        const SUPER = 1 << 3;
+    }
+}
+
+/// Which way the mouse crossed the edge of an `Area`, as given to
+/// [`AreaHandler::mouse_crossed`](trait.AreaHandler.html#method.mouse_crossed).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Crossing {
+    /// The mouse moved into the area.
+    Entered,
+    /// The mouse moved out of the area.
+    Left,
+}
+
+impl Crossing {
+    /// Converts the `left` flag libUI gives, which is nonzero when the mouse left the area.
+    fn from_left(left: c_int) -> Crossing {
+        if left == 0 {
+            Crossing::Entered
+        } else {
+            Crossing::Left
+        }
     }
 }
 
 #[derive(Copy, Clone, Debug)]
 /// Represents a mouse event in an `Area`.
+///
+/// Positions are in points, relative to the top-left corner of the area; for scrolling areas,
+/// they are relative to the whole scrollable content rather than to its visible part, like
+/// drawing coordinates.
 pub struct AreaMouseEvent {
+    /// Horizontal position of the mouse.
     pub x: f64,
+    /// Vertical position of the mouse.
     pub y: f64,
 
+    /// The width of the `Area`, for non-scrolling `Area`s.
     pub area_width: f64,
+    /// The height of the `Area`, for non-scrolling `Area`s.
     pub area_height: f64,
 
+    /// The button which was just pressed, numbered from 1 (the left button), or 0 if no button
+    /// was pressed by this event.
     pub down: i32,
+    /// The button which was just released, or 0 if no button was released by this event.
     pub up: i32,
 
+    /// For a press, how many times in a row the button was clicked: 2 for a double click, and
+    /// so on.
     pub count: i32,
 
+    /// The modifier keys held during the event.
     pub modifiers: Modifiers,
 
+    /// The buttons held during the event, as a bitmask: the lowest bit is set while button 1
+    /// is held, and so on up to button 64.
     pub held_1_to_64: u64,
 }
 
 impl AreaMouseEvent {
+    /// Creates an `AreaMouseEvent` from the event given by libUI.
     pub fn from_ui_area_mouse_event(ui_area_mouse_event: &uiAreaMouseEvent) -> AreaMouseEvent {
         AreaMouseEvent {
             x: ui_area_mouse_event.X,
@@ -290,32 +460,161 @@ impl AreaMouseEvent {
             down: ui_area_mouse_event.Down,
             up: ui_area_mouse_event.Up,
             count: ui_area_mouse_event.Count,
-            modifiers: Modifiers::from_bits(ui_area_mouse_event.Modifiers as u8)
-                .unwrap_or(Modifiers::empty()),
+            modifiers: Modifiers::from_bits_truncate(ui_area_mouse_event.Modifiers as u8),
             held_1_to_64: ui_area_mouse_event.Held1To64,
         }
     }
+
+    /// Returns the position of the mouse in the coordinates of a scene drawn with `transform`,
+    /// i.e. the point which `transform` puts under the mouse. This is `None` if the transform
+    /// can't be inverted.
+    ///
+    /// ```
+    /// # use iui::controls::AreaMouseEvent;
+    /// # use iui::draw::Transform;
+    /// # fn on_mouse(event: &AreaMouseEvent) {
+    /// // The scene is drawn zoomed in twice, and panned by (50, 0).
+    /// let mut view = Transform::identity();
+    /// view.scale(0.0, 0.0, 2.0, 2.0);
+    /// view.translate(50.0, 0.0);
+    ///
+    /// if let Some((x, y)) = event.position_in(&view) {
+    ///     println!("clicked at ({}, {}) in the scene", x, y);
+    /// }
+    /// # }
+    /// ```
+    pub fn position_in(&self, transform: &draw::Transform) -> Option<(f64, f64)> {
+        transform
+            .inverse()
+            .map(|inverse| inverse.transform_point((self.x, self.y)))
+    }
+}
+
+/// A key which doesn't produce a character, as reported in an `AreaKeyEvent`.
+///
+/// Numpad keys (`N0` to `NDivide`) are reported as such whatever the state of Num Lock.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ExtKey {
+    Escape,
+    /// Equivalent to "Help" on Apple keyboards.
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    N0,
+    N1,
+    N2,
+    N3,
+    N4,
+    N5,
+    N6,
+    N7,
+    N8,
+    N9,
+    NDot,
+    NEnter,
+    NAdd,
+    NSubtract,
+    NMultiply,
+    NDivide,
+}
+
+impl ExtKey {
+    /// Converts the key reported by libUI, returning `None` for 0 (a character key) and for
+    /// values this version does not know about.
+    pub fn from_ui_ext_key(ext_key: uiExtKey) -> Option<ExtKey> {
+        match ext_key {
+            ui_sys::uiExtKeyEscape => Some(ExtKey::Escape),
+            ui_sys::uiExtKeyInsert => Some(ExtKey::Insert),
+            ui_sys::uiExtKeyDelete => Some(ExtKey::Delete),
+            ui_sys::uiExtKeyHome => Some(ExtKey::Home),
+            ui_sys::uiExtKeyEnd => Some(ExtKey::End),
+            ui_sys::uiExtKeyPageUp => Some(ExtKey::PageUp),
+            ui_sys::uiExtKeyPageDown => Some(ExtKey::PageDown),
+            ui_sys::uiExtKeyUp => Some(ExtKey::Up),
+            ui_sys::uiExtKeyDown => Some(ExtKey::Down),
+            ui_sys::uiExtKeyLeft => Some(ExtKey::Left),
+            ui_sys::uiExtKeyRight => Some(ExtKey::Right),
+            ui_sys::uiExtKeyF1 => Some(ExtKey::F1),
+            ui_sys::uiExtKeyF2 => Some(ExtKey::F2),
+            ui_sys::uiExtKeyF3 => Some(ExtKey::F3),
+            ui_sys::uiExtKeyF4 => Some(ExtKey::F4),
+            ui_sys::uiExtKeyF5 => Some(ExtKey::F5),
+            ui_sys::uiExtKeyF6 => Some(ExtKey::F6),
+            ui_sys::uiExtKeyF7 => Some(ExtKey::F7),
+            ui_sys::uiExtKeyF8 => Some(ExtKey::F8),
+            ui_sys::uiExtKeyF9 => Some(ExtKey::F9),
+            ui_sys::uiExtKeyF10 => Some(ExtKey::F10),
+            ui_sys::uiExtKeyF11 => Some(ExtKey::F11),
+            ui_sys::uiExtKeyF12 => Some(ExtKey::F12),
+            ui_sys::uiExtKeyN0 => Some(ExtKey::N0),
+            ui_sys::uiExtKeyN1 => Some(ExtKey::N1),
+            ui_sys::uiExtKeyN2 => Some(ExtKey::N2),
+            ui_sys::uiExtKeyN3 => Some(ExtKey::N3),
+            ui_sys::uiExtKeyN4 => Some(ExtKey::N4),
+            ui_sys::uiExtKeyN5 => Some(ExtKey::N5),
+            ui_sys::uiExtKeyN6 => Some(ExtKey::N6),
+            ui_sys::uiExtKeyN7 => Some(ExtKey::N7),
+            ui_sys::uiExtKeyN8 => Some(ExtKey::N8),
+            ui_sys::uiExtKeyN9 => Some(ExtKey::N9),
+            ui_sys::uiExtKeyNDot => Some(ExtKey::NDot),
+            ui_sys::uiExtKeyNEnter => Some(ExtKey::NEnter),
+            ui_sys::uiExtKeyNAdd => Some(ExtKey::NAdd),
+            ui_sys::uiExtKeyNSubtract => Some(ExtKey::NSubtract),
+            ui_sys::uiExtKeyNMultiply => Some(ExtKey::NMultiply),
+            ui_sys::uiExtKeyNDivide => Some(ExtKey::NDivide),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
 /// A keypress or key release event for an `Area`.
+///
+/// Exactly one of `key`, `ext_key` and `modifier` describes the key of the event.
 pub struct AreaKeyEvent {
-    pub key: u8,
-    pub ext_key: ExtKey,
+    /// The character of the key, for keys which produce one. This is the character of the key
+    /// itself, unaffected by the modifiers: `'a'` even with Shift held.
+    pub key: Option<char>,
+    /// The key, for keys which don't produce a character.
+    pub ext_key: Option<ExtKey>,
+    /// The key, when it is a modifier key.
     pub modifier: Modifiers,
+    /// The modifier keys held during the event.
     pub modifiers: Modifiers,
+    /// `true` if the key was released, `false` if it was pressed.
     pub up: bool,
 }
 
 impl AreaKeyEvent {
+    /// Creates an `AreaKeyEvent` from the event given by libUI.
     pub fn from_ui_area_key_event(ui_area_key_event: &uiAreaKeyEvent) -> AreaKeyEvent {
         AreaKeyEvent {
-            key: ui_area_key_event.Key as u8,
-            ext_key: ui_area_key_event.ExtKey,
-            modifier: Modifiers::from_bits(ui_area_key_event.Modifier as u8)
-                .unwrap_or(Modifiers::empty()),
-            modifiers: Modifiers::from_bits(ui_area_key_event.Modifiers as u8)
-                .unwrap_or(Modifiers::empty()),
+            key: match ui_area_key_event.Key as u8 {
+                0 => None,
+                key => Some(key as char),
+            },
+            ext_key: ExtKey::from_ui_ext_key(ui_area_key_event.ExtKey),
+            modifier: Modifiers::from_bits_truncate(ui_area_key_event.Modifier as u8),
+            modifiers: Modifiers::from_bits_truncate(ui_area_key_event.Modifiers as u8),
             up: ui_area_key_event.Up != 0,
         }
     }
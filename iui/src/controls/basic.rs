@@ -1,41 +1,61 @@
 use super::Control;
-use libc::c_void;
-use std::ffi::{CStr, CString};
+use ffi_tools;
+use libc::{c_int, c_void};
+use std::ffi::CStr;
 use std::mem;
 use ui::UI;
-use ui_sys::{self, uiButton, uiControl, uiLabel};
+use ui_sys::{self, uiButton, uiControl, uiLabel, uiMultilineEntry, uiProgressBar};
 
 define_control!{
     /// A non-interactable piece of text.
+    ///
+    /// Labels never wrap their text, so long text can be clipped by the edge of the window;
+    /// use a [`WrappingLabel`](struct.WrappingLabel.html) for text that should wrap.
     rust_type: Label,
     sys_type: uiLabel
 }
 
+define_control!{
+    /// A piece of text which wraps to the width it is given, for text too long for a `Label`.
+    ///
+    /// `libui` labels cannot wrap, so this is a read-only multi-line entry underneath. It
+    /// therefore looks like a text field, with a border and a scroll bar when the text does not
+    /// fit, and its text can be selected and copied.
+    rust_type: WrappingLabel,
+    sys_type: uiMultilineEntry
+}
+
 define_control!{
     /// A textual button which users can click on, causing a callback to run.
     rust_type: Button,
     sys_type: uiButton
 }
 
+define_control!{
+    /// Shows the progress of an operation, either as a percentage or, when its duration is
+    /// unknown, as a continuous animation.
+    rust_type: ProgressBar,
+    sys_type: uiProgressBar
+}
+
 impl Button {
     /// Create a new button with the given text as its label.
     pub fn new(_ctx: &UI, text: &str) -> Button {
         unsafe {
-            let c_string = CString::new(text.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(text);
             Button::from_raw(ui_sys::uiNewButton(c_string.as_ptr()))
         }
     }
 
     /// Get a copy of the existing text on the button.
     pub fn text(&self, _ctx: &UI) -> String {
-        unsafe {
-            CStr::from_ptr(ui_sys::uiButtonText(self.uiButton))
-                .to_string_lossy()
-                .into_owned()
-        }
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiButtonText(self.uiButton)) }
     }
 
     /// Get a reference to the existing text on the button.
+    ///
+    /// libui returns a fresh copy of the text each time, which this never frees.
+    #[deprecated(note = "use `text`, which does not leak the string libui returns")]
     pub fn text_ref(&self, _ctx: &UI) -> &CStr {
         unsafe { CStr::from_ptr(ui_sys::uiButtonText(self.uiButton)) }
     }
@@ -43,27 +63,51 @@ impl Button {
     /// Set the text on the button.
     pub fn set_text(&mut self, _ctx: &UI, text: &str) {
         unsafe {
-            let c_string = CString::new(text.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(text);
             ui_sys::uiButtonSetText(self.uiButton, c_string.as_ptr())
         }
     }
 
     /// Run the given callback when the button is clicked.
-    pub fn on_clicked<F: FnMut(&mut Button)>(&mut self, _ctx: &UI, callback: F) {
+    ///
+    /// Setting a new callback replaces the previous one, which is dropped the next time the
+    /// event loop runs; the last one is dropped when the `UI` is torn down. Setting a handler
+    /// repeatedly therefore does not leak:
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Button;
+    /// # use std::cell::Cell;
+    /// # use std::rc::Rc;
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Clicks", 200, 100, WindowType::NoMenubar);
+    /// let mut button = Button::new(&ui, "Click me");
+    /// window.set_child(&ui, button.clone());
+    ///
+    /// let clicks = Rc::new(Cell::new(0));
+    /// for _ in 0..10_000 {
+    ///     let clicks = clicks.clone();
+    ///     button.on_clicked(&ui, move |_| clicks.set(clicks.get() + 1));
+    /// }
+    /// ui.event_loop().next_tick(&ui);
+    ///
+    /// // Only the handler currently set is still alive.
+    /// assert_eq!(Rc::strong_count(&clicks), 2);
+    /// ```
+    pub fn on_clicked<F: FnMut(&mut Button) + 'static>(&mut self, _ctx: &UI, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(&mut Button)>> = Box::new(Box::new(callback));
+            let data: Box<dyn FnMut(&mut Button)> = Box::new(callback);
             ui_sys::uiButtonOnClicked(
                 self.uiButton,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(&mut Button)> as *mut c_void,
+                ffi_tools::register_callback(self.uiButton, "clicked", data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(button: *mut uiButton, data: *mut c_void) {
             unsafe {
                 let mut button = Button { uiButton: button };
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(&mut Button)>>(data)(&mut button)
+                (*(data as *mut Box<dyn FnMut(&mut Button)>))(&mut button)
             }
         }
     }
@@ -75,30 +119,106 @@ impl Label {
     /// to fit.
     pub fn new(_ctx: &UI, text: &str) -> Label {
         unsafe {
-            let c_string = CString::new(text.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(text);
             Label::from_raw(ui_sys::uiNewLabel(c_string.as_ptr()))
         }
     }
 
-    /// Get a copy of the existing text on the label.
+    /// Get a copy of the existing text on the label. libui's own copy is freed straight away,
+    /// so this can be called as often as needed.
     pub fn text(&self, _ctx: &UI) -> String {
-        unsafe {
-            CStr::from_ptr(ui_sys::uiLabelText(self.uiLabel))
-                .to_string_lossy()
-                .into_owned()
-        }
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiLabelText(self.uiLabel)) }
     }
 
     /// Get a reference to the existing text on the label.
+    ///
+    /// libui returns a fresh copy of the text each time, which this never frees.
+    #[deprecated(note = "use `text`, which does not leak the string libui returns")]
     pub fn text_ref(&self, _ctx: &UI) -> &CStr {
         unsafe { CStr::from_ptr(ui_sys::uiLabelText(self.uiLabel)) }
     }
 
-    /// Set the text on the label.
+    /// Set the text on the label. As everywhere in this crate, the text is cut short at its
+    /// first `'\0'`.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Label;
+    /// # let ui = UI::init().unwrap();
+    /// let mut status = Label::new(&ui, "Ready");
+    /// for i in 0..10_000 {
+    ///     status.set_text(&ui, &format!("Processed {} items", i));
+    /// }
+    /// assert_eq!(status.text(&ui), "Processed 9999 items");
+    ///
+    /// status.set_text(&ui, "Done\0 and more");
+    /// assert_eq!(status.text(&ui), "Done");
+    /// ```
     pub fn set_text(&mut self, _ctx: &UI, text: &str) {
         unsafe {
-            let c_string = CString::new(text.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(text);
             ui_sys::uiLabelSetText(self.uiLabel, c_string.as_ptr())
         }
     }
 }
+
+impl WrappingLabel {
+    /// Create a new wrapping label with the given string as its text.
+    pub fn new(_ctx: &UI, text: &str) -> WrappingLabel {
+        unsafe {
+            let label = WrappingLabel::from_raw(ui_sys::uiNewMultilineEntry());
+            ui_sys::uiMultilineEntrySetReadOnly(label.uiMultilineEntry, true as c_int);
+            let c_string = ffi_tools::to_c_string(text);
+            ui_sys::uiMultilineEntrySetText(label.uiMultilineEntry, c_string.as_ptr());
+            label
+        }
+    }
+
+    /// Get a copy of the existing text on the label.
+    pub fn text(&self, _ctx: &UI) -> String {
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiMultilineEntryText(self.uiMultilineEntry)) }
+    }
+
+    /// Set the text on the label.
+    pub fn set_text(&mut self, _ctx: &UI, text: &str) {
+        unsafe {
+            let c_string = ffi_tools::to_c_string(text);
+            ui_sys::uiMultilineEntrySetText(self.uiMultilineEntry, c_string.as_ptr())
+        }
+    }
+}
+
+impl ProgressBar {
+    /// Create a new progress bar, showing 0%.
+    pub fn new(_ctx: &UI) -> ProgressBar {
+        unsafe { ProgressBar::from_raw(ui_sys::uiNewProgressBar()) }
+    }
+
+    /// Get the percentage shown by the progress bar, or `None` if it is indeterminate.
+    pub fn value(&self, _ctx: &UI) -> Option<u32> {
+        let value = unsafe { ui_sys::uiProgressBarValue(self.uiProgressBar) };
+        if value < 0 {
+            None
+        } else {
+            Some(value as u32)
+        }
+    }
+
+    /// Show the given percentage, which is clamped to `0..=100`. This also switches an
+    /// indeterminate progress bar back to showing a percentage.
+    pub fn set_value(&mut self, _ctx: &UI, value: u32) {
+        let value = value.min(100) as c_int;
+        unsafe { ui_sys::uiProgressBarSetValue(self.uiProgressBar, value) }
+    }
+
+    /// Make the progress bar indeterminate: instead of a percentage, it shows an animation
+    /// to indicate that something is happening. `libui` represents this with the value -1.
+    pub fn set_indeterminate(&mut self, _ctx: &UI) {
+        unsafe { ui_sys::uiProgressBarSetValue(self.uiProgressBar, -1) }
+    }
+
+    /// Check whether or not the progress bar is indeterminate.
+    pub fn is_indeterminate(&self, _ctx: &UI) -> bool {
+        self.value(_ctx).is_none()
+    }
+}
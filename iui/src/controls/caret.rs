@@ -0,0 +1,76 @@
+//! Moving the caret of entries through the native toolkit, as `libui` has no caret or selection
+//! functions.
+
+/// Moves the caret of the native single-line entry `handle`, as returned by `uiControlHandle`,
+/// after its last character, clearing any selection.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) unsafe fn move_to_end(handle: usize) {
+    // -1 stands for the end of the text.
+    gtk::gtk_editable_set_position(handle as *mut _, -1);
+}
+
+#[cfg(windows)]
+pub(crate) unsafe fn move_to_end(handle: usize) {
+    let hwnd = handle as *mut _;
+    let len = win32::GetWindowTextLengthW(hwnd).max(0) as usize;
+    win32::SendMessageW(hwnd, win32::EM_SETSEL, len, len as isize);
+}
+
+// The caret of a text field belongs to its field editor, which only exists while the field is
+// being edited: otherwise, macOS places the caret itself when the field gains focus.
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn move_to_end(handle: usize) {
+    use libc::{c_char, c_void};
+    use std::mem;
+    use std::ptr;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    // `objc_msgSend` has to be called through a pointer of the method's own type.
+    let send: extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void =
+        mem::transmute(objc_msgSend as *const c_void);
+    let send1: extern "C" fn(*mut c_void, *mut c_void, *const c_void) -> *mut c_void =
+        mem::transmute(objc_msgSend as *const c_void);
+    let editor = send(
+        handle as *mut c_void,
+        sel_registerName(b"currentEditor\0".as_ptr() as *const c_char),
+    );
+    if !editor.is_null() {
+        send1(
+            editor,
+            sel_registerName(b"moveToEndOfDocument:\0".as_ptr() as *const c_char),
+            ptr::null(),
+        );
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) unsafe fn move_to_end(_handle: usize) {}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod gtk {
+    use libc::{c_int, c_void};
+
+    #[link(name = "gtk-3")]
+    extern "C" {
+        pub fn gtk_editable_set_position(editable: *mut c_void, position: c_int);
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod win32 {
+    use libc::{c_int, c_void};
+
+    pub const EM_SETSEL: u32 = 0x00B1;
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn GetWindowTextLengthW(hwnd: *mut c_void) -> c_int;
+        pub fn SendMessageW(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> isize;
+    }
+}
@@ -7,8 +7,21 @@
 //          rust_type: Slider,
 //          sys_type: uiSlider,
 //      }
+//
+// Ending the invocation with `owned_handles` leaves out `Drop`, `Clone` and `from_raw`, for
+// controls whose handles manage the lifetime of the control themselves.
 macro_rules! define_control {
     // Match first any attributes (incl. doc comments) and then the actual invocation
+    {$(#[$attr:meta])* rust_type: $rust_type:ident, sys_type: $sys_type:ident, owned_handles$(,)* } => {
+        #[allow(non_snake_case)]
+        // Include all attributes
+        $(#[$attr])*
+        pub struct $rust_type {
+            $sys_type: *mut $sys_type,
+        }
+
+        define_control!(@impls $rust_type, $sys_type);
+    };
     {$(#[$attr:meta])* rust_type: $rust_type:ident, sys_type: $sys_type:ident$(,)* } => {
         #[allow(non_snake_case)]
         // Include all attributes
@@ -32,6 +45,24 @@ macro_rules! define_control {
             }
         }
 
+        impl $rust_type {
+            /// Create an `iui` struct for this control from the raw pointer for it.
+            ///
+            /// # Unsafety
+            /// The given pointer must point to a valid control or memory unsafety may result.
+            #[allow(non_snake_case)]
+            #[allow(unused)]
+            pub unsafe fn from_raw($sys_type: *mut $sys_type) -> $rust_type {
+                $rust_type {
+                    $sys_type: $sys_type
+                }
+            }
+        }
+
+        define_control!(@impls $rust_type, $sys_type);
+    };
+    // The parts shared by every control
+    (@impls $rust_type:ident, $sys_type:ident) => {
         impl Into<Control> for $rust_type {
             fn into(self) -> Control {
                 unsafe {
@@ -42,29 +73,29 @@ macro_rules! define_control {
             }
         }
 
-        impl $rust_type {
-            // Show this control to the user. This will also show its non-hidden children.
-            pub fn show(&mut self, _ctx: &UI) {
-                let control: Control = self.clone().into();
-                unsafe { ui_sys::uiControlShow(control.ui_control) }
+        unsafe impl $crate::controls::ControlExt for $rust_type {
+            fn as_ui_control(&self) -> *mut uiControl {
+                self.$sys_type as *mut uiControl
             }
 
-            // Hide this control from the user. This will hide its children.
-            pub fn hide(&mut self, _ctx: &UI) {
-                let control: Control = self.clone().into();
-                unsafe { ui_sys::uiControlHide(control.ui_control) }
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
             }
 
-            /// Create an `iui` struct for this control from the raw pointer for it.
-            ///
-            /// # Unsafety
-            /// The given pointer must point to a valid control or memory unsafety may result.
-            #[allow(non_snake_case)]
-            #[allow(unused)]
-            pub unsafe fn from_raw($sys_type: *mut $sys_type) -> $rust_type {
-                $rust_type {
-                    $sys_type: $sys_type
-                }
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+                self
+            }
+        }
+
+        impl $rust_type {
+            /// Show this control to the user. This will also show its non-hidden children.
+            pub fn show(&mut self, ctx: &UI) {
+                $crate::controls::ControlExt::show(self, ctx)
+            }
+
+            /// Hide this control from the user. This will hide its children.
+            pub fn hide(&mut self, ctx: &UI) {
+                $crate::controls::ControlExt::hide(self, ctx)
             }
 
             /// Return the underlying pointer for this control.
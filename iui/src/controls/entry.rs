@@ -1,29 +1,161 @@
 //! User input mechanisms: numbers, colors, and text in various forms.
 
-use super::Control;
-use libc::c_void;
-use std::ffi::{CStr, CString};
+use super::{caret, forget_control, Control, ControlExt};
+use draw::FontDescriptor;
+use ffi_tools;
+use libc::{self, c_int, c_void};
+use std::any::Any;
+use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::i64;
 use std::mem;
+use std::ptr;
+use std::rc::Rc;
 use ui::UI;
 use ui_sys::{
-    self, uiCheckbox, uiCombobox, uiControl, uiEntry, uiMultilineEntry, uiSlider, uiSpinbox,
+    self, uiCheckbox, uiColorButton, uiCombobox, uiControl, uiDateTimePicker, uiEditableCombobox,
+    uiEntry, uiFontButton, uiMultilineEntry, uiRadioButtons, uiSlider, uiSpinbox,
 };
+use util_types::Color;
+
+thread_local! {
+    // The range of each `Spinbox`, which libui does not let us read back.
+    static SPINBOX_RANGES: RefCell<HashMap<*mut uiSpinbox, (i64, i64)>> = RefCell::new(HashMap::new());
+    // The same for each `Slider`.
+    static SLIDER_RANGES: RefCell<HashMap<*mut uiSlider, (i64, i64)>> = RefCell::new(HashMap::new());
+    // The steps given to `Spinbox::set_step` and `Spinbox::set_page_step`.
+    static SPINBOX_STEPS: RefCell<HashMap<*mut uiSpinbox, Step>> = RefCell::new(HashMap::new());
+    // The steps given to `Slider::set_step` and `Slider::set_page_step`.
+    static SLIDER_STEPS: RefCell<HashMap<*mut uiSlider, Step>> = RefCell::new(HashMap::new());
+    // The state each read-only `Checkbox` is held at.
+    static READ_ONLY_CHECKBOXES: RefCell<HashMap<*mut uiCheckbox, bool>> = RefCell::new(HashMap::new());
+    // The number of decimal places of each `DoubleSpinbox`.
+    static DOUBLE_SPINBOX_PRECISIONS: RefCell<HashMap<*mut uiSpinbox, u32>> = RefCell::new(HashMap::new());
+    // The selection each read-only `RadioButtons` is held at.
+    static READ_ONLY_RADIO_BUTTONS: RefCell<HashMap<*mut uiRadioButtons, i32>> = RefCell::new(HashMap::new())
+}
+
+/// Forgets the state kept for input controls. Called when the UI is torn down.
+pub(crate) fn clear_entry_state() {
+    SPINBOX_RANGES.with(|ranges| ranges.borrow_mut().clear());
+    DOUBLE_SPINBOX_PRECISIONS.with(|precisions| precisions.borrow_mut().clear());
+    SLIDER_RANGES.with(|ranges| ranges.borrow_mut().clear());
+    SPINBOX_STEPS.with(|steps| steps.borrow_mut().clear());
+    SLIDER_STEPS.with(|steps| steps.borrow_mut().clear());
+    READ_ONLY_CHECKBOXES.with(|checkboxes| checkboxes.borrow_mut().clear());
+    READ_ONLY_RADIO_BUTTONS.with(|radio_buttons| radio_buttons.borrow_mut().clear());
+}
+
+/// Forgets the state kept for the input control `control`, as it is about to be destroyed or
+/// was just created at the address of one destroyed without its state being forgotten.
+pub(crate) fn forget_entry_state(control: *mut uiControl) {
+    SPINBOX_RANGES.with(|ranges| ranges.borrow_mut().remove(&(control as *mut uiSpinbox)));
+    DOUBLE_SPINBOX_PRECISIONS.with(|precisions| {
+        precisions.borrow_mut().remove(&(control as *mut uiSpinbox))
+    });
+    SLIDER_RANGES.with(|ranges| ranges.borrow_mut().remove(&(control as *mut uiSlider)));
+    SPINBOX_STEPS.with(|steps| steps.borrow_mut().remove(&(control as *mut uiSpinbox)));
+    SLIDER_STEPS.with(|steps| steps.borrow_mut().remove(&(control as *mut uiSlider)));
+    READ_ONLY_CHECKBOXES.with(|checkboxes| {
+        checkboxes.borrow_mut().remove(&(control as *mut uiCheckbox))
+    });
+    READ_ONLY_RADIO_BUTTONS.with(|radio_buttons| {
+        radio_buttons.borrow_mut().remove(&(control as *mut uiRadioButtons))
+    });
+}
+
+/// The grid a `Spinbox` or `Slider` keeps its values on: multiples of `size` away from the
+/// minimum of its range, along with how far a page moves them.
+struct Step {
+    size: i64,
+    /// The distance of a page change, or 0 if changes are only snapped to the grid.
+    page: i64,
+    /// The last value the control had, to tell which way the user moved it.
+    last: i64,
+}
+
+impl Step {
+    /// Changes the step of `control` in `steps` with `update`, starting from `last`. The step
+    /// is dropped once it no longer changes anything. Returns whether it is kept.
+    fn update<K: Eq + Hash, F: FnOnce(&mut Step)>(
+        steps: &mut HashMap<K, Step>,
+        control: K,
+        last: i64,
+        update: F,
+    ) -> bool {
+        let mut step = steps.remove(&control).unwrap_or(Step {
+            size: 1,
+            page: 0,
+            last,
+        });
+        step.last = last;
+        update(&mut step);
+        let kept = step.size > 1 || step.page > 0;
+        if kept {
+            steps.insert(control, step);
+        }
+        kept
+    }
+
+    /// Moves `value` onto the grid within `range`, onward in the direction it was moved in, so
+    /// that every change by the user moves the value by at least one step. A change of more
+    /// than one unit, as the native control makes for the Page keys, and of at most a page is
+    /// first widened to exactly a page.
+    fn snap(&mut self, value: i64, range: (i64, i64)) -> i64 {
+        let change = (value - self.last).abs();
+        let value = if self.page > 0 && change > 1 && change <= self.page {
+            let page = if value > self.last { self.page } else { -self.page };
+            self.last.saturating_add(page).clamp(range.0, range.1)
+        } else {
+            value
+        };
+        let (min, max) = (range.0 as i128, range.1 as i128);
+        let size = self.size as i128;
+        let offset = value as i128 - min;
+        let mut steps = offset / size;
+        if offset % size != 0 && value > self.last {
+            steps += 1;
+        }
+        let mut snapped = min + steps * size;
+        if snapped > max {
+            snapped -= size;
+        }
+        self.last = snapped as i64;
+        self.last
+    }
+}
 
 pub trait NumericEntry {
     fn value(&self, ctx: &UI) -> i64;
     fn set_value(&mut self, ctx: &UI, value: i64);
-    fn on_changed<F: FnMut(i64)>(&mut self, ctx: &UI, callback: F);
+    fn on_changed<F: FnMut(i64) + 'static>(&mut self, ctx: &UI, callback: F);
 }
 
 pub trait TextEntry {
+    /// Get a copy of the entry's text. libui's own copy is freed straight away, so this can be
+    /// called as often as needed.
     fn value(&self, ctx: &UI) -> String;
     fn set_value(&mut self, ctx: &UI, value: &str);
-    fn on_changed<F: FnMut(String)>(&mut self, ctx: &UI, callback: F);
+    fn on_changed<F: FnMut(String) + 'static>(&mut self, ctx: &UI, callback: F);
 }
 
 define_control!{
     /// Numerical entry control which allows users to set any value in a range by typing or incrementing/decrementing.
+    ///
+    /// Its value is read, set and watched through [`NumericEntry`](trait.NumericEntry.html).
+    /// `set_value` clamps the value into the range the spinbox was created with, and does not
+    /// run the `on_changed` callback, which is only run for changes made by the user:
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Spinbox;
+    /// # let ui = UI::init().unwrap();
+    /// let mut quantity = Spinbox::new(&ui, 1, 10);
+    /// quantity.on_changed(&ui, |value| println!("Quantity: {}", value));
+    /// quantity.set_value(&ui, 25);
+    /// assert_eq!(quantity.value(&ui), 10);
+    /// ```
     rust_type: Spinbox, 
     sys_type: uiSpinbox
 }
@@ -35,88 +167,467 @@ define_control!{
 }
 
 impl Spinbox {
-    // Create a new Spinbox which can produce values from `min` to `max`.
+    /// Create a new Spinbox which can produce values from `min` to `max`. If `min` is larger
+    /// than `max`, they are swapped. `libui` keeps the values in an `int`, so bounds beyond
+    /// the range of an `i32` are moved to its ends.
     pub fn new(_ctx: &UI, min: i64, max: i64) -> Self {
-        unsafe { Spinbox::from_raw(ui_sys::uiNewSpinbox(min, max)) }
+        let (min, max) = (ffi_tools::to_c_int(min), ffi_tools::to_c_int(max));
+        let range = (i64::from(min.min(max)), i64::from(min.max(max)));
+        unsafe {
+            let ptr = ui_sys::uiNewSpinbox(min.min(max), min.max(max));
+            forget_control(ptr as *mut uiControl);
+            SPINBOX_RANGES.with(|ranges| ranges.borrow_mut().insert(ptr, range));
+            Spinbox::from_raw(ptr)
+        }
     }
 
-    // Create a new Spinbox with the maximum possible range.
+    /// Create a new Spinbox with the maximum possible range, that of an `i32`.
     pub fn new_unlimited(_ctx: &UI) -> Self {
         Self::new(_ctx, i64::MIN, i64::MAX)
     }
+
+    /// Returns the step the spinbox's values are kept to multiples of, from the minimum of its
+    /// range; 1 unless set with `set_step`.
+    pub fn step(&self, _ctx: &UI) -> i64 {
+        SPINBOX_STEPS.with(|steps| steps.borrow().get(&self.uiSpinbox).map_or(1, |step| step.size))
+    }
+
+    /// Keep the values the user picks to multiples of `step`, counted from the minimum of the
+    /// spinbox's range. A `step` of 1 or less removes the restriction.
+    ///
+    /// `libui` has no way to change how far the spinbox moves, so the values it produces are
+    /// rounded: after each change by the user, the value is moved onward to the next multiple
+    /// of `step` in the direction it went, and `on_changed` is given that value. The arrow keys
+    /// and buttons, the mouse wheel (where the platform lets it move the spinbox at all), and
+    /// the Page keys therefore move the value by at least one step; `set_page_step` gives the
+    /// Page keys a larger one. `set_value` is not rounded.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Spinbox;
+    /// # let ui = UI::init().unwrap();
+    /// let mut minutes = Spinbox::new(&ui, 0, 60);
+    /// minutes.set_step(&ui, 15);
+    /// assert_eq!(minutes.step(&ui), 15);
+    /// ```
+    pub fn set_step(&mut self, ctx: &UI, step: i64) {
+        self.update_step(ctx, |current| current.size = step.max(1));
+    }
+
+    /// Returns how far the Page keys move the spinbox's value, as set with `set_page_step`, or
+    /// 0 if they move it like any other change.
+    pub fn page_step(&self, _ctx: &UI) -> i64 {
+        SPINBOX_STEPS.with(|steps| steps.borrow().get(&self.uiSpinbox).map_or(0, |step| step.page))
+    }
+
+    /// Make the Page keys move the value by `page`. A `page` of 0 or less goes back to the
+    /// native behavior.
+    ///
+    /// `libui` does not tell the Page keys apart from other changes, so this goes by how far
+    /// the value moved: the arrow keys and buttons move the native spinbox by one, and any
+    /// change by the user of more than one and at most `page` is widened to exactly `page` in
+    /// the same direction, then rounded to `step` as usual. That includes a value typed in
+    /// within `page` of the previous one; values typed further away are kept. How far the Page
+    /// keys move the native spinbox, and whether the mouse wheel does, is up to the platform,
+    /// so `page` should be at least as large as the native page for it to be used.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Spinbox;
+    /// # let ui = UI::init().unwrap();
+    /// let mut minutes = Spinbox::new(&ui, 0, 600);
+    /// minutes.set_step(&ui, 5);
+    /// minutes.set_page_step(&ui, 60);
+    /// assert_eq!((minutes.step(&ui), minutes.page_step(&ui)), (5, 60));
+    /// ```
+    pub fn set_page_step(&mut self, ctx: &UI, page: i64) {
+        self.update_step(ctx, |current| current.page = page.max(0));
+    }
+
+    fn update_step<F: FnOnce(&mut Step)>(&mut self, ctx: &UI, update: F) {
+        let last = self.value(ctx);
+        let kept = SPINBOX_STEPS
+            .with(|steps| Step::update(&mut steps.borrow_mut(), self.uiSpinbox, last, update));
+        if kept {
+            unsafe { ui_sys::uiSpinboxOnChanged(self.uiSpinbox, spinbox_changed, ptr::null_mut()) }
+        }
+    }
+}
+
+extern "C" fn spinbox_changed(spinbox: *mut uiSpinbox, _data: *mut c_void) {
+    unsafe {
+        let mut value = i64::from(ui_sys::uiSpinboxValue(spinbox));
+        let range = SPINBOX_RANGES.with(|ranges| ranges.borrow().get(&spinbox).cloned());
+        let snapped = SPINBOX_STEPS.with(|steps| {
+            match (steps.borrow_mut().get_mut(&spinbox), range) {
+                (Some(step), Some(range)) => Some(step.snap(value, range)),
+                _ => None,
+            }
+        });
+        if let Some(snapped) = snapped {
+            if snapped != value {
+                ui_sys::uiSpinboxSetValue(spinbox, snapped as c_int);
+                value = snapped;
+            }
+        }
+        if let Some(callback) =
+            ffi_tools::registered_callback::<_, Box<dyn FnMut(i64)>>(spinbox, "changed")
+        {
+            callback(value);
+        }
+    }
+}
+
+define_control!{
+    /// Numerical entry control like `Spinbox`, but for fractional values with a fixed number of
+    /// decimal places.
+    ///
+    /// `libui` spinboxes only hold integers, so a `DoubleSpinbox` is a `Spinbox` counting in
+    /// units of its last decimal place: with a precision of 2, the value 1.5 is held as 150,
+    /// and that is also what the spinbox shows, and what the user types. The values given to
+    /// and returned by a `DoubleSpinbox` are converted, and the range kept by the underlying
+    /// spinbox is that of an `i32` in those units, so it shrinks tenfold with each decimal place.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::DoubleSpinbox;
+    /// # let ui = UI::init().unwrap();
+    /// let mut price = DoubleSpinbox::new(&ui, 0.0, 100.0, 2);
+    /// price.on_changed(&ui, |value| println!("Price: {:.2}", value));
+    /// price.set_value(&ui, 19.999);
+    /// assert_eq!(price.value(&ui), 20.0);
+    /// price.set_value(&ui, 250.0);
+    /// assert_eq!(price.value(&ui), 100.0);
+    /// ```
+    rust_type: DoubleSpinbox,
+    sys_type: uiSpinbox
+}
+
+impl DoubleSpinbox {
+    /// The largest number of decimal places a `DoubleSpinbox` can have.
+    pub const MAX_PRECISION: u32 = 9;
+
+    /// Create a new `DoubleSpinbox` which can produce values from `min` to `max`, with
+    /// `precision` decimal places. If `min` is larger than `max`, they are swapped, and
+    /// `precision` is capped at
+    /// [`MAX_PRECISION`](struct.DoubleSpinbox.html#associatedconstant.MAX_PRECISION).
+    pub fn new(ctx: &UI, min: f64, max: f64, precision: u32) -> Self {
+        let precision = precision.min(Self::MAX_PRECISION);
+        let scale = 10f64.powi(precision as i32);
+        let spinbox = Spinbox::new(ctx, to_units(min, scale), to_units(max, scale));
+        DOUBLE_SPINBOX_PRECISIONS.with(|precisions| {
+            precisions.borrow_mut().insert(spinbox.uiSpinbox, precision)
+        });
+        unsafe { DoubleSpinbox::from_raw(spinbox.uiSpinbox) }
+    }
+
+    /// Returns the number of decimal places of the spinbox's values.
+    pub fn precision(&self, _ctx: &UI) -> u32 {
+        DOUBLE_SPINBOX_PRECISIONS.with(|precisions| {
+            precisions.borrow().get(&self.uiSpinbox).cloned().unwrap_or(0)
+        })
+    }
+
+    /// Get the current value of the spinbox.
+    pub fn value(&self, ctx: &UI) -> f64 {
+        self.spinbox().value(ctx) as f64 / self.scale(ctx)
+    }
+
+    /// Set the value of the spinbox. It is clamped into the range the spinbox was created
+    /// with, and rounded to the nearest multiple of the last decimal place, so `value` may not
+    /// return exactly the number given here. Halfway values round away from zero, but most
+    /// decimals are not exact in an `f64`: 0.285 is slightly less, and rounds to 0.28 with a
+    /// precision of 2. Like `Spinbox::set_value`, this does not run the `on_changed` callback.
+    pub fn set_value(&mut self, ctx: &UI, value: f64) {
+        let value = to_units(value, self.scale(ctx));
+        self.spinbox().set_value(ctx, value);
+    }
+
+    /// Returns the step the spinbox's values are kept to multiples of; one unit of the last
+    /// decimal place unless set with `set_step`.
+    pub fn step(&self, ctx: &UI) -> f64 {
+        self.spinbox().step(ctx) as f64 / self.scale(ctx)
+    }
+
+    /// Keep the values the user picks to multiples of `step`, counted from the minimum of the
+    /// spinbox's range, as `Spinbox::set_step` does. `step` is rounded to the spinbox's
+    /// precision.
+    pub fn set_step(&mut self, ctx: &UI, step: f64) {
+        let step = to_units(step, self.scale(ctx));
+        self.spinbox().set_step(ctx, step);
+    }
+
+    /// Set a callback to be run with the new value when the user changes the value of the
+    /// spinbox. Setting a new callback replaces the previous one.
+    pub fn on_changed<F: FnMut(f64) + 'static>(&mut self, ctx: &UI, mut callback: F) {
+        let scale = self.scale(ctx);
+        self.spinbox().on_changed(ctx, move |value| callback(value as f64 / scale));
+    }
+
+    // The underlying integer spinbox.
+    fn spinbox(&self) -> Spinbox {
+        unsafe { Spinbox::from_raw(self.uiSpinbox) }
+    }
+
+    // The number of units of the last decimal place in 1.
+    fn scale(&self, ctx: &UI) -> f64 {
+        10f64.powi(self.precision(ctx) as i32)
+    }
+}
+
+// Converts `value` to the units of a `DoubleSpinbox` with the given scale, saturating at the
+// ends of an `i64`.
+fn to_units(value: f64, scale: f64) -> i64 {
+    (value * scale).round() as i64
 }
 
 impl Slider {
-    // Create a new Spinbox which can produce values from `min` to `max`.
+    /// Create a new Slider which can produce values from `min` to `max`. If `min` is larger
+    /// than `max`, they are swapped. As for a `Spinbox`, the bounds are kept within the range
+    /// of an `i32`.
     pub fn new(_ctx: &UI, min: i64, max: i64) -> Self {
-        unsafe { Slider::from_raw(ui_sys::uiNewSlider(min, max)) }
+        let (min, max) = (ffi_tools::to_c_int(min), ffi_tools::to_c_int(max));
+        let range = (i64::from(min.min(max)), i64::from(min.max(max)));
+        unsafe {
+            let ptr = ui_sys::uiNewSlider(min.min(max), min.max(max));
+            forget_control(ptr as *mut uiControl);
+            SLIDER_RANGES.with(|ranges| ranges.borrow_mut().insert(ptr, range));
+            Slider::from_raw(ptr)
+        }
     }
-}
 
-impl NumericEntry for Spinbox {
-    fn value(&self, _ctx: &UI) -> i64 {
-        unsafe { ui_sys::uiSpinboxValue(self.uiSpinbox) }
+    /// Returns the step the slider's values are kept to multiples of, from the minimum of its
+    /// range; 1 unless set with `set_step`.
+    pub fn step(&self, _ctx: &UI) -> i64 {
+        SLIDER_STEPS.with(|steps| steps.borrow().get(&self.uiSlider).map_or(1, |step| step.size))
     }
 
-    fn set_value(&mut self, _ctx: &UI, value: i64) {
-        unsafe { ui_sys::uiSpinboxSetValue(self.uiSpinbox, value) }
+    /// Keep the values the user picks to multiples of `step`, counted from the minimum of the
+    /// slider's range. A `step` of 1 or less removes the restriction.
+    ///
+    /// This works like [`Spinbox::set_step`](struct.Spinbox.html#method.set_step): the value is
+    /// moved onward to the next multiple of `step` after every change by the user, be it a drag,
+    /// the arrow or Page keys, or the mouse wheel, whose reach is up to the platform. While the
+    /// slider is dragged, it jumps from step to step.
+    pub fn set_step(&mut self, ctx: &UI, step: i64) {
+        self.update_step(ctx, |current| current.size = step.max(1));
+    }
+
+    /// Returns how far the Page keys move the slider's value, as set with `set_page_step`, or
+    /// 0 if they move it like any other change.
+    pub fn page_step(&self, _ctx: &UI) -> i64 {
+        SLIDER_STEPS.with(|steps| steps.borrow().get(&self.uiSlider).map_or(0, |step| step.page))
+    }
+
+    /// Make the Page keys move the value by `page`. A `page` of 0 or less goes back to the
+    /// native behavior.
+    ///
+    /// This works like [`Spinbox::set_page_step`](struct.Spinbox.html#method.set_page_step):
+    /// any change by the user of more than one and at most `page` is widened to exactly
+    /// `page`. Dragging the slider mostly moves it one unit at a time, but a fast drag across
+    /// a few units in one go, or a click on the track, also moves it by a page.
+    pub fn set_page_step(&mut self, ctx: &UI, page: i64) {
+        self.update_step(ctx, |current| current.page = page.max(0));
+    }
+
+    fn update_step<F: FnOnce(&mut Step)>(&mut self, ctx: &UI, update: F) {
+        let last = self.value(ctx);
+        let kept = SLIDER_STEPS
+            .with(|steps| Step::update(&mut steps.borrow_mut(), self.uiSlider, last, update));
+        if kept {
+            unsafe { ui_sys::uiSliderOnChanged(self.uiSlider, slider_changed, ptr::null_mut()) }
+        }
     }
 
-    fn on_changed<F: FnMut(i64)>(&mut self, _ctx: &UI, callback: F) {
+    /// Set a callback to be run when the user lets go of the slider, with its final value.
+    ///
+    /// Unlike `on_changed`, which runs for every intermediate value while the slider is being
+    /// dragged, this runs once per drag.
+    pub fn on_released<F: FnMut(i64) + 'static>(&mut self, _ctx: &UI, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(i64)>> = Box::new(Box::new(callback));
-            ui_sys::uiSpinboxOnChanged(
-                self.uiSpinbox,
+            let data: Box<dyn FnMut(i64)> = Box::new(callback);
+            ui_sys::uiSliderOnReleased(
+                self.uiSlider,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(i64)> as *mut c_void,
+                ffi_tools::register_callback(self.uiSlider, "released", data),
             );
-            mem::forget(data);
         }
 
-        extern "C" fn c_callback(spinbox: *mut uiSpinbox, data: *mut c_void) {
+        extern "C" fn c_callback(slider: *mut uiSlider, data: *mut c_void) {
             unsafe {
-                let val = ui_sys::uiSpinboxValue(spinbox);
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(i64)>>(data)(val);
+                let val = i64::from(ui_sys::uiSliderValue(slider));
+                (*(data as *mut Box<dyn FnMut(i64)>))(val);
             }
         }
     }
-}
 
-impl NumericEntry for Slider {
-    fn value(&self, _ctx: &UI) -> i64 {
-        unsafe { ui_sys::uiSliderValue(self.uiSlider) }
+    /// Check whether or not the slider shows its current value in a tooltip while it is used.
+    pub fn has_tool_tip(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiSliderHasToolTip(self.uiSlider) != 0 }
     }
 
-    fn set_value(&mut self, _ctx: &UI, value: i64) {
-        unsafe { ui_sys::uiSliderSetValue(self.uiSlider, value) }
+    /// Set whether or not the slider shows its current value in a tooltip while it is used.
+    /// This is enabled by default.
+    pub fn set_has_tool_tip(&mut self, _ctx: &UI, has_tool_tip: bool) {
+        unsafe { ui_sys::uiSliderSetHasToolTip(self.uiSlider, has_tool_tip as c_int) }
     }
 
-    fn on_changed<F: FnMut(i64)>(&mut self, _ctx: &UI, callback: F) {
+    /// Change the range of values the slider produces to `min` to `max`, keeping its place in
+    /// the layout. A value outside of the new range is clamped into it. If `min` is larger than
+    /// `max`, they are swapped.
+    ///
+    /// Like `set_value`, this does not run the `on_changed` callback, even when the value is
+    /// clamped.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Slider;
+    /// # let ui = UI::init().unwrap();
+    /// // A temperature in degrees Fahrenheit, switched over to Celsius.
+    /// let mut temperature = Slider::new(&ui, 32, 212);
+    /// temperature.set_value(&ui, 200);
+    /// temperature.set_range(&ui, 0, 100);
+    /// assert_eq!(temperature.value(&ui), 100);
+    /// ```
+    pub fn set_range(&mut self, _ctx: &UI, min: i64, max: i64) {
+        let (min, max) = (ffi_tools::to_c_int(min), ffi_tools::to_c_int(max));
+        let (min, max) = (min.min(max), min.max(max));
+        SLIDER_RANGES.with(|ranges| {
+            ranges.borrow_mut().insert(self.uiSlider, (i64::from(min), i64::from(max)))
+        });
         unsafe {
-            let mut data: Box<Box<dyn FnMut(i64)>> = Box::new(Box::new(callback));
-            ui_sys::uiSliderOnChanged(
-                self.uiSlider,
-                c_callback,
-                &mut *data as *mut Box<dyn FnMut(i64)> as *mut c_void,
-            );
-            mem::forget(data);
+            let value = ui_sys::uiSliderValue(self.uiSlider);
+            ui_sys::uiSliderSetRange(self.uiSlider, min, max);
+            let clamped = value.clamp(min, max);
+            if clamped != value {
+                ui_sys::uiSliderSetValue(self.uiSlider, clamped);
+            }
+            SLIDER_STEPS.with(|steps| {
+                if let Some(step) = steps.borrow_mut().get_mut(&self.uiSlider) {
+                    step.last = i64::from(clamped);
+                }
+            });
         }
+    }
+}
 
-        extern "C" fn c_callback(slider: *mut uiSlider, data: *mut c_void) {
-            unsafe {
-                let val = ui_sys::uiSliderValue(slider);
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(i64)>>(data)(val);
+extern "C" fn slider_changed(slider: *mut uiSlider, _data: *mut c_void) {
+    unsafe {
+        let mut value = i64::from(ui_sys::uiSliderValue(slider));
+        let range = SLIDER_RANGES.with(|ranges| ranges.borrow().get(&slider).cloned());
+        let snapped = SLIDER_STEPS.with(|steps| {
+            match (steps.borrow_mut().get_mut(&slider), range) {
+                (Some(step), Some(range)) => Some(step.snap(value, range)),
+                _ => None,
+            }
+        });
+        if let Some(snapped) = snapped {
+            if snapped != value {
+                ui_sys::uiSliderSetValue(slider, snapped as c_int);
+                value = snapped;
             }
         }
+        if let Some(callback) =
+            ffi_tools::registered_callback::<_, Box<dyn FnMut(i64)>>(slider, "changed")
+        {
+            callback(value);
+        }
+    }
+}
+
+impl NumericEntry for Spinbox {
+    fn value(&self, _ctx: &UI) -> i64 {
+        unsafe { i64::from(ui_sys::uiSpinboxValue(self.uiSpinbox)) }
+    }
+
+    fn set_value(&mut self, _ctx: &UI, value: i64) {
+        let range = SPINBOX_RANGES.with(|ranges| ranges.borrow().get(&self.uiSpinbox).cloned());
+        let value = match range {
+            Some((min, max)) => value.clamp(min, max),
+            None => value,
+        };
+        SPINBOX_STEPS.with(|steps| {
+            if let Some(step) = steps.borrow_mut().get_mut(&self.uiSpinbox) {
+                step.last = value;
+            }
+        });
+        unsafe { ui_sys::uiSpinboxSetValue(self.uiSpinbox, ffi_tools::to_c_int(value)) }
+    }
+
+    fn on_changed<F: FnMut(i64) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        let data: Box<dyn FnMut(i64)> = Box::new(callback);
+        ffi_tools::register_callback(self.uiSpinbox, "changed", data);
+        unsafe { ui_sys::uiSpinboxOnChanged(self.uiSpinbox, spinbox_changed, ptr::null_mut()) }
+    }
+}
+
+impl NumericEntry for Slider {
+    fn value(&self, _ctx: &UI) -> i64 {
+        unsafe { i64::from(ui_sys::uiSliderValue(self.uiSlider)) }
+    }
+
+    fn set_value(&mut self, _ctx: &UI, value: i64) {
+        let range = SLIDER_RANGES.with(|ranges| ranges.borrow().get(&self.uiSlider).cloned());
+        let value = match range {
+            Some((min, max)) => value.clamp(min, max),
+            None => value,
+        };
+        SLIDER_STEPS.with(|steps| {
+            if let Some(step) = steps.borrow_mut().get_mut(&self.uiSlider) {
+                step.last = value;
+            }
+        });
+        unsafe { ui_sys::uiSliderSetValue(self.uiSlider, ffi_tools::to_c_int(value)) }
+    }
+
+    fn on_changed<F: FnMut(i64) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        let data: Box<dyn FnMut(i64)> = Box::new(callback);
+        ffi_tools::register_callback(self.uiSlider, "changed", data);
+        unsafe { ui_sys::uiSliderOnChanged(self.uiSlider, slider_changed, ptr::null_mut()) }
     }
 }
 
 define_control! {
     /// Single-line editable text buffer.
+    ///
+    /// `libui` gives no access to the selection or the caret of an entry, so they can neither
+    /// be read nor set; `set_value` leaves the caret wherever the platform puts it, and
+    /// [`set_value_with_caret_at_end`](#method.set_value_with_caret_at_end) moves it after the
+    /// new text through the native toolkit. Text changes such as a find-and-replace are
+    /// therefore done on the value itself, whose positions are byte offsets into a Rust
+    /// `String`:
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Entry;
+    /// # let ui = UI::init().unwrap();
+    /// let mut entry = Entry::new(&ui);
+    /// entry.set_value(&ui, "Hello, world");
+    ///
+    /// let value = entry.value(&ui);
+    /// if let Some(start) = value.find("world") {
+    ///     let mut replaced = value.clone();
+    ///     replaced.replace_range(start..start + "world".len(), "libui");
+    ///     entry.set_value(&ui, &replaced);
+    /// }
+    /// assert_eq!(entry.value(&ui), "Hello, libui");
+    /// ```
     rust_type: Entry,
     sys_type: uiEntry
 }
 
+define_control! {
+    /// Single-line editable text buffer which hides the characters typed into it.
+    rust_type: PasswordEntry,
+    sys_type: uiEntry
+}
+
+define_control! {
+    /// Single-line editable text buffer styled as a search field.
+    rust_type: SearchEntry,
+    sys_type: uiEntry
+}
+
 define_control! {
     /// Multi-line editable text buffer.
     rust_type: MultilineEntry,
@@ -127,81 +638,249 @@ impl Entry {
     pub fn new(_ctx: &UI) -> Entry {
         unsafe { Entry::from_raw(ui_sys::uiNewEntry()) }
     }
+
+    /// Set the text like [`set_value`](trait.TextEntry.html#tymethod.set_value), then move the
+    /// caret after its last character, clearing any selection, so that the user can carry on
+    /// typing at the end.
+    ///
+    /// `libui` has no caret functions, so the caret is moved through GTK+ or Win32. On macOS,
+    /// it is only moved while the entry is being edited, as the caret does not exist otherwise
+    /// and macOS places it when the entry gains focus.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Entry;
+    /// # let ui = UI::init().unwrap();
+    /// let mut command = Entry::new(&ui);
+    /// command.set_value_with_caret_at_end(&ui, "git commit -m ");
+    /// assert_eq!(command.value(&ui), "git commit -m ");
+    /// ```
+    pub fn set_value_with_caret_at_end(&mut self, _ctx: &UI, value: &str) {
+        unsafe { entry_set_value_with_caret_at_end(self.uiEntry, value) }
+    }
+
+    /// Check whether or not the user is prevented from editing the text.
+    pub fn read_only(&self, _ctx: &UI) -> bool {
+        unsafe { entry_read_only(self.uiEntry) }
+    }
+
+    /// Set whether or not the user is prevented from editing the text. Unlike disabling the
+    /// entry, this keeps its normal appearance, and the text can still be selected and copied.
+    pub fn set_read_only(&mut self, _ctx: &UI, read_only: bool) {
+        unsafe { entry_set_read_only(self.uiEntry, read_only) }
+    }
+
+    /// Registers a callback for when the user has stopped changing the text: it is called with
+    /// the new text once `ms` milliseconds have passed without another change, so that a burst
+    /// of keystrokes results in a single call.
+    ///
+    /// This uses the same event as [`on_changed`](trait.TextEntry.html#tymethod.on_changed),
+    /// and replaces any callback given to either. A pending call is cancelled if the callback
+    /// is replaced or the entry destroyed. The delay works as for
+    /// [`UI::debounce`](../struct.UI.html#method.debounce), which has an example of it.
+    ///
+    /// ```no_run
+    /// use iui::prelude::*;
+    /// use iui::controls::Entry;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Search", 300, 100, WindowType::NoMenubar);
+    /// let mut search = Entry::new(&ui);
+    /// search.on_changed_debounced(&ui, 300, |query| println!("searching for {:?}", query));
+    /// window.set_child(&ui, search);
+    /// window.show(&ui);
+    /// ui.main();
+    /// ```
+    pub fn on_changed_debounced<F: FnMut(String) + 'static>(&mut self, ctx: &UI, ms: u32, callback: F) {
+        let debounced = ctx.debounce(ms, callback);
+        self.on_changed(ctx, debounced);
+    }
+}
+
+impl PasswordEntry {
+    /// Create a new entry whose text is masked as it is typed.
+    pub fn new(_ctx: &UI) -> PasswordEntry {
+        unsafe { PasswordEntry::from_raw(ui_sys::uiNewPasswordEntry()) }
+    }
+
+    /// Set the text, then move the caret after it, as
+    /// [`Entry::set_value_with_caret_at_end`](struct.Entry.html#method.set_value_with_caret_at_end)
+    /// does.
+    pub fn set_value_with_caret_at_end(&mut self, _ctx: &UI, value: &str) {
+        unsafe { entry_set_value_with_caret_at_end(self.uiEntry, value) }
+    }
+
+    /// Check whether or not the user is prevented from editing the text.
+    pub fn read_only(&self, _ctx: &UI) -> bool {
+        unsafe { entry_read_only(self.uiEntry) }
+    }
+
+    /// Set whether or not the user is prevented from editing the text.
+    pub fn set_read_only(&mut self, _ctx: &UI, read_only: bool) {
+        unsafe { entry_set_read_only(self.uiEntry, read_only) }
+    }
+}
+
+impl SearchEntry {
+    /// Create a new search field, which the platform may decorate with a search icon and a
+    /// button to clear it.
+    pub fn new(_ctx: &UI) -> SearchEntry {
+        unsafe { SearchEntry::from_raw(ui_sys::uiNewSearchEntry()) }
+    }
+
+    /// Set the text, then move the caret after it, as
+    /// [`Entry::set_value_with_caret_at_end`](struct.Entry.html#method.set_value_with_caret_at_end)
+    /// does.
+    pub fn set_value_with_caret_at_end(&mut self, _ctx: &UI, value: &str) {
+        unsafe { entry_set_value_with_caret_at_end(self.uiEntry, value) }
+    }
+
+    /// Check whether or not the user is prevented from editing the text.
+    pub fn read_only(&self, _ctx: &UI) -> bool {
+        unsafe { entry_read_only(self.uiEntry) }
+    }
+
+    /// Set whether or not the user is prevented from editing the text.
+    pub fn set_read_only(&mut self, _ctx: &UI, read_only: bool) {
+        unsafe { entry_set_read_only(self.uiEntry, read_only) }
+    }
 }
 
 impl MultilineEntry {
     pub fn new(_ctx: &UI) -> MultilineEntry {
         unsafe { MultilineEntry::from_raw(ui_sys::uiNewMultilineEntry()) }
     }
+
+    /// Check whether or not the user is prevented from editing the text.
+    pub fn read_only(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiMultilineEntryReadOnly(self.uiMultilineEntry) != 0 }
+    }
+
+    /// Set whether or not the user is prevented from editing the text. Unlike disabling the
+    /// entry, this keeps its normal appearance, and the text can still be selected and copied.
+    pub fn set_read_only(&mut self, _ctx: &UI, read_only: bool) {
+        unsafe { ui_sys::uiMultilineEntrySetReadOnly(self.uiMultilineEntry, read_only as i32) }
+    }
+
+    /// Add the given text to the end of the entry's contents. Unlike replacing the whole value
+    /// with `set_value`, this does not reset the view, which makes it suitable for logs.
+    ///
+    /// No newline is added; include `"\n"` in `text` to start a new line.
+    pub fn append(&mut self, _ctx: &UI, text: &str) {
+        let cstring = ffi_tools::to_c_string(text);
+        unsafe { ui_sys::uiMultilineEntryAppend(self.uiMultilineEntry, cstring.as_ptr()) }
+    }
 }
 
 impl TextEntry for Entry {
     fn value(&self, _ctx: &UI) -> String {
-        unsafe {
-            CStr::from_ptr(ui_sys::uiEntryText(self.uiEntry))
-                .to_string_lossy()
-                .into_owned()
-        }
+        unsafe { entry_value(self.uiEntry) }
     }
+
     fn set_value(&mut self, _ctx: &UI, value: &str) {
-        let cstring = CString::new(value.as_bytes().to_vec()).unwrap();
-        unsafe { ui_sys::uiEntrySetText(self.uiEntry, cstring.as_ptr()) }
+        unsafe { entry_set_value(self.uiEntry, value) }
     }
 
-    fn on_changed<F: FnMut(String)>(&mut self, _ctx: &UI, callback: F) {
-        unsafe {
-            let mut data: Box<Box<dyn FnMut(String)>> = Box::new(Box::new(callback));
-            ui_sys::uiEntryOnChanged(
-                self.uiEntry,
-                c_callback,
-                &mut *data as *mut Box<dyn FnMut(String)> as *mut c_void,
-            );
-            mem::forget(data);
-        }
+    fn on_changed<F: FnMut(String) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe { entry_on_changed(self.uiEntry, callback) }
+    }
+}
 
-        extern "C" fn c_callback(entry: *mut uiEntry, data: *mut c_void) {
-            unsafe {
-                let string = CStr::from_ptr(ui_sys::uiEntryText(entry))
-                    .to_string_lossy()
-                    .into_owned();
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(String)>>(data)(string);
-                mem::forget(entry);
-            }
-        }
+impl TextEntry for PasswordEntry {
+    fn value(&self, _ctx: &UI) -> String {
+        unsafe { entry_value(self.uiEntry) }
+    }
+
+    fn set_value(&mut self, _ctx: &UI, value: &str) {
+        unsafe { entry_set_value(self.uiEntry, value) }
+    }
+
+    fn on_changed<F: FnMut(String) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe { entry_on_changed(self.uiEntry, callback) }
     }
 }
 
-impl TextEntry for MultilineEntry {
+impl TextEntry for SearchEntry {
     fn value(&self, _ctx: &UI) -> String {
+        unsafe { entry_value(self.uiEntry) }
+    }
+
+    fn set_value(&mut self, _ctx: &UI, value: &str) {
+        unsafe { entry_set_value(self.uiEntry, value) }
+    }
+
+    /// Set a callback to be run when the search text changes.
+    ///
+    /// Unlike the other entries, `libui` does not report every keystroke: on most platforms the
+    /// callback only runs once the user pauses typing (or clears the field), so it is a good
+    /// place to start an actual search.
+    fn on_changed<F: FnMut(String) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe { entry_on_changed(self.uiEntry, callback) }
+    }
+}
+
+// Shared between the single-line entries, which are all `uiEntry`s underneath.
+unsafe fn entry_value(entry: *mut uiEntry) -> String {
+    ffi_tools::copy_and_free_text(ui_sys::uiEntryText(entry))
+}
+
+unsafe fn entry_set_value(entry: *mut uiEntry, value: &str) {
+    let cstring = ffi_tools::to_c_string(value);
+    ui_sys::uiEntrySetText(entry, cstring.as_ptr())
+}
+
+unsafe fn entry_set_value_with_caret_at_end(entry: *mut uiEntry, value: &str) {
+    entry_set_value(entry, value);
+    caret::move_to_end(ui_sys::uiControlHandle(entry as *mut uiControl));
+}
+
+unsafe fn entry_on_changed<F: FnMut(String) + 'static>(entry: *mut uiEntry, callback: F) {
+    let data: Box<dyn FnMut(String)> = Box::new(callback);
+    ui_sys::uiEntryOnChanged(
+        entry,
+        c_callback,
+        ffi_tools::register_callback(entry, "changed", data),
+    );
+
+    extern "C" fn c_callback(entry: *mut uiEntry, data: *mut c_void) {
         unsafe {
-            CStr::from_ptr(ui_sys::uiMultilineEntryText(self.uiMultilineEntry))
-                .to_string_lossy()
-                .into_owned()
+            let string = entry_value(entry);
+            (*(data as *mut Box<dyn FnMut(String)>))(string);
         }
     }
+}
+
+unsafe fn entry_read_only(entry: *mut uiEntry) -> bool {
+    ui_sys::uiEntryReadOnly(entry) != 0
+}
+
+unsafe fn entry_set_read_only(entry: *mut uiEntry, read_only: bool) {
+    ui_sys::uiEntrySetReadOnly(entry, read_only as i32)
+}
+
+impl TextEntry for MultilineEntry {
+    fn value(&self, _ctx: &UI) -> String {
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiMultilineEntryText(self.uiMultilineEntry)) }
+    }
     fn set_value(&mut self, _ctx: &UI, value: &str) {
-        let cstring = CString::new(value.as_bytes().to_vec()).unwrap();
+        let cstring = ffi_tools::to_c_string(value);
         unsafe { ui_sys::uiMultilineEntrySetText(self.uiMultilineEntry, cstring.as_ptr()) }
     }
 
-    fn on_changed<F: FnMut(String)>(&mut self, _ctx: &UI, callback: F) {
+    fn on_changed<F: FnMut(String) + 'static>(&mut self, _ctx: &UI, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(String)>> = Box::new(Box::new(callback));
+            let data: Box<dyn FnMut(String)> = Box::new(callback);
             ui_sys::uiMultilineEntryOnChanged(
                 self.uiMultilineEntry,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(String)> as *mut c_void,
+                ffi_tools::register_callback(self.uiMultilineEntry, "changed", data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(entry: *mut uiMultilineEntry, data: *mut c_void) {
             unsafe {
-                let string = CStr::from_ptr(ui_sys::uiMultilineEntryText(entry))
-                    .to_string_lossy()
-                    .into_owned();
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(String)>>(data)(string);
-                mem::forget(entry);
+                let string = ffi_tools::copy_and_free_text(ui_sys::uiMultilineEntryText(entry));
+                (*(data as *mut Box<dyn FnMut(String)>))(string);
             }
         }
     }
@@ -222,37 +901,236 @@ impl Combobox {
     /// Adds a new option to the combination box.
     pub fn append(&self, _ctx: &UI, name: &str) {
         unsafe {
-            let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(name);
             ui_sys::uiComboboxAppend(self.uiCombobox, c_string.as_ptr())
         }
     }
 
+    /// Get the index of the selected option, or -1 if no option is selected, which is the case
+    /// until the user or `set_selected` selects one.
+    pub fn selected(&self, _ctx: &UI) -> i64 {
+        unsafe { i64::from(ui_sys::uiComboboxSelected(self.uiCombobox)) }
+    }
+
+    /// Select the option with the given index, or clear the selection if given -1.
     pub fn set_selected(&mut self, _ctx: &UI, value: i64) {
-        unsafe { ui_sys::uiComboboxSetSelected(self.uiCombobox, value) }
+        unsafe { ui_sys::uiComboboxSetSelected(self.uiCombobox, ffi_tools::to_c_int(value)) }
     }
 
-    pub fn on_selected<F: FnMut(i64)>(&mut self, _ctx: &UI, callback: F) {
+    /// Set a callback to be run with the index of the newly selected option when the user
+    /// changes the selection. Setting a new callback replaces the previous one.
+    pub fn on_selected<F: FnMut(i64) + 'static>(&mut self, _ctx: &UI, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(i64)>> = Box::new(Box::new(callback));
+            let data: Box<dyn FnMut(i64)> = Box::new(callback);
             ui_sys::uiComboboxOnSelected(
                 self.uiCombobox,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(i64)> as *mut c_void,
+                ffi_tools::register_callback(self.uiCombobox, "selected", data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(combobox: *mut uiCombobox, data: *mut c_void) {
             unsafe {
-                let val = ui_sys::uiComboboxSelected(combobox);
+                let val = i64::from(ui_sys::uiComboboxSelected(combobox));
                 // let combobox = Combobox::from_ui_control(combobox);
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(i64)>>(data)(val);
+                (*(data as *mut Box<dyn FnMut(i64)>))(val);
                 // mem::forget(combobox);
             }
         }
     }
 }
 
+/// A [`Combobox`](struct.Combobox.html) whose options each carry a value, so that the
+/// selection can be read as a value rather than as an index.
+///
+/// Clones of a `TypedCombobox` share the combobox and its values.
+///
+/// ```
+/// # use iui::prelude::*;
+/// # use iui::controls::TypedCombobox;
+/// # let ui = UI::init().unwrap();
+/// #[derive(Debug, PartialEq)]
+/// enum Quality {
+///     Low,
+///     High,
+/// }
+///
+/// let mut quality = TypedCombobox::new(&ui);
+/// quality.push(&ui, "Low (faster)", Quality::Low);
+/// quality.push(&ui, "High (slower)", Quality::High);
+/// quality.on_selected(&ui, |quality| println!("rendering at {:?} quality", quality));
+///
+/// assert!(quality.selected_value(&ui).is_none());
+/// quality.set_selected(&ui, Some(1));
+/// assert_eq!(*quality.selected_value(&ui).unwrap(), Quality::High);
+/// ```
+pub struct TypedCombobox<T> {
+    combobox: Combobox,
+    values: Rc<RefCell<Vec<T>>>,
+}
+
+impl<T> Clone for TypedCombobox<T> {
+    fn clone(&self) -> TypedCombobox<T> {
+        TypedCombobox {
+            combobox: self.combobox.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<T: 'static> TypedCombobox<T> {
+    /// Create a new combobox, with no options.
+    pub fn new(ctx: &UI) -> Self {
+        TypedCombobox {
+            combobox: Combobox::new(ctx),
+            values: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Add an option showing `label` to the end of the list, standing for `value`.
+    ///
+    /// # Panics
+    /// Panics if a value returned by `selected_value` is still borrowed.
+    pub fn push(&mut self, ctx: &UI, label: &str, value: T) {
+        self.values.borrow_mut().push(value);
+        self.combobox.append(ctx, label);
+    }
+
+    /// Returns the number of options.
+    pub fn len(&self) -> usize {
+        self.values.borrow().len()
+    }
+
+    /// Returns true if there are no options.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the value of the selected option, or `None` if no option is selected.
+    pub fn selected_value(&self, ctx: &UI) -> Option<Ref<'_, T>> {
+        let index = self.selected(ctx)?;
+        Some(Ref::map(self.values.borrow(), |values| &values[index]))
+    }
+
+    /// Get the index of the selected option, or `None` if no option is selected.
+    pub fn selected(&self, ctx: &UI) -> Option<usize> {
+        let selected = self.combobox.selected(ctx);
+        if selected < 0 || selected as usize >= self.len() {
+            None
+        } else {
+            Some(selected as usize)
+        }
+    }
+
+    /// Select the option with the given index, or clear the selection if given `None`.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn set_selected(&mut self, ctx: &UI, index: Option<usize>) {
+        let selected = match index {
+            Some(index) => {
+                assert!(index < self.len(), "option {} of {} is out of bounds", index, self.len());
+                index as i64
+            }
+            None => -1,
+        };
+        self.combobox.set_selected(ctx, selected)
+    }
+
+    /// Set a callback to be run with the value of the newly selected option when the user
+    /// changes the selection. Setting a new callback replaces the previous one.
+    ///
+    /// The values are borrowed while the callback runs, so it must not add options.
+    pub fn on_selected<F: FnMut(&T) + 'static>(&mut self, ctx: &UI, mut callback: F) {
+        let values = self.values.clone();
+        self.combobox.on_selected(ctx, move |selected| {
+            if selected >= 0 {
+                if let Some(value) = values.borrow().get(selected as usize) {
+                    callback(value);
+                }
+            }
+        });
+    }
+}
+
+impl<T> From<TypedCombobox<T>> for Control {
+    fn from(combobox: TypedCombobox<T>) -> Control {
+        combobox.combobox.into()
+    }
+}
+
+unsafe impl<T: 'static> ControlExt for TypedCombobox<T> {
+    fn as_ui_control(&self) -> *mut uiControl {
+        self.combobox.as_ui_control()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+define_control! {
+    /// Allows the user to either pick one of its options from a list, or type in any other
+    /// value.
+    rust_type: EditableCombobox,
+    sys_type: uiEditableCombobox
+}
+
+impl EditableCombobox {
+    /// Create a new, empty editable combination box.
+    pub fn new(_ctx: &UI) -> Self {
+        unsafe { EditableCombobox::from_raw(ui_sys::uiNewEditableCombobox()) }
+    }
+
+    /// Adds a new option to the list of suggestions.
+    pub fn append(&self, _ctx: &UI, name: &str) {
+        unsafe {
+            let c_string = ffi_tools::to_c_string(name);
+            ui_sys::uiEditableComboboxAppend(self.uiEditableCombobox, c_string.as_ptr())
+        }
+    }
+}
+
+impl TextEntry for EditableCombobox {
+    /// Get the text in the editable field, whether it was picked from the list or typed in.
+    fn value(&self, _ctx: &UI) -> String {
+        unsafe { editable_combobox_text(self.uiEditableCombobox) }
+    }
+
+    fn set_value(&mut self, _ctx: &UI, value: &str) {
+        let cstring = ffi_tools::to_c_string(value);
+        unsafe { ui_sys::uiEditableComboboxSetText(self.uiEditableCombobox, cstring.as_ptr()) }
+    }
+
+    /// Set a callback to be run when the text changes, either because the user typed or
+    /// because they picked an option from the list.
+    fn on_changed<F: FnMut(String) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: Box<dyn FnMut(String)> = Box::new(callback);
+            ui_sys::uiEditableComboboxOnChanged(
+                self.uiEditableCombobox,
+                c_callback,
+                ffi_tools::register_callback(self.uiEditableCombobox, "changed", data),
+            );
+        }
+
+        extern "C" fn c_callback(combobox: *mut uiEditableCombobox, data: *mut c_void) {
+            unsafe {
+                let string = editable_combobox_text(combobox);
+                (*(data as *mut Box<dyn FnMut(String)>))(string);
+            }
+        }
+    }
+}
+
+unsafe fn editable_combobox_text(combobox: *mut uiEditableCombobox) -> String {
+    ffi_tools::copy_and_free_text(ui_sys::uiEditableComboboxText(combobox))
+}
+
 define_control! {
     /// Boolean selection control which can be checked or unchecked.
     rust_type: Checkbox,
@@ -260,36 +1138,370 @@ define_control! {
 }
 
 impl Checkbox {
-    // Create a new Checkbox which can produce values from `min` to `max`.
+    /// Create a new, unchecked checkbox labelled with the given text.
     pub fn new(_ctx: &UI, text: &str) -> Self {
-        let c_string = CString::new(text.as_bytes().to_vec()).unwrap();
-        unsafe { Checkbox::from_raw(ui_sys::uiNewCheckbox(c_string.as_ptr())) }
+        let c_string = ffi_tools::to_c_string(text);
+        unsafe {
+            let ptr = ui_sys::uiNewCheckbox(c_string.as_ptr());
+            forget_control(ptr as *mut uiControl);
+            Checkbox::from_raw(ptr)
+        }
+    }
+
+    /// Get a copy of the text the checkbox is labelled with.
+    pub fn text(&self, _ctx: &UI) -> String {
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiCheckboxText(self.uiCheckbox)) }
+    }
+
+    /// Set the text the checkbox is labelled with.
+    pub fn set_text(&mut self, _ctx: &UI, text: &str) {
+        unsafe {
+            let c_string = ffi_tools::to_c_string(text);
+            ui_sys::uiCheckboxSetText(self.uiCheckbox, c_string.as_ptr())
+        }
     }
 
+    /// Check whether the checkbox is checked.
     pub fn checked(&self, _ctx: &UI) -> bool {
         unsafe { ui_sys::uiCheckboxChecked(self.uiCheckbox) != 0 }
     }
 
+    /// Check or uncheck the checkbox. This does not call the `on_toggled` callback. It works
+    /// even if the checkbox is read-only.
     pub fn set_checked(&mut self, _ctx: &UI, checked: bool) {
+        READ_ONLY_CHECKBOXES.with(|checkboxes| {
+            if let Some(held) = checkboxes.borrow_mut().get_mut(&self.uiCheckbox) {
+                *held = checked;
+            }
+        });
         unsafe { ui_sys::uiCheckboxSetChecked(self.uiCheckbox, checked as i32) }
     }
 
-    pub fn on_toggled<F: FnMut(bool)>(&mut self, _ctx: &UI, callback: F) {
+    /// Registers a callback for when the user checks or unchecks the checkbox, which is given
+    /// whether it is now checked.
+    pub fn on_toggled<F: FnMut(bool) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        let data: Box<dyn FnMut(bool)> = Box::new(callback);
+        ffi_tools::register_callback(self.uiCheckbox, "toggled", data);
+        unsafe { ui_sys::uiCheckboxOnToggled(self.uiCheckbox, checkbox_toggled, ptr::null_mut()) }
+    }
+
+    /// Check whether the checkbox is read-only.
+    pub fn read_only(&self, _ctx: &UI) -> bool {
+        READ_ONLY_CHECKBOXES.with(|checkboxes| checkboxes.borrow().contains_key(&self.uiCheckbox))
+    }
+
+    /// Make the checkbox read-only, or editable again.
+    ///
+    /// `libui` has no read-only checkboxes, so this is a soft read-only: the checkbox looks
+    /// just like an editable one, but whenever the user toggles it, it is put straight back
+    /// and `on_toggled` is not run. Unlike [`disable`](trait.ControlExt.html#method.disable),
+    /// which greys the checkbox out, its state stays easy to read, which suits review screens.
+    /// `set_checked` still changes it.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Checkbox;
+    /// # let ui = UI::init().unwrap();
+    /// let mut saved = Checkbox::new(&ui, "Send usage statistics");
+    /// saved.set_checked(&ui, true);
+    /// saved.set_read_only(&ui, true);
+    /// assert!(saved.read_only(&ui));
+    /// assert!(saved.enabled(&ui));
+    /// ```
+    pub fn set_read_only(&mut self, ctx: &UI, read_only: bool) {
+        if read_only {
+            let checked = self.checked(ctx);
+            READ_ONLY_CHECKBOXES.with(|checkboxes| checkboxes.borrow_mut().insert(self.uiCheckbox, checked));
+            unsafe { ui_sys::uiCheckboxOnToggled(self.uiCheckbox, checkbox_toggled, ptr::null_mut()) }
+        } else {
+            READ_ONLY_CHECKBOXES.with(|checkboxes| checkboxes.borrow_mut().remove(&self.uiCheckbox));
+        }
+    }
+}
+
+extern "C" fn checkbox_toggled(checkbox: *mut uiCheckbox, _data: *mut c_void) {
+    unsafe {
+        let held = READ_ONLY_CHECKBOXES.with(|checkboxes| checkboxes.borrow().get(&checkbox).cloned());
+        if let Some(held) = held {
+            ui_sys::uiCheckboxSetChecked(checkbox, held as c_int);
+            return;
+        }
+        if let Some(callback) =
+            ffi_tools::registered_callback::<_, Box<dyn FnMut(bool)>>(checkbox, "toggled")
+        {
+            callback(ui_sys::uiCheckboxChecked(checkbox) != 0);
+        }
+    }
+}
+
+define_control! {
+    /// Allows the user to select any one of its options, from a list of buttons shown one
+    /// above the other.
+    rust_type: RadioButtons,
+    sys_type: uiRadioButtons
+}
+
+impl RadioButtons {
+    /// Create a new, empty group of radio buttons.
+    pub fn new(_ctx: &UI) -> Self {
+        unsafe {
+            let ptr = ui_sys::uiNewRadioButtons();
+            forget_control(ptr as *mut uiControl);
+            RadioButtons::from_raw(ptr)
+        }
+    }
+
+    /// Adds a new option, as a button with the given text below the existing ones.
+    pub fn append(&self, _ctx: &UI, name: &str) {
+        unsafe {
+            let c_string = ffi_tools::to_c_string(name);
+            ui_sys::uiRadioButtonsAppend(self.uiRadioButtons, c_string.as_ptr())
+        }
+    }
+
+    /// Get the index of the selected option, or -1 if no option is selected, which is the case
+    /// until the user or `set_selected` selects one.
+    pub fn selected(&self, _ctx: &UI) -> i32 {
+        unsafe { ui_sys::uiRadioButtonsSelected(self.uiRadioButtons) }
+    }
+
+    /// Select the option with the given index. Passing -1 (or any other negative value) clears
+    /// the selection. It works even if the radio buttons are read-only.
+    pub fn set_selected(&mut self, _ctx: &UI, index: i32) {
+        let index = if index < 0 { -1 } else { index };
+        READ_ONLY_RADIO_BUTTONS.with(|radio_buttons| {
+            if let Some(held) = radio_buttons.borrow_mut().get_mut(&self.uiRadioButtons) {
+                *held = index;
+            }
+        });
+        unsafe { ui_sys::uiRadioButtonsSetSelected(self.uiRadioButtons, index) }
+    }
+
+    /// Set a callback to be run with the index of the newly selected option when the user
+    /// changes the selection.
+    pub fn on_selected<F: FnMut(i32) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        let data: Box<dyn FnMut(i32)> = Box::new(callback);
+        ffi_tools::register_callback(self.uiRadioButtons, "selected", data);
+        unsafe {
+            ui_sys::uiRadioButtonsOnSelected(self.uiRadioButtons, radio_buttons_selected, ptr::null_mut())
+        }
+    }
+
+    /// Check whether the radio buttons are read-only.
+    pub fn read_only(&self, _ctx: &UI) -> bool {
+        READ_ONLY_RADIO_BUTTONS
+            .with(|radio_buttons| radio_buttons.borrow().contains_key(&self.uiRadioButtons))
+    }
+
+    /// Make the radio buttons read-only, or editable again.
+    ///
+    /// Like [`Checkbox::set_read_only`](struct.Checkbox.html#method.set_read_only), this is a
+    /// soft read-only: the buttons look editable, but a selection made by the user is undone
+    /// straight away without running `on_selected`, while `set_selected` still works.
+    pub fn set_read_only(&mut self, ctx: &UI, read_only: bool) {
+        if read_only {
+            let selected = self.selected(ctx);
+            READ_ONLY_RADIO_BUTTONS
+                .with(|radio_buttons| radio_buttons.borrow_mut().insert(self.uiRadioButtons, selected));
+            unsafe {
+                ui_sys::uiRadioButtonsOnSelected(self.uiRadioButtons, radio_buttons_selected, ptr::null_mut())
+            }
+        } else {
+            READ_ONLY_RADIO_BUTTONS
+                .with(|radio_buttons| radio_buttons.borrow_mut().remove(&self.uiRadioButtons));
+        }
+    }
+}
+
+extern "C" fn radio_buttons_selected(radio_buttons: *mut uiRadioButtons, _data: *mut c_void) {
+    unsafe {
+        let held = READ_ONLY_RADIO_BUTTONS
+            .with(|read_only| read_only.borrow().get(&radio_buttons).cloned());
+        if let Some(held) = held {
+            ui_sys::uiRadioButtonsSetSelected(radio_buttons, held);
+            return;
+        }
+        if let Some(callback) =
+            ffi_tools::registered_callback::<_, Box<dyn FnMut(i32)>>(radio_buttons, "selected")
+        {
+            callback(ui_sys::uiRadioButtonsSelected(radio_buttons));
+        }
+    }
+}
+
+define_control! {
+    /// Allows the user to pick a date, a time of day, or both.
+    rust_type: DateTimePicker,
+    sys_type: uiDateTimePicker
+}
+
+impl DateTimePicker {
+    /// Create a new picker for both a date and a time of day.
+    pub fn new(_ctx: &UI) -> Self {
+        unsafe { DateTimePicker::from_raw(ui_sys::uiNewDateTimePicker()) }
+    }
+
+    /// Create a new picker for a date only.
+    pub fn new_date(_ctx: &UI) -> Self {
+        unsafe { DateTimePicker::from_raw(ui_sys::uiNewDatePicker()) }
+    }
+
+    /// Create a new picker for a time of day only.
+    pub fn new_time(_ctx: &UI) -> Self {
+        unsafe { DateTimePicker::from_raw(ui_sys::uiNewTimePicker()) }
+    }
+
+    /// Get the date and time shown by the picker.
+    ///
+    /// The value is in local time, with the fields laid out as by `localtime`: `tm_year` counts
+    /// from 1900 and `tm_mon` from 0. `tm_wday`, `tm_yday` and `tm_isdst` are not meaningful,
+    /// so use `mktime` on a copy of the value to fill them in if they are needed. For pickers
+    /// which only show a date or a time, the other fields are not meaningful either.
+    pub fn time(&self, _ctx: &UI) -> libc::tm {
+        unsafe { date_time_picker_time(self.uiDateTimePicker) }
+    }
+
+    /// Set the date and time shown by the picker, in local time. Only the date and time fields
+    /// of `time` are used; `tm_wday`, `tm_yday` and `tm_isdst` are ignored.
+    ///
+    /// Local times which do not exist or are ambiguous because of a daylight saving time
+    /// change are resolved by the platform, and may not be shown exactly as given.
+    pub fn set_time(&mut self, _ctx: &UI, time: libc::tm) {
+        unsafe { ui_sys::uiDateTimePickerSetTime(self.uiDateTimePicker, &time) }
+    }
+
+    /// Set a callback to be run with the new value, as given by `time`, when the user changes
+    /// the date or time.
+    pub fn on_changed<F: FnMut(libc::tm) + 'static>(&mut self, _ctx: &UI, callback: F) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(bool)>> = Box::new(Box::new(callback));
-            ui_sys::uiCheckboxOnToggled(
-                self.uiCheckbox,
+            let data: Box<dyn FnMut(libc::tm)> = Box::new(callback);
+            ui_sys::uiDateTimePickerOnChanged(
+                self.uiDateTimePicker,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(bool)> as *mut c_void,
+                ffi_tools::register_callback(self.uiDateTimePicker, "changed", data),
             );
-            mem::forget(data);
         }
 
-        extern "C" fn c_callback(checkbox: *mut uiCheckbox, data: *mut c_void) {
+        extern "C" fn c_callback(picker: *mut uiDateTimePicker, data: *mut c_void) {
             unsafe {
-                let val = ui_sys::uiCheckboxChecked(checkbox) != 0;
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(bool)>>(data)(val);
+                let val = date_time_picker_time(picker);
+                (*(data as *mut Box<dyn FnMut(libc::tm)>))(val);
             }
         }
     }
 }
+
+unsafe fn date_time_picker_time(picker: *mut uiDateTimePicker) -> libc::tm {
+    // `tm` is plain old data, and libui fills in every field it uses.
+    let mut time: libc::tm = mem::zeroed();
+    ui_sys::uiDateTimePickerTime(picker, &mut time);
+    time
+}
+
+define_control! {
+    /// A button showing a color, which opens a color picker when clicked.
+    rust_type: ColorButton,
+    sys_type: uiColorButton
+}
+
+impl ColorButton {
+    /// Create a new color button, initially showing opaque black.
+    pub fn new(_ctx: &UI) -> Self {
+        unsafe { ColorButton::from_raw(ui_sys::uiNewColorButton()) }
+    }
+
+    /// Get the selected color. It can be given directly to
+    /// [`Brush::solid`](../draw/enum.Brush.html#method.solid).
+    pub fn color(&self, _ctx: &UI) -> Color {
+        unsafe { color_button_color(self.uiColorButton) }
+    }
+
+    /// Set the selected color. Each channel is clamped to `0.0..=1.0`.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::Color;
+    /// # use iui::controls::ColorButton;
+    /// # let ui = UI::init().unwrap();
+    /// let mut button = ColorButton::new(&ui);
+    /// button.set_color(&ui, Color::from_hex("#3366CC").unwrap());
+    /// assert_eq!(button.color(&ui).to_hex(), "#3366CCFF");
+    /// ```
+    pub fn set_color(&mut self, _ctx: &UI, color: Color) {
+        let color = color.clamped();
+        unsafe {
+            ui_sys::uiColorButtonSetColor(self.uiColorButton, color.r, color.g, color.b, color.a)
+        }
+    }
+
+    /// Set a callback to be run with the new color when the user picks one.
+    pub fn on_changed<F: FnMut(Color) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: Box<dyn FnMut(Color)> = Box::new(callback);
+            ui_sys::uiColorButtonOnChanged(
+                self.uiColorButton,
+                c_callback,
+                ffi_tools::register_callback(self.uiColorButton, "changed", data),
+            );
+        }
+
+        extern "C" fn c_callback(button: *mut uiColorButton, data: *mut c_void) {
+            unsafe {
+                let color = color_button_color(button);
+                (*(data as *mut Box<dyn FnMut(Color)>))(color);
+            }
+        }
+    }
+}
+
+unsafe fn color_button_color(button: *mut uiColorButton) -> Color {
+    let (mut r, mut g, mut b, mut a) = (0.0, 0.0, 0.0, 0.0);
+    ui_sys::uiColorButtonColor(button, &mut r, &mut g, &mut b, &mut a);
+    Color::new(r, g, b, a)
+}
+
+define_control! {
+    /// A button showing a font, which opens a font picker when clicked.
+    rust_type: FontButton,
+    sys_type: uiFontButton
+}
+
+impl FontButton {
+    /// Create a new font button, initially showing the platform's default font.
+    pub fn new(_ctx: &UI) -> Self {
+        unsafe { FontButton::from_raw(ui_sys::uiNewFontButton()) }
+    }
+
+    /// Get the selected font, as a descriptor which can be used to draw text in it.
+    pub fn font(&self, _ctx: &UI) -> FontDescriptor {
+        unsafe { font_button_font(self.uiFontButton) }
+    }
+
+    /// Set a callback to be run with the new font, as given by `font`, when the user picks one.
+    pub fn on_changed<F: FnMut(FontDescriptor) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: Box<dyn FnMut(FontDescriptor)> = Box::new(callback);
+            ui_sys::uiFontButtonOnChanged(
+                self.uiFontButton,
+                c_callback,
+                ffi_tools::register_callback(self.uiFontButton, "changed", data),
+            );
+        }
+
+        extern "C" fn c_callback(button: *mut uiFontButton, data: *mut c_void) {
+            unsafe {
+                let font = font_button_font(button);
+                (*(data as *mut Box<dyn FnMut(FontDescriptor)>))(font);
+            }
+        }
+    }
+}
+
+unsafe fn font_button_font(button: *mut uiFontButton) -> FontDescriptor {
+    // libui fills in every field of the descriptor.
+    let mut desc: ui_sys::uiFontDescriptor = mem::zeroed();
+    ui_sys::uiFontButtonFont(button, &mut desc);
+    let font = FontDescriptor::from_ui_font_descriptor(&desc);
+    ui_sys::uiFreeFontButtonFont(&mut desc);
+    font
+}
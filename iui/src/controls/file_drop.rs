@@ -0,0 +1,229 @@
+//! Accepting files dropped onto windows through the native toolkit, as `libui` has no drag and
+//! drop support.
+
+#[cfg(any(all(unix, not(target_os = "macos")), windows))]
+use super::window::files_dropped;
+use error::UIError;
+#[cfg(all(unix, not(target_os = "macos")))]
+use libc::{c_int, c_uint};
+#[cfg(any(all(unix, not(target_os = "macos")), windows))]
+use libc::c_void;
+use ui_sys::uiWindow;
+
+/// Makes the native window `handle`, as returned by `uiControlHandle`, accept files dropped
+/// onto it, passing their paths to `files_dropped` along with `window`.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) unsafe fn accept_files(handle: usize, window: *mut uiWindow) -> Result<(), UIError> {
+    let targets = [gtk::GtkTargetEntry {
+        target: b"text/uri-list\0".as_ptr() as *mut _,
+        flags: 0,
+        info: 0,
+    }];
+    // GTK+ copies the targets.
+    gtk::gtk_drag_dest_set(
+        handle as *mut c_void,
+        gtk::GTK_DEST_DEFAULT_ALL,
+        targets.as_ptr(),
+        targets.len() as _,
+        gtk::GDK_ACTION_COPY,
+    );
+    gtk::g_signal_connect_data(
+        handle as *mut c_void,
+        b"drag-data-received\0".as_ptr() as *const _,
+        drag_data_received as gtk::DragDataReceived as *const c_void,
+        window as *mut c_void,
+        None,
+        0,
+    );
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+extern "C" fn drag_data_received(
+    _widget: *mut c_void,
+    _context: *mut c_void,
+    _x: c_int,
+    _y: c_int,
+    selection: *mut c_void,
+    _info: c_uint,
+    _time: c_uint,
+    window: *mut c_void,
+) {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+    use std::ptr;
+
+    unsafe {
+        let uris = gtk::gtk_selection_data_get_uris(selection);
+        if uris.is_null() {
+            return;
+        }
+        let mut paths = Vec::new();
+        let mut uri = uris;
+        while !(*uri).is_null() {
+            // URIs which are not local files, like links dropped from a browser, are skipped.
+            let path = gtk::g_filename_from_uri(*uri, ptr::null_mut(), ptr::null_mut());
+            if !path.is_null() {
+                paths.push(PathBuf::from(OsStr::from_bytes(CStr::from_ptr(path).to_bytes())));
+                gtk::g_free(path as *mut c_void);
+            }
+            uri = uri.add(1);
+        }
+        gtk::g_strfreev(uris);
+        if !paths.is_empty() {
+            files_dropped(window as *mut uiWindow, &paths);
+        }
+    }
+}
+
+#[cfg(windows)]
+pub(crate) unsafe fn accept_files(handle: usize, window: *mut uiWindow) -> Result<(), UIError> {
+    let hwnd = handle as *mut c_void;
+    if win32::SetWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID, window as usize) == 0 {
+        return Err(UIError::Unsupported {
+            operation: "accepting dropped files on this window",
+        });
+    }
+    win32::DragAcceptFiles(hwnd, 1);
+    Ok(())
+}
+
+/// Identifies the window procedure added by `accept_files` among those of the window.
+#[cfg(windows)]
+const SUBCLASS_ID: usize = 0x6975_6964;
+
+#[cfg(windows)]
+extern "system" fn subclass_proc(
+    hwnd: *mut c_void,
+    msg: u32,
+    wparam: usize,
+    lparam: isize,
+    _id: usize,
+    window: usize,
+) -> isize {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use std::path::PathBuf;
+    use std::ptr;
+
+    unsafe {
+        match msg {
+            win32::WM_DROPFILES => {
+                let drop = wparam as *mut c_void;
+                let count = win32::DragQueryFileW(drop, 0xFFFF_FFFF, ptr::null_mut(), 0);
+                let mut paths = Vec::with_capacity(count as usize);
+                for i in 0..count {
+                    let len = win32::DragQueryFileW(drop, i, ptr::null_mut(), 0);
+                    let mut buffer = vec![0u16; len as usize + 1];
+                    let len = win32::DragQueryFileW(drop, i, buffer.as_mut_ptr(), buffer.len() as u32);
+                    paths.push(PathBuf::from(OsString::from_wide(&buffer[..len as usize])));
+                }
+                win32::DragFinish(drop);
+                if !paths.is_empty() {
+                    files_dropped(window as *mut uiWindow, &paths);
+                }
+                0
+            }
+            win32::WM_NCDESTROY => {
+                win32::RemoveWindowSubclass(hwnd, subclass_proc, SUBCLASS_ID);
+                win32::DefSubclassProc(hwnd, msg, wparam, lparam)
+            }
+            _ => win32::DefSubclassProc(hwnd, msg, wparam, lparam),
+        }
+    }
+}
+
+// Windows on macOS would need an `NSView` registered for dragged types, which `libui` gives no
+// way to hook into.
+#[cfg(any(target_os = "macos", not(any(unix, windows))))]
+pub(crate) unsafe fn accept_files(_handle: usize, _window: *mut uiWindow) -> Result<(), UIError> {
+    Err(UIError::Unsupported {
+        operation: "dropping files onto a window on this platform",
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod gtk {
+    use libc::{c_char, c_int, c_uint, c_ulong, c_void};
+
+    pub const GTK_DEST_DEFAULT_ALL: c_int = 0x07;
+    pub const GDK_ACTION_COPY: c_int = 1 << 1;
+
+    pub type DragDataReceived = extern "C" fn(
+        *mut c_void,
+        *mut c_void,
+        c_int,
+        c_int,
+        *mut c_void,
+        c_uint,
+        c_uint,
+        *mut c_void,
+    );
+
+    #[repr(C)]
+    pub struct GtkTargetEntry {
+        pub target: *mut c_char,
+        pub flags: c_uint,
+        pub info: c_uint,
+    }
+
+    #[link(name = "gtk-3")]
+    #[link(name = "gobject-2.0")]
+    #[link(name = "glib-2.0")]
+    extern "C" {
+        pub fn gtk_drag_dest_set(
+            widget: *mut c_void,
+            flags: c_int,
+            targets: *const GtkTargetEntry,
+            n_targets: c_int,
+            actions: c_int,
+        );
+        pub fn gtk_selection_data_get_uris(selection: *mut c_void) -> *mut *mut c_char;
+        pub fn g_signal_connect_data(
+            instance: *mut c_void,
+            signal: *const c_char,
+            handler: *const c_void,
+            data: *mut c_void,
+            destroy_data: Option<extern "C" fn(*mut c_void, *mut c_void)>,
+            flags: c_int,
+        ) -> c_ulong;
+        pub fn g_filename_from_uri(
+            uri: *const c_char,
+            hostname: *mut *mut c_char,
+            error: *mut *mut c_void,
+        ) -> *mut c_char;
+        pub fn g_strfreev(strings: *mut *mut c_char);
+        pub fn g_free(mem: *mut c_void);
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod win32 {
+    use libc::{c_int, c_void};
+
+    pub const WM_DROPFILES: u32 = 0x0233;
+    pub const WM_NCDESTROY: u32 = 0x0082;
+
+    pub type SubclassProc = extern "system" fn(*mut c_void, u32, usize, isize, usize, usize) -> isize;
+
+    #[link(name = "comctl32")]
+    extern "system" {
+        pub fn SetWindowSubclass(
+            hwnd: *mut c_void,
+            subclass: SubclassProc,
+            id: usize,
+            data: usize,
+        ) -> c_int;
+        pub fn RemoveWindowSubclass(hwnd: *mut c_void, subclass: SubclassProc, id: usize) -> c_int;
+        pub fn DefSubclassProc(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> isize;
+    }
+
+    #[link(name = "shell32")]
+    extern "system" {
+        pub fn DragAcceptFiles(hwnd: *mut c_void, accept: c_int);
+        pub fn DragQueryFileW(drop: *mut c_void, file: u32, buffer: *mut u16, len: u32) -> u32;
+        pub fn DragFinish(drop: *mut c_void);
+    }
+}
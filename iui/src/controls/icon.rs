@@ -0,0 +1,204 @@
+//! Setting window icons through the native toolkit, as `libui` has no function for it.
+
+use super::Bitmap;
+use error::UIError;
+#[cfg(any(all(unix, not(target_os = "macos")), windows))]
+use libc::{c_int, c_void};
+
+/// Makes `bitmaps` the icon of the native window `handle`, as returned by `uiControlHandle`.
+/// Does nothing if there are no bitmaps.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) unsafe fn set_window_icon(handle: usize, bitmaps: &[Bitmap]) -> Result<(), UIError> {
+    use std::ptr;
+
+    if bitmaps.is_empty() {
+        return Ok(());
+    }
+    // GTK+ picks the bitmap to use for each size the icon is shown at.
+    let mut list = ptr::null_mut();
+    for bitmap in bitmaps {
+        let pixbuf = gtk::gdk_pixbuf_new(
+            gtk::GDK_COLORSPACE_RGB,
+            1,
+            8,
+            bitmap.width as c_int,
+            bitmap.height as c_int,
+        );
+        if pixbuf.is_null() {
+            continue;
+        }
+        // Rows of a pixbuf may be padded, so they are copied one by one.
+        let stride = gtk::gdk_pixbuf_get_rowstride(pixbuf) as usize;
+        let data = gtk::gdk_pixbuf_get_pixels(pixbuf);
+        let row_len = bitmap.width as usize * 4;
+        for (y, row) in bitmap.pixels.chunks(row_len).enumerate() {
+            ptr::copy_nonoverlapping(row.as_ptr(), data.add(y * stride), row_len);
+        }
+        list = gtk::g_list_append(list, pixbuf);
+    }
+    // The window keeps its own references to the pixbufs.
+    gtk::gtk_window_set_icon_list(handle as *mut c_void, list);
+    let mut item = list;
+    while !item.is_null() {
+        gtk::g_object_unref((*item).data);
+        item = (*item).next;
+    }
+    gtk::g_list_free(list);
+    Ok(())
+}
+
+#[cfg(windows)]
+pub(crate) unsafe fn set_window_icon(handle: usize, bitmaps: &[Bitmap]) -> Result<(), UIError> {
+    use std::ptr;
+
+    if bitmaps.is_empty() {
+        return Ok(());
+    }
+    let hwnd = handle as *mut c_void;
+    // Windows shows a large icon in the taskbar and the task switcher, and a small one in the
+    // title bar.
+    let sizes = [
+        (win32::ICON_BIG, win32::GetSystemMetrics(win32::SM_CXICON)),
+        (win32::ICON_SMALL, win32::GetSystemMetrics(win32::SM_CXSMICON)),
+    ];
+    for &(kind, size) in &sizes {
+        let bitmap = closest_bitmap(bitmaps, size as u32);
+        // The color bitmap holds BGRA pixels. Its alpha channel is used as the icon's mask,
+        // so the mask bitmap only needs to exist.
+        let bgra: Vec<u8> = bitmap
+            .pixels
+            .chunks(4)
+            .flat_map(|pixel| vec![pixel[2], pixel[1], pixel[0], pixel[3]])
+            .collect();
+        let width = bitmap.width as c_int;
+        let height = bitmap.height as c_int;
+        let mut info = win32::ICONINFO {
+            fIcon: 1,
+            xHotspot: 0,
+            yHotspot: 0,
+            hbmMask: win32::CreateBitmap(width, height, 1, 1, ptr::null()),
+            hbmColor: win32::CreateBitmap(width, height, 1, 32, bgra.as_ptr() as *const c_void),
+        };
+        let icon = win32::CreateIconIndirect(&mut info);
+        win32::DeleteObject(info.hbmMask);
+        win32::DeleteObject(info.hbmColor);
+        if icon.is_null() {
+            return Err(UIError::Unsupported {
+                operation: "creating an icon from this image",
+            });
+        }
+        let previous = win32::SendMessageW(hwnd, win32::WM_SETICON, kind, icon as isize);
+        // Only icons made here are destroyed: the default one is shared.
+        ICONS.with(|icons| {
+            let mut icons = icons.borrow_mut();
+            let previous = previous as *mut c_void;
+            if let Some(index) = icons.iter().position(|&made| made == previous) {
+                win32::DestroyIcon(icons.swap_remove(index));
+            }
+            icons.push(icon);
+        });
+    }
+    Ok(())
+}
+
+/// Returns the smallest bitmap at least `size` pixels wide, or the largest one if they are
+/// all smaller.
+#[cfg(windows)]
+fn closest_bitmap(bitmaps: &[Bitmap], size: u32) -> &Bitmap {
+    bitmaps
+        .iter()
+        .filter(|bitmap| bitmap.width >= size)
+        .min_by_key(|bitmap| bitmap.width)
+        .or_else(|| bitmaps.iter().max_by_key(|bitmap| bitmap.width))
+        .unwrap()
+}
+
+#[cfg(windows)]
+thread_local! {
+    // The icons created by `set_window_icon`, which are destroyed once replaced.
+    static ICONS: ::std::cell::RefCell<Vec<*mut c_void>> = ::std::cell::RefCell::new(Vec::new())
+}
+
+// Windows on macOS have no icons of their own: the Dock shows the icon of the application
+// bundle.
+#[cfg(any(target_os = "macos", not(any(unix, windows))))]
+pub(crate) unsafe fn set_window_icon(_handle: usize, _bitmaps: &[Bitmap]) -> Result<(), UIError> {
+    Err(UIError::Unsupported {
+        operation: "setting the icon of a window on this platform",
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod gtk {
+    use libc::{c_int, c_void};
+
+    pub const GDK_COLORSPACE_RGB: c_int = 0;
+
+    #[repr(C)]
+    pub struct GList {
+        pub data: *mut c_void,
+        pub next: *mut GList,
+        pub prev: *mut GList,
+    }
+
+    #[link(name = "gtk-3")]
+    #[link(name = "gdk_pixbuf-2.0")]
+    #[link(name = "gobject-2.0")]
+    #[link(name = "glib-2.0")]
+    extern "C" {
+        pub fn gdk_pixbuf_new(
+            colorspace: c_int,
+            has_alpha: c_int,
+            bits_per_sample: c_int,
+            width: c_int,
+            height: c_int,
+        ) -> *mut c_void;
+        pub fn gdk_pixbuf_get_pixels(pixbuf: *const c_void) -> *mut u8;
+        pub fn gdk_pixbuf_get_rowstride(pixbuf: *const c_void) -> c_int;
+        pub fn gtk_window_set_icon_list(window: *mut c_void, list: *mut GList);
+        pub fn g_list_append(list: *mut GList, data: *mut c_void) -> *mut GList;
+        pub fn g_list_free(list: *mut GList);
+        pub fn g_object_unref(object: *mut c_void);
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod win32 {
+    use libc::{c_int, c_void};
+
+    pub const WM_SETICON: u32 = 0x0080;
+    pub const ICON_SMALL: usize = 0;
+    pub const ICON_BIG: usize = 1;
+    pub const SM_CXICON: c_int = 11;
+    pub const SM_CXSMICON: c_int = 49;
+
+    #[repr(C)]
+    pub struct ICONINFO {
+        pub fIcon: c_int,
+        pub xHotspot: u32,
+        pub yHotspot: u32,
+        pub hbmMask: *mut c_void,
+        pub hbmColor: *mut c_void,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn CreateIconIndirect(info: *mut ICONINFO) -> *mut c_void;
+        pub fn DestroyIcon(icon: *mut c_void) -> c_int;
+        pub fn SendMessageW(hwnd: *mut c_void, msg: u32, wparam: usize, lparam: isize) -> isize;
+        pub fn GetSystemMetrics(index: c_int) -> c_int;
+    }
+
+    #[link(name = "gdi32")]
+    extern "system" {
+        pub fn CreateBitmap(
+            width: c_int,
+            height: c_int,
+            planes: u32,
+            bits_per_pixel: u32,
+            bits: *const c_void,
+        ) -> *mut c_void;
+        pub fn DeleteObject(object: *mut c_void) -> c_int;
+    }
+}
@@ -1,10 +1,11 @@
-use super::Control;
+use super::{adopt, disown, disown_all, Control};
 use error::UIError;
-use libc::c_int;
-use std::ffi::{CStr, CString};
+use ffi_tools;
+use libc::{c_int, c_void};
+use std::ffi::CStr;
 use std::mem;
 use ui::UI;
-use ui_sys::{self, uiBox, uiControl, uiGroup, uiSeparator, uiTab, uiGrid, uiAlign, uiAt};
+use ui_sys::{self, uiBox, uiControl, uiForm, uiGroup, uiSeparator, uiTab, uiGrid, uiAlign, uiAt};
 
 /// Defines the ways in which the children of boxes can be layed out.
 pub enum LayoutStrategy {
@@ -52,7 +53,23 @@ fn append<T: Into<Control>>(b: *mut uiBox, ctx: &UI, child: T, strategy: LayoutS
     let control = child.into();
     unsafe {
         assert!(ctx.parent_of(control.clone()).is_none());
-        ui_sys::uiBoxAppend(b, control.ui_control, stretchy as c_int)
+        ui_sys::uiBoxAppend(b, control.ui_control, stretchy as c_int);
+        adopt(b as *mut uiControl, control.ui_control, None);
+    }
+}
+
+fn num_children(b: *mut uiBox, _ctx: &UI) -> u64 {
+    unsafe { ui_sys::uiBoxNumChildren(b) as u64 }
+}
+
+fn delete(b: *mut uiBox, ctx: &UI, index: u64) -> Result<u64, UIError> {
+    let n = num_children(b, ctx);
+    if index < n {
+        unsafe { ui_sys::uiBoxDelete(b, index as c_int) };
+        disown(b as *mut uiControl, index as usize);
+        Ok(num_children(b, ctx))
+    } else {
+        Err(UIError::ChildIndexOutOfBounds { index, n })
     }
 }
 
@@ -70,6 +87,20 @@ impl VerticalBox {
         append(self.uiBox, _ctx, child, strategy)
     }
 
+    /// Returns the number of children in the box.
+    pub fn num_children(&self, _ctx: &UI) -> u64 {
+        num_children(self.uiBox, _ctx)
+    }
+
+    /// Remove the child at the given index. The children after it move down by one index.
+    ///
+    /// Returns the number of children after removing this one, or an error if that index was
+    /// out of bounds. The child is not destroyed: it no longer has a parent, and can be added
+    /// somewhere else.
+    pub fn delete(&mut self, _ctx: &UI, index: u64) -> Result<u64, UIError> {
+        delete(self.uiBox, _ctx, index)
+    }
+
     /// Determine whenther the box provides padding around its children.
     pub fn padded(&self, _ctx: &UI) -> bool {
         padded(self.uiBox, _ctx)
@@ -87,6 +118,20 @@ impl HorizontalBox {
         append(self.uiBox, _ctx, child, strategy)
     }
 
+    /// Returns the number of children in the box.
+    pub fn num_children(&self, _ctx: &UI) -> u64 {
+        num_children(self.uiBox, _ctx)
+    }
+
+    /// Remove the child at the given index. The children after it move down by one index.
+    ///
+    /// Returns the number of children after removing this one, or an error if that index was
+    /// out of bounds. The child is not destroyed: it no longer has a parent, and can be added
+    /// somewhere else.
+    pub fn delete(&mut self, _ctx: &UI, index: u64) -> Result<u64, UIError> {
+        delete(self.uiBox, _ctx, index)
+    }
+
     /// Determine whenther the box provides padding around its children.
     pub fn padded(&self, _ctx: &UI) -> bool {
         padded(self.uiBox, _ctx)
@@ -98,6 +143,77 @@ impl HorizontalBox {
     }
 }
 
+define_control! {
+    /// Lays out its children in rows, each with a label to its left, as in a settings dialog.
+    rust_type: Form,
+    sys_type: uiForm
+}
+
+impl Form {
+    /// Create a new, empty form.
+    pub fn new(_ctx: &UI) -> Form {
+        unsafe { Form::from_raw(ui_sys::uiNewForm()) }
+    }
+
+    /// Add a row to the end of the form, with the given label next to the control. The
+    /// strategy decides whether the row's height stays fit to the control (`Compact`) or the
+    /// row grows to share the form's extra vertical space (`Stretchy`).
+    pub fn append<T: Into<Control>>(
+        &mut self,
+        ctx: &UI,
+        label: &str,
+        child: T,
+        strategy: LayoutStrategy,
+    ) {
+        let stretchy = match strategy {
+            LayoutStrategy::Compact => false,
+            LayoutStrategy::Stretchy => true,
+        };
+        let control = child.into();
+        unsafe {
+            assert!(ctx.parent_of(control.clone()).is_none());
+            let c_string = ffi_tools::to_c_string(label);
+            ui_sys::uiFormAppend(
+                self.uiForm,
+                c_string.as_ptr(),
+                control.ui_control,
+                stretchy as c_int,
+            );
+        }
+        adopt(self.uiForm as *mut uiControl, control.ui_control, None);
+    }
+
+    /// Returns the number of rows in the form.
+    pub fn num_children(&self, _ctx: &UI) -> u64 {
+        unsafe { ui_sys::uiFormNumChildren(self.uiForm) as u64 }
+    }
+
+    /// Remove the row at the given index. The rows after it move up by one index.
+    ///
+    /// Returns the number of rows after removing this one, or an error if that index was out
+    /// of bounds. The row's control is not destroyed, and can be added somewhere else.
+    pub fn delete(&mut self, _ctx: &UI, index: u64) -> Result<u64, UIError> {
+        let n = self.num_children(_ctx);
+        if index < n {
+            unsafe { ui_sys::uiFormDelete(self.uiForm, index as c_int) };
+            disown(self.uiForm as *mut uiControl, index as usize);
+            Ok(self.num_children(_ctx))
+        } else {
+            Err(UIError::ChildIndexOutOfBounds { index, n })
+        }
+    }
+
+    /// Determine whether or not the form provides padding between its rows.
+    pub fn padded(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiFormPadded(self.uiForm) != 0 }
+    }
+
+    /// Set whether or not the form should provide padding between its rows.
+    pub fn set_padded(&mut self, _ctx: &UI, padded: bool) {
+        unsafe { ui_sys::uiFormSetPadded(self.uiForm, padded as c_int) }
+    }
+}
+
 define_control! {
     /// Group of tabs, each of which shows a different sub-control.
     rust_type: TabGroup,
@@ -105,7 +221,8 @@ define_control! {
 }
 
 define_control! {
-    /// Collects controls together, with (optionally) a margin and/or title.
+    /// Collects controls together, drawing a frame with a title around them and (optionally) a
+    /// margin inside it.
     rust_type: Group,
     sys_type: uiGroup
 }
@@ -114,7 +231,7 @@ impl Group {
     /// Create a new group with the given title.
     pub fn new(_ctx: &UI, title: &str) -> Group {
         let mut group = unsafe {
-            let c_string = CString::new(title.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(title);
             Group::from_raw(ui_sys::uiNewGroup(c_string.as_ptr()))
         };
         group.set_margined(_ctx, true);
@@ -123,37 +240,40 @@ impl Group {
 
     /// Get a copy of the current group title.
     pub fn title(&self, _ctx: &UI) -> String {
-        unsafe {
-            CStr::from_ptr(ui_sys::uiGroupTitle(self.uiGroup))
-                .to_string_lossy()
-                .into_owned()
-        }
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiGroupTitle(self.uiGroup)) }
     }
 
     /// Get a reference to the existing group title.
+    ///
+    /// libui returns a fresh copy of the text each time, which this never frees.
+    #[deprecated(note = "use `title`, which does not leak the string libui returns")]
     pub fn title_ref(&self, _ctx: &UI) -> &CStr {
         unsafe { CStr::from_ptr(ui_sys::uiGroupTitle(self.uiGroup)) }
     }
 
-    // Set the group's title.
+    /// Set the group's title.
     pub fn set_title(&mut self, _ctx: &UI, title: &str) {
         unsafe {
-            let c_string = CString::new(title.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(title);
             ui_sys::uiGroupSetTitle(self.uiGroup, c_string.as_ptr())
         }
     }
 
-    // Set the group's child widget.
+    /// Set the group's child widget, which is shown inside its frame. The group can only have
+    /// one child at a time; to show several controls, put them in a box or grid first.
     pub fn set_child<T: Into<Control>>(&mut self, _ctx: &UI, child: T) {
-        unsafe { ui_sys::uiGroupSetChild(self.uiGroup, child.into().ui_control) }
+        let child = child.into().ui_control;
+        unsafe { ui_sys::uiGroupSetChild(self.uiGroup, child) }
+        disown_all(self.uiGroup as *mut uiControl);
+        adopt(self.uiGroup as *mut uiControl, child, None);
     }
 
-    // Check whether or not the group draws a margin.
+    /// Check whether or not the group draws a margin.
     pub fn margined(&self, _ctx: &UI) -> bool {
         unsafe { ui_sys::uiGroupMargined(self.uiGroup) != 0 }
     }
 
-    // Set whether or not the group draws a margin.
+    /// Set whether or not the group draws a margin. Groups are margined by default.
     pub fn set_margined(&mut self, _ctx: &UI, margined: bool) {
         unsafe { ui_sys::uiGroupSetMargined(self.uiGroup, margined as c_int) }
     }
@@ -171,74 +291,177 @@ impl TabGroup {
     pub fn append<T: Into<Control>>(&mut self, _ctx: &UI, name: &str, control: T) -> u64 {
         let control = control.into();
         unsafe {
-            let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(name);
             ui_sys::uiTabAppend(self.uiTab, c_string.as_ptr(), control.ui_control);
-            ui_sys::uiTabNumPages(self.uiTab) as u64
         }
+        adopt(self.uiTab as *mut uiControl, control.ui_control, None);
+        self.num_pages(_ctx)
     }
 
     /// Add the given control before the given index in the tab group, as a new tab with a given name.
+    /// An index equal to the number of tabs adds the tab at the end.
     ///
-    /// Returns the number of tabs in the group after adding the new tab.
+    /// Returns the number of tabs in the group after adding the new tab, or an error if that
+    /// index was out of bounds.
     pub fn insert_at<T: Into<Control>>(
         &mut self,
         _ctx: &UI,
         name: &str,
         before: u64,
         control: T,
-    ) -> u64 {
+    ) -> Result<u64, UIError> {
+        let n = self.num_pages(_ctx);
+        if before > n {
+            return Err(UIError::TabGroupIndexOutOfBounds { index: before, n });
+        }
+        let control = control.into().ui_control;
         unsafe {
-            let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
-            ui_sys::uiTabInsertAt(
-                self.uiTab,
-                c_string.as_ptr(),
-                before,
-                control.into().ui_control,
-            );
-            ui_sys::uiTabNumPages(self.uiTab) as u64
+            let c_string = ffi_tools::to_c_string(name);
+            ui_sys::uiTabInsertAt(self.uiTab, c_string.as_ptr(), before as c_int, control);
         }
+        adopt(self.uiTab as *mut uiControl, control, Some(before as usize));
+        Ok(self.num_pages(_ctx))
     }
 
-    /// Remove the control at the given index in the tab group.
+    /// Remove the control at the given index in the tab group. The tabs after it move down by
+    /// one index.
     ///
     /// Returns the number of tabs in the group after removing the tab, or an error if that index was out of bounds.
     ///
-    /// NOTE: This will leak the deleted control! We have no way of actually getting it
-    /// to decrement its reference count per `libui`'s UI as of today, unless we maintain a
-    /// separate list of children ourselves…
+    /// The control shown in the removed tab is not destroyed: it no longer has a parent, and
+    /// can be added somewhere else. If it is not, it is leaked, as `libui` gives us no way to
+    /// destroy it along with the tab.
     pub fn delete(&mut self, _ctx: &UI, index: u64) -> Result<u64, UIError> {
-        let n = unsafe { ui_sys::uiTabNumPages(self.uiTab) as u64 };
+        let n = self.num_pages(_ctx);
         if index < n {
-            unsafe { ui_sys::uiTabDelete(self.uiTab, index) };
-            Ok(n)
+            unsafe { ui_sys::uiTabDelete(self.uiTab, index as c_int) };
+            disown(self.uiTab as *mut uiControl, index as usize);
+            Ok(self.num_pages(_ctx))
         } else {
-            Err(UIError::TabGroupIndexOutOfBounds { index: index, n: n })
+            Err(UIError::TabGroupIndexOutOfBounds { index, n })
+        }
+    }
+
+    /// Returns the number of tabs in the group.
+    pub fn num_pages(&self, _ctx: &UI) -> u64 {
+        unsafe { ui_sys::uiTabNumPages(self.uiTab) as u64 }
+    }
+
+    /// Returns the index of the selected tab, or `None` if the group has no tabs.
+    pub fn selected(&self, _ctx: &UI) -> Option<u64> {
+        let index = unsafe { ui_sys::uiTabSelected(self.uiTab) };
+        if index < 0 {
+            None
+        } else {
+            Some(index as u64)
+        }
+    }
+
+    /// Switch to the tab at the given index, running the [`on_selected`](#method.on_selected)
+    /// callback if that is not already the selected tab.
+    ///
+    /// Returns an error if that index is out of bounds.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Label, TabGroup};
+    /// # let ui = UI::init().unwrap();
+    /// let mut tabs = TabGroup::new(&ui);
+    /// tabs.append(&ui, "General", Label::new(&ui, "General settings"));
+    /// tabs.append(&ui, "Advanced", Label::new(&ui, "Advanced settings"));
+    /// tabs.on_selected(&ui, |index| println!("Showing tab {}", index));
+    ///
+    /// tabs.set_selected(&ui, 1).unwrap();
+    /// assert_eq!(tabs.selected(&ui), Some(1));
+    /// assert!(tabs.set_selected(&ui, 2).is_err());
+    /// ```
+    pub fn set_selected(&mut self, _ctx: &UI, index: u64) -> Result<(), UIError> {
+        let n = self.num_pages(_ctx);
+        if index >= n {
+            return Err(UIError::TabGroupIndexOutOfBounds { index, n });
+        }
+        if self.selected(_ctx) != Some(index) {
+            unsafe {
+                ui_sys::uiTabSetSelected(self.uiTab, index as c_int);
+                // libui only runs the callback for the user's clicks.
+                let callback =
+                    ffi_tools::registered_callback::<_, Box<dyn FnMut(u64)>>(self.uiTab, "selected");
+                if let Some(callback) = callback {
+                    callback(index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set a callback to be run with the index of the newly selected tab when the user switches
+    /// to another tab, or when [`set_selected`](#method.set_selected) does. Setting a new
+    /// callback replaces the previous one.
+    pub fn on_selected<F: FnMut(u64) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: Box<dyn FnMut(u64)> = Box::new(callback);
+            ui_sys::uiTabOnSelected(
+                self.uiTab,
+                c_callback,
+                ffi_tools::register_callback(self.uiTab, "selected", data),
+            );
+        }
+
+        extern "C" fn c_callback(tab: *mut uiTab, data: *mut c_void) {
+            unsafe {
+                let val = ui_sys::uiTabSelected(tab) as u64;
+                (*(data as *mut Box<dyn FnMut(u64)>))(val);
+            }
         }
     }
 
     /// Determine whether or not the tab group provides margins around its children.
     pub fn margined(&self, _ctx: &UI, page: u64) -> bool {
-        unsafe { ui_sys::uiTabMargined(self.uiTab, page) != 0 }
+        unsafe { ui_sys::uiTabMargined(self.uiTab, page as c_int) != 0 }
     }
 
     /// Set whether or not the tab group provides margins around its children.
     pub fn set_margined(&mut self, _ctx: &UI, page: u64, margined: bool) {
-        unsafe { ui_sys::uiTabSetMargined(self.uiTab, page, margined as c_int) }
+        unsafe { ui_sys::uiTabSetMargined(self.uiTab, page as c_int, margined as c_int) }
     }
 }
 
 define_control!{
-    /// Horizontal line, to seperate things visually.
+    /// Horizontal line, to seperate things visually, e.g. the sections of a `VerticalBox`.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{HorizontalSeparator, Label, VerticalBox};
+    /// # let ui = UI::init().unwrap();
+    /// let mut vbox = VerticalBox::new(&ui);
+    /// vbox.append(&ui, Label::new(&ui, "General"), LayoutStrategy::Compact);
+    /// vbox.append(&ui, HorizontalSeparator::new(&ui), LayoutStrategy::Compact);
+    /// vbox.append(&ui, Label::new(&ui, "Advanced"), LayoutStrategy::Compact);
+    /// ```
     rust_type: HorizontalSeparator,
     sys_type: uiSeparator
 }
 
 impl HorizontalSeparator {
+    /// Create a new horizontal separator.
     pub fn new(_ctx: &UI) -> Self {
         unsafe { HorizontalSeparator::from_raw(ui_sys::uiNewHorizontalSeparator()) }
     }
 }
 
+define_control!{
+    /// Vertical line, to seperate things visually, e.g. the sections of a `HorizontalBox`.
+    rust_type: VerticalSeparator,
+    sys_type: uiSeparator
+}
+
+impl VerticalSeparator {
+    /// Create a new vertical separator.
+    pub fn new(_ctx: &UI) -> Self {
+        unsafe { VerticalSeparator::from_raw(ui_sys::uiNewVerticalSeparator()) }
+    }
+}
+
 define_control! {
     /// Seperates components with empty space.
     rust_type: Spacer,
@@ -331,11 +554,7 @@ impl LayoutGrid {
     
     /// Returns `true` if the `LayoutGrid` is padded and `false` if not.
     pub fn padded(&self, _ctx: &UI) -> bool {
-        if unsafe { ui_sys::uiGridPadded(self.uiGrid) } == 0 {
-            true
-        } else {
-            false
-        }
+        unsafe { ui_sys::uiGridPadded(self.uiGrid) != 0 }
     }
 
     /// Sets the padding state of the `LayoutGrid`
@@ -348,8 +567,33 @@ impl LayoutGrid {
     }
 
     /// Adds a control to the `LayoutGrid`.
+    ///
+    /// The control's top-left cell is at column `left` and row `top`, and it covers `xspan`
+    /// columns and `yspan` rows from there. `expand` decides whether the control's columns and
+    /// rows take a share of any extra space, and `halign` and `valign` position the control in
+    /// the area it covers.
+    ///
+    /// For instance, a keypad whose "0" key is twice as wide as the others:
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Button, GridAlignment, GridExpand, LayoutGrid};
+    /// let ui = UI::init().unwrap();
+    /// let mut grid = LayoutGrid::new(&ui);
+    /// for (i, key) in ["1", "2", "3"].iter().enumerate() {
+    ///     grid.append(&ui, Button::new(&ui, key), i as i32, 0, 1, 1,
+    ///                 GridExpand::Both, GridAlignment::Fill, GridAlignment::Fill);
+    /// }
+    /// grid.append(&ui, Button::new(&ui, "0"), 0, 1, 2, 1,
+    ///             GridExpand::Both, GridAlignment::Fill, GridAlignment::Fill);
+    /// grid.append(&ui, Button::new(&ui, "."), 2, 1, 1, 1,
+    ///             GridExpand::Both, GridAlignment::Fill, GridAlignment::Fill);
+    ///
+    /// let mut window = Window::new(&ui, "Keypad", 200, 200, WindowType::NoMenubar);
+    /// window.set_child(&ui, grid);
+    /// ```
     pub fn append<T: Into<Control>>(&mut self, _ctx: &UI, control: T,
-                                    left: i32, height: i32,
+                                    left: i32, top: i32,
                                     xspan: i32, yspan: i32,
                                     expand: GridExpand,
                                     halign: GridAlignment, valign: GridAlignment) {
@@ -359,18 +603,22 @@ impl LayoutGrid {
             GridExpand::Vertical => (0, 1),
             GridExpand::Both => (1, 1),
         };
+        let control = control.into().ui_control;
         unsafe { 
             ui_sys::uiGridAppend(
-                self.uiGrid, control.into().ui_control, left, height, xspan, yspan,
+                self.uiGrid, control, left, top, xspan, yspan,
                 hexpand, halign.into_ui_align(), vexpand, valign.into_ui_align()
             );
-        }    
+        }
+        adopt(self.uiGrid as *mut uiControl, control, None);
     }
 
-    /// Inserts a control in to the `LayoutGrid` relative to an existing control.
+    /// Inserts a control in to the `LayoutGrid` relative to an existing control, on the side
+    /// given by `at`. The other parameters are the same as for
+    /// [`append`](struct.LayoutGrid.html#method.append).
     pub fn insert_at<T: Into<Control>, U: Into<Control>>(&mut self, _ctx: &UI, 
                                     control: T, existing:U, at: GridInsertionStrategy,
-                                    left: i32, height: i32,
+                                    left: i32, top: i32,
                                     xspan: i32, yspan: i32,
                                     expand: GridExpand,
                                     halign: GridAlignment, valign: GridAlignment){
@@ -381,12 +629,14 @@ impl LayoutGrid {
             GridExpand::Vertical => (0, 1),
             GridExpand::Both => (1, 1),
         };
+        let control = control.into().ui_control;
         unsafe {
             ui_sys::uiGridInsertAt(
-                self.uiGrid, control.into().ui_control, existing.into().ui_control,
-                at.into_ui_at(), left, height, xspan, yspan,
+                self.uiGrid, control, existing.into().ui_control,
+                at.into_ui_at(), left, top, xspan, yspan,
                 hexpand, halign.into_ui_align(), vexpand, valign.into_ui_align()
             );
         }
+        adopt(self.uiGrid as *mut uiControl, control, None);
     } 
 }
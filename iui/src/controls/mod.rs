@@ -1,10 +1,16 @@
 //! Available user interface controls and related functionality.
 //! 
 //! Note that `Control` and all specific control types are references to memory which is owned by the UI library.
+//! Windows own the controls inside them, and are destroyed when their last handle is dropped;
+//! see [`Window`](struct.Window.html#ownership).
 
+use ffi_tools;
 use ui::UI;
 use ui_sys::{self, uiControl};
 
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ptr;
 
 #[macro_use]
@@ -17,6 +23,105 @@ mod layout;
 pub use self::layout::*;
 mod entry;
 pub use self::entry::*;
+mod area;
+pub use self::area::*;
+mod table;
+pub use self::table::*;
+mod token;
+pub use self::token::*;
+mod accessibility;
+mod caret;
+mod file_drop;
+mod icon;
+mod screen;
+
+thread_local! {
+    // The children of each container, in order, as `libui` has no way to list them. Destroying
+    // a control destroys its children too, so their state has to be forgotten along with its.
+    static CHILDREN: RefCell<HashMap<usize, Vec<usize>>> = RefCell::new(HashMap::new());
+}
+
+/// Records that `child` was added to `parent`, before the child at `index` or at the end.
+pub(crate) fn adopt(parent: *mut uiControl, child: *mut uiControl, index: Option<usize>) {
+    detach(child);
+    CHILDREN.with(|children| {
+        let mut children = children.borrow_mut();
+        let siblings = children.entry(parent as usize).or_default();
+        match index {
+            Some(index) if index < siblings.len() => siblings.insert(index, child as usize),
+            _ => siblings.push(child as usize),
+        }
+    });
+}
+
+/// Records that the child of `parent` at `index` was removed from it.
+pub(crate) fn disown(parent: *mut uiControl, index: usize) {
+    CHILDREN.with(|children| {
+        if let Some(siblings) = children.borrow_mut().get_mut(&(parent as usize)) {
+            if index < siblings.len() {
+                siblings.remove(index);
+            }
+        }
+    });
+}
+
+/// Records that every child of `parent` was removed from it.
+pub(crate) fn disown_all(parent: *mut uiControl) {
+    CHILDREN.with(|children| children.borrow_mut().remove(&(parent as usize)));
+}
+
+// Removes `control` from the children of its parent.
+fn detach(control: *mut uiControl) {
+    CHILDREN.with(|children| {
+        for siblings in children.borrow_mut().values_mut() {
+            siblings.retain(|&child| child != control as usize);
+        }
+    });
+}
+
+// Forgets the state kept for `control` itself, but not for its children.
+fn forget_state(control: *mut uiControl) {
+    ffi_tools::unregister_callbacks(control);
+    entry::forget_entry_state(control);
+    area::forget_area_state(control);
+    token::forget_tokens_of(control);
+}
+
+/// Forgets the state kept for `control` and every control in it, as they are about to be
+/// destroyed. Returns all of them.
+pub(crate) fn forget_tree(control: *mut uiControl) -> Vec<*mut uiControl> {
+    detach(control);
+    let mut forgotten = Vec::new();
+    let mut pending = vec![control as usize];
+    while let Some(control) = pending.pop() {
+        let children = CHILDREN.with(|children| children.borrow_mut().remove(&control));
+        pending.extend(children.unwrap_or_default());
+        forget_state(control as *mut uiControl);
+        forgotten.push(control as *mut uiControl);
+    }
+    forgotten
+}
+
+/// Destroys `control` and every control in it, forgetting their state, then frees what `libui`
+/// only lets go of once they are gone.
+pub(crate) unsafe fn destroy_tree(control: *mut uiControl) {
+    let destroyed = forget_tree(control);
+    ui_sys::uiControlDestroy(control);
+    table::free_table_models(&destroyed);
+}
+
+/// Forgets the state kept for a control which was just created, in case a control destroyed
+/// without going through `forget_tree` had the same address.
+pub(crate) fn forget_control(control: *mut uiControl) {
+    detach(control);
+    disown_all(control);
+    forget_state(control);
+}
+
+/// Forgets the children of every container. Called when the UI is torn down.
+pub(crate) fn clear_children() {
+    CHILDREN.with(|children| children.borrow_mut().clear());
+}
 
 /// A generic UI control. Any UI control can be turned into this type.
 /// 
@@ -41,6 +146,163 @@ impl Clone for Control {
     }
 }
 
+/// Operations shared by every control, so that generic code can manage controls of any type.
+///
+/// Every control type in this crate implements this trait, as does `Control` itself.
+///
+/// ```
+/// # use iui::prelude::*;
+/// # use iui::controls::{Button, Entry, Label};
+/// # let ui = UI::init().unwrap();
+/// let mut controls: Vec<Box<dyn ControlExt>> = vec![
+///     Box::new(Label::new(&ui, "Name:")),
+///     Box::new(Entry::new(&ui)),
+///     Box::new(Button::new(&ui, "Save")),
+/// ];
+/// for control in controls.iter_mut() {
+///     control.disable(&ui);
+/// }
+/// assert!(controls.iter().all(|control| !control.enabled(&ui)));
+/// ```
+///
+/// A `dyn ControlExt` can be turned back into its concrete type with
+/// [`downcast_ref`](#method.downcast_ref) or [`downcast_mut`](#method.downcast_mut).
+///
+/// # Safety
+/// Implementors must return a pointer to a live `uiControl` from `as_ui_control`, one which has
+/// not been destroyed and stays valid for as long as the implementor does, since the other
+/// methods pass it straight to `libui`. `as_any` and `as_any_mut` must return the implementor
+/// itself, so that downcasting finds the control `as_ui_control` points to.
+pub unsafe trait ControlExt {
+    /// Returns the underlying `*mut uiControl`.
+    fn as_ui_control(&self) -> *mut uiControl;
+
+    /// Returns this control as an `Any`, to check its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns this control as a mutable `Any`, to check its concrete type.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Show this control to the user. This will also show its non-hidden children.
+    fn show(&mut self, _ctx: &UI) {
+        unsafe { ui_sys::uiControlShow(self.as_ui_control()) }
+    }
+
+    /// Hide this control from the user. This will hide its children.
+    fn hide(&mut self, _ctx: &UI) {
+        unsafe { ui_sys::uiControlHide(self.as_ui_control()) }
+    }
+
+    /// Returns true if this control is set to be shown. A control can be set to be shown but
+    /// still be invisible because its parent is hidden.
+    fn visible(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiControlVisible(self.as_ui_control()) != 0 }
+    }
+
+    /// Let the user interact with this control again.
+    ///
+    /// A control inside a disabled container stays unusable until the container is enabled too.
+    fn enable(&mut self, _ctx: &UI) {
+        unsafe { ui_sys::uiControlEnable(self.as_ui_control()) }
+    }
+
+    /// Stop the user from interacting with this control, and show it as such. Disabling a
+    /// container also disables all of its descendants, without changing their own state.
+    fn disable(&mut self, _ctx: &UI) {
+        unsafe { ui_sys::uiControlDisable(self.as_ui_control()) }
+    }
+
+    /// Returns true if this control itself is enabled. This does not take its parents into
+    /// account, so a control inside a disabled container might still report being enabled.
+    fn enabled(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiControlEnabled(self.as_ui_control()) != 0 }
+    }
+
+    /// Set the name screen readers announce for this control, in place of its visible text,
+    /// for instance to name a button showing only a symbol.
+    ///
+    /// `libui` has no accessibility functions, so the name is given to the native toolkit:
+    /// to ATK with GTK+, as a Microsoft Active Accessibility property on Windows, and as the
+    /// accessibility label of the view on macOS. Elsewhere, this does nothing. For a control
+    /// which `libui` builds out of several native widgets, like a box, the name is given to
+    /// the outermost one.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Button;
+    /// # let ui = UI::init().unwrap();
+    /// let mut add = Button::new(&ui, "+");
+    /// add.set_accessible_name(&ui, "Add item");
+    /// add.set_accessible_description(&ui, "Adds an empty item to the end of the list");
+    /// ```
+    fn set_accessible_name(&mut self, _ctx: &UI, name: &str) {
+        unsafe {
+            let handle = ui_sys::uiControlHandle(self.as_ui_control());
+            accessibility::set_property(handle, accessibility::Property::Name, name)
+        }
+    }
+
+    /// Set the description screen readers give for this control, on top of its name. It is
+    /// given to the native toolkit like [`set_accessible_name`](#method.set_accessible_name),
+    /// as the help of the view on macOS, and does nothing on other platforms.
+    fn set_accessible_description(&mut self, _ctx: &UI, description: &str) {
+        unsafe {
+            let handle = ui_sys::uiControlHandle(self.as_ui_control());
+            accessibility::set_property(handle, accessibility::Property::Description, description)
+        }
+    }
+}
+
+unsafe impl ControlExt for Control {
+    fn as_ui_control(&self) -> *mut uiControl {
+        self.ui_control
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl dyn ControlExt {
+    /// Returns true if this control was created as a `T`.
+    ///
+    /// This checks the Rust type the control was stored as, not the `libui` one: a control stored
+    /// as a generic `Control` is never anything else, even if it is actually an entry.
+    pub fn is<T: ControlExt + 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Returns a reference to this control as a `T`, or `None` if it is of another type.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Entry, Label};
+    /// # let ui = UI::init().unwrap();
+    /// let mut entry = Entry::new(&ui);
+    /// entry.set_value(&ui, "Ferris");
+    /// let controls: Vec<Box<dyn ControlExt>> = vec![
+    ///     Box::new(Label::new(&ui, "Name:")),
+    ///     Box::new(entry),
+    /// ];
+    ///
+    /// assert!(controls[0].downcast_ref::<Entry>().is_none());
+    /// let entry = controls[1].downcast_ref::<Entry>().unwrap();
+    /// assert_eq!(entry.value(&ui), "Ferris");
+    /// ```
+    pub fn downcast_ref<T: ControlExt + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Returns a mutable reference to this control as a `T`, or `None` if it is of another type.
+    pub fn downcast_mut<T: ControlExt + 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut::<T>()
+    }
+}
+
 impl Control {
     /// Creates a new `Control` object from an existing `*mut uiControl`.
     pub unsafe fn from_ui_control(ui_control: *mut uiControl) -> Control {
@@ -58,7 +320,7 @@ impl Control {
     /// is marked unsafe.
     pub unsafe fn destroy(&self) {
         // Don't check for initialization here since this can be run during deinitialization.
-        ui_sys::uiControlDestroy(self.ui_control)
+        destroy_tree(self.ui_control)
     }
 }
 
@@ -79,13 +341,17 @@ impl UI {
     /// the UI tree or, if passed `None`, removing it from the tree.
     // TODO: Does this actually need to be unsafe? I don't really see why it is.
     pub unsafe fn set_parent_of<T: Into<Control>>(&mut self, control: T, parent: Option<T>) {
-        ui_sys::uiControlSetParent(
-            control.into().ui_control,
-            match parent {
-                None => ptr::null_mut(),
-                Some(parent) => parent.into().ui_control,
-            },
-        )
+        let control = control.into().ui_control;
+        let parent = match parent {
+            None => ptr::null_mut(),
+            Some(parent) => parent.into().ui_control,
+        };
+        ui_sys::uiControlSetParent(control, parent);
+        if parent.is_null() {
+            detach(control);
+        } else {
+            adopt(parent, control, None);
+        }
     }
 
     /// Returns true if this control is a top-level control; the root of
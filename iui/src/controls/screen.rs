@@ -0,0 +1,167 @@
+//! Placing windows on the screen through the native toolkit, as `libui` cannot tell which
+//! display a window is on, or how large it is.
+
+use error::UIError;
+use ui_sys::uiWindow;
+
+/// Moves `window`, whose native window is `handle`, as returned by `uiControlHandle`, to the
+/// center of the part of its display that windows can be placed in.
+#[cfg(any(all(unix, not(target_os = "macos")), windows))]
+pub(crate) unsafe fn center_window(handle: usize, window: *mut uiWindow) -> Result<(), UIError> {
+    use libc::c_int;
+    use ui_sys;
+
+    let (x, y, width, height) = work_area(handle).ok_or(UIError::Unsupported {
+        operation: "finding the display of this window",
+    })?;
+    let (mut content_width, mut content_height): (c_int, c_int) = (0, 0);
+    ui_sys::uiWindowContentSize(window, &mut content_width, &mut content_height);
+    // The frame is left out of the size, so the window is only off by its borders and title
+    // bar; windows larger than the display keep their top-left corner on it.
+    ui_sys::uiWindowSetPosition(
+        window,
+        x + ((width - content_width) / 2).max(0),
+        y + ((height - content_height) / 2).max(0),
+    );
+    Ok(())
+}
+
+/// Returns the part of the display showing the native window `handle` that windows can be
+/// placed in, leaving out panels and docks, as `(x, y, width, height)`.
+#[cfg(all(unix, not(target_os = "macos")))]
+unsafe fn work_area(handle: usize) -> Option<(i32, i32, i32, i32)> {
+    use std::ptr;
+
+    let display = gtk::gdk_display_get_default();
+    if display.is_null() {
+        return None;
+    }
+    // Windows which have never been shown are not on any display yet.
+    let gdk_window = gtk::gtk_widget_get_window(handle as *mut _);
+    let mut monitor = if gdk_window.is_null() {
+        ptr::null_mut()
+    } else {
+        gtk::gdk_display_get_monitor_at_window(display, gdk_window)
+    };
+    if monitor.is_null() {
+        monitor = gtk::gdk_display_get_primary_monitor(display);
+    }
+    if monitor.is_null() {
+        monitor = gtk::gdk_display_get_monitor(display, 0);
+    }
+    if monitor.is_null() {
+        return None;
+    }
+    let mut area = gtk::GdkRectangle {
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+    };
+    gtk::gdk_monitor_get_workarea(monitor, &mut area);
+    Some((area.x, area.y, area.width, area.height))
+}
+
+#[cfg(windows)]
+unsafe fn work_area(handle: usize) -> Option<(i32, i32, i32, i32)> {
+    use std::mem;
+
+    let monitor = win32::MonitorFromWindow(handle as *mut _, win32::MONITOR_DEFAULTTONEAREST);
+    if monitor.is_null() {
+        return None;
+    }
+    let mut info: win32::MONITORINFO = mem::zeroed();
+    info.cbSize = mem::size_of::<win32::MONITORINFO>() as u32;
+    if win32::GetMonitorInfoW(monitor, &mut info) == 0 {
+        return None;
+    }
+    let work = info.rcWork;
+    Some((work.left, work.top, work.right - work.left, work.bottom - work.top))
+}
+
+// macOS measures positions from the bottom of the screen, and can center windows itself.
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn center_window(handle: usize, _window: *mut uiWindow) -> Result<(), UIError> {
+    use libc::{c_char, c_void};
+    use std::mem;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    // `objc_msgSend` has to be called through a pointer of the method's own type.
+    let send: extern "C" fn(*mut c_void, *mut c_void) =
+        mem::transmute(objc_msgSend as *const c_void);
+    send(
+        handle as *mut c_void,
+        sel_registerName(b"center\0".as_ptr() as *const c_char),
+    );
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) unsafe fn center_window(_handle: usize, _window: *mut uiWindow) -> Result<(), UIError> {
+    Err(UIError::Unsupported {
+        operation: "centering a window on this platform",
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod gtk {
+    use libc::{c_int, c_void};
+
+    #[repr(C)]
+    pub struct GdkRectangle {
+        pub x: c_int,
+        pub y: c_int,
+        pub width: c_int,
+        pub height: c_int,
+    }
+
+    #[link(name = "gtk-3")]
+    #[link(name = "gdk-3")]
+    extern "C" {
+        pub fn gtk_widget_get_window(widget: *mut c_void) -> *mut c_void;
+        pub fn gdk_display_get_default() -> *mut c_void;
+        pub fn gdk_display_get_monitor_at_window(
+            display: *mut c_void,
+            window: *mut c_void,
+        ) -> *mut c_void;
+        pub fn gdk_display_get_primary_monitor(display: *mut c_void) -> *mut c_void;
+        pub fn gdk_display_get_monitor(display: *mut c_void, monitor: c_int) -> *mut c_void;
+        pub fn gdk_monitor_get_workarea(monitor: *mut c_void, workarea: *mut GdkRectangle);
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+mod win32 {
+    use libc::{c_int, c_void};
+
+    pub const MONITOR_DEFAULTTONEAREST: u32 = 0x0000_0002;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct RECT {
+        pub left: i32,
+        pub top: i32,
+        pub right: i32,
+        pub bottom: i32,
+    }
+
+    #[repr(C)]
+    pub struct MONITORINFO {
+        pub cbSize: u32,
+        pub rcMonitor: RECT,
+        pub rcWork: RECT,
+        pub dwFlags: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn MonitorFromWindow(hwnd: *mut c_void, flags: u32) -> *mut c_void;
+        pub fn GetMonitorInfoW(monitor: *mut c_void, info: *mut MONITORINFO) -> c_int;
+    }
+}
@@ -0,0 +1,803 @@
+//! Tables showing rows of data provided by a model.
+
+use super::{forget_control, Control};
+use error::UIError;
+use ffi_tools;
+use libc::{c_int, c_void};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::mem;
+use std::ptr;
+use std::slice;
+use ui::UI;
+use ui_sys::{
+    self, uiControl, uiImage, uiTable, uiTableModel, uiTableModelHandler, uiTableValue,
+    uiTableValueType,
+};
+use util_types::Color;
+
+/// How many rows of a table the user can select: `None`, `ZeroOrOne`, exactly `One`, or
+/// `ZeroOrMany`. Tables allow `ZeroOrOne` by default.
+pub use ui_sys::uiTableSelectionMode as TableSelectionMode;
+pub use ui_sys::uiTableValueType as TableValueType;
+
+thread_local! {
+    // The native model of each `Table`, and the handler it calls, which must outlive the table.
+    static TABLE_MODELS: RefCell<HashMap<*mut uiTable, TableModelState>> = RefCell::new(HashMap::new());
+    // Every `Image` created, which tables may show at any time.
+    static IMAGES: RefCell<Vec<*mut uiImage>> = const { RefCell::new(Vec::new()) };
+    // A copy of the bitmaps of each `Image`, as libui gives no way to read them back.
+    static IMAGE_BITMAPS: RefCell<HashMap<*mut uiImage, Vec<Bitmap>>> = RefCell::new(HashMap::new())
+}
+
+/// One of the bitmaps of an `Image`, with 4 bytes of non-premultiplied RGBA per pixel.
+pub(crate) struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// A callback given to `Table::on_selection_changed`.
+type SelectionChangedCallback = Box<dyn FnMut(&[i32])>;
+
+struct TableModelState {
+    ui_table_model: *mut uiTableModel,
+    // Kept alive here, as the native model points to it.
+    handler: Box<RustTableModelHandler>,
+}
+
+/// Frees the models of those of `controls` which are tables, once they have been destroyed.
+pub(crate) unsafe fn free_table_models(controls: &[*mut uiControl]) {
+    let models: Vec<TableModelState> = TABLE_MODELS.with(|models| {
+        let mut models = models.borrow_mut();
+        controls
+            .iter()
+            .filter_map(|&control| models.remove(&(control as *mut uiTable)))
+            .collect()
+    });
+    for state in models {
+        ui_sys::uiFreeTableModel(state.ui_table_model);
+    }
+}
+
+/// Frees the models of the tables and the images they show. Called when the UI is torn down,
+/// once the tables using them have been destroyed.
+pub(crate) unsafe fn clear_table_state() {
+    let models = TABLE_MODELS.with(|models| mem::take(&mut *models.borrow_mut()));
+    for (_, state) in models {
+        ui_sys::uiFreeTableModel(state.ui_table_model);
+    }
+    let images = IMAGES.with(|images| mem::take(&mut *images.borrow_mut()));
+    for image in images {
+        ui_sys::uiFreeImage(image);
+    }
+    IMAGE_BITMAPS.with(|bitmaps| bitmaps.borrow_mut().clear());
+}
+
+/// A picture which can be shown in a table's image columns, as a
+/// [`TableValue::Image`](enum.TableValue.html#variant.Image), or used as the icon of a window
+/// with [`Window::set_icon`](struct.Window.html#method.set_icon).
+///
+/// An image has a size, in points, and holds one or more bitmaps of that picture at different
+/// resolutions, so that the best one can be used on high-DPI displays.
+///
+/// Like controls, images are references to memory owned by the UI library: cloning one does
+/// not copy it, and it stays alive until the `UI` is dropped. Images should therefore be
+/// created once and reused, rather than created each time a model is asked for a cell.
+///
+/// ```
+/// # use iui::prelude::*;
+/// # use iui::controls::Image;
+/// # let ui = UI::init().unwrap();
+/// // A 16x16 point red square, with a bitmap for normal displays and one for 2x displays.
+/// let mut image = Image::from_rgba(&ui, 16, 16, &[255, 0, 0, 255].repeat(16 * 16), 1.0).unwrap();
+/// image.append(&ui, 32, 32, &[255, 0, 0, 255].repeat(32 * 32)).unwrap();
+///
+/// // The data must hold 4 bytes for each pixel.
+/// assert!(image.append(&ui, 32, 32, &[255, 0, 0, 255]).is_err());
+/// // And the image must not be too large for libui.
+/// assert!(Image::from_rgba(&ui, u32::max_value(), 1, &[], 1.0).is_err());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Image {
+    ui_image: *mut uiImage,
+}
+
+impl Image {
+    /// Create an image of the given size, in points, with no bitmaps yet. At least one must be
+    /// appended before the image is shown.
+    ///
+    /// Fails with [`NativeCallFailed`](../enum.UIError.html#variant.NativeCallFailed) if
+    /// `libui` could not create the image.
+    pub fn new(_ctx: &UI, width: f64, height: f64) -> Result<Image, UIError> {
+        let ui_image = unsafe { ui_sys::uiNewImage(width, height) };
+        if ui_image.is_null() {
+            return Err(UIError::NativeCallFailed { function: "uiNewImage" });
+        }
+        IMAGES.with(|images| images.borrow_mut().push(ui_image));
+        Ok(Image { ui_image })
+    }
+
+    /// Create an image from the given bitmap, whose `scale` is its number of pixels per point:
+    /// 1.0 for normal displays, 2.0 for most high-DPI ones.
+    ///
+    /// `pixels` holds the red, green, blue and alpha components, not premultiplied, of each
+    /// pixel, row by row from the top left; its length must therefore be
+    /// `pixel_width * pixel_height * 4`, or this fails with
+    /// [`ImageSizeMismatch`](../enum.UIError.html#variant.ImageSizeMismatch). Bitmaps too wide
+    /// or tall for `libui`, whose rows of bytes must fit in an `i32`, fail with
+    /// [`ImageTooLarge`](../enum.UIError.html#variant.ImageTooLarge).
+    pub fn from_rgba(
+        ctx: &UI,
+        pixel_width: u32,
+        pixel_height: u32,
+        pixels: &[u8],
+        scale: f64,
+    ) -> Result<Image, UIError> {
+        check_image_data(pixel_width, pixel_height, pixels)?;
+        let mut image = Image::new(ctx, pixel_width as f64 / scale, pixel_height as f64 / scale)?;
+        image.append(ctx, pixel_width, pixel_height, pixels)?;
+        Ok(image)
+    }
+
+    /// Add a bitmap of this image at another resolution. `pixels` is laid out as for
+    /// [`from_rgba`](#method.from_rgba), and must be `pixel_width * pixel_height * 4` bytes long.
+    pub fn append(
+        &mut self,
+        _ctx: &UI,
+        pixel_width: u32,
+        pixel_height: u32,
+        pixels: &[u8],
+    ) -> Result<(), UIError> {
+        check_image_data(pixel_width, pixel_height, pixels)?;
+        unsafe {
+            // libUI copies the pixels, despite taking them mutably.
+            ui_sys::uiImageAppend(
+                self.ui_image,
+                pixels.as_ptr() as *mut c_void,
+                pixel_width as c_int,
+                pixel_height as c_int,
+                pixel_width as c_int * 4,
+            )
+        }
+        IMAGE_BITMAPS.with(|bitmaps| {
+            bitmaps
+                .borrow_mut()
+                .entry(self.ui_image)
+                .or_insert_with(Vec::new)
+                .push(Bitmap {
+                    width: pixel_width,
+                    height: pixel_height,
+                    pixels: pixels.to_vec(),
+                })
+        });
+        Ok(())
+    }
+
+    /// Calls `f` with the bitmaps appended to this image so far.
+    pub(crate) fn with_bitmaps<R, F: FnOnce(&[Bitmap]) -> R>(&self, f: F) -> R {
+        IMAGE_BITMAPS.with(|bitmaps| match bitmaps.borrow().get(&self.ui_image) {
+            Some(bitmaps) => f(bitmaps),
+            None => f(&[]),
+        })
+    }
+
+    /// Return the underlying pointer for this image.
+    pub fn ptr(&self) -> *mut uiImage {
+        self.ui_image
+    }
+}
+
+fn check_image_data(pixel_width: u32, pixel_height: u32, pixels: &[u8]) -> Result<(), UIError> {
+    // libUI takes the size and the length of a row as `int`s.
+    if pixel_width > (i32::MAX / 4) as u32 || pixel_height > i32::MAX as u32 {
+        return Err(UIError::ImageTooLarge {
+            width: pixel_width,
+            height: pixel_height,
+        });
+    }
+    let expected = (pixel_width as usize)
+        .checked_mul(pixel_height as usize)
+        .and_then(|n| n.checked_mul(4));
+    if expected == Some(pixels.len()) {
+        Ok(())
+    } else {
+        Err(UIError::ImageSizeMismatch {
+            len: pixels.len(),
+            expected: expected.unwrap_or(usize::MAX),
+        })
+    }
+}
+
+/// The contents of a table cell, as given by and to a [`TableModel`](trait.TableModel.html).
+#[derive(Clone, PartialEq, Debug)]
+pub enum TableValue {
+    String(String),
+    Int(i32),
+    /// An [`Image`](struct.Image.html), for image columns.
+    Image(Image),
+    /// A color, for the text or background color of a column.
+    Color(Color),
+}
+
+impl TableValue {
+    /// Returns the type of this value, as reported by `TableModel::column_type`.
+    pub fn value_type(&self) -> TableValueType {
+        match *self {
+            TableValue::String(_) => TableValueType::String,
+            TableValue::Int(_) => TableValueType::Int,
+            TableValue::Image(_) => TableValueType::Image,
+            TableValue::Color(..) => TableValueType::Color,
+        }
+    }
+
+    /// Create the libUI value for this one. The caller owns it.
+    fn to_ui_table_value(&self) -> *mut uiTableValue {
+        unsafe {
+            match *self {
+                TableValue::String(ref string) => {
+                    let c_string = ffi_tools::to_c_string(string);
+                    ui_sys::uiNewTableValueString(c_string.as_ptr())
+                }
+                TableValue::Int(int) => ui_sys::uiNewTableValueInt(int),
+                TableValue::Image(ref image) => ui_sys::uiNewTableValueImage(image.ui_image),
+                TableValue::Color(color) => {
+                    let Color { r, g, b, a } = color.clamped();
+                    ui_sys::uiNewTableValueColor(r, g, b, a)
+                }
+            }
+        }
+    }
+
+    /// Copy a value given by libUI. The value is not freed.
+    unsafe fn from_ui_table_value(value: *const uiTableValue) -> Option<TableValue> {
+        match ui_sys::uiTableValueGetType(value) {
+            uiTableValueType::String => {
+                let string = CStr::from_ptr(ui_sys::uiTableValueString(value));
+                Some(TableValue::String(string.to_string_lossy().into_owned()))
+            }
+            uiTableValueType::Int => Some(TableValue::Int(ui_sys::uiTableValueInt(value))),
+            uiTableValueType::Color => {
+                let mut color = Color::new(0.0, 0.0, 0.0, 0.0);
+                ui_sys::uiTableValueColor(value, &mut color.r, &mut color.g, &mut color.b, &mut color.a);
+                Some(TableValue::Color(color))
+            }
+            uiTableValueType::Image => Some(TableValue::Image(Image {
+                ui_image: ui_sys::uiTableValueImage(value),
+            })),
+        }
+    }
+}
+
+/// The data shown by a [`Table`](struct.Table.html), organized in rows and columns.
+///
+/// The columns of the model are not the columns shown by the table: each column of the table
+/// reads one or more columns of the model, e.g. one for its text and one for whether that text
+/// can be edited, so the model can hold data which is never shown directly.
+///
+/// The model is called on the GUI thread whenever the table needs to display a cell, and is
+/// dropped when the `UI` is. Data which should also be changed from elsewhere in the
+/// application can be shared with the model through an `Rc<RefCell<_>>`.
+pub trait TableModel {
+    /// Returns the number of columns of the model. It must not change.
+    fn num_columns(&self) -> i32;
+    /// Returns the type of the values in the given column of the model. It must not change.
+    fn column_type(&self, column: i32) -> TableValueType;
+    /// Returns the number of rows of the model.
+    fn num_rows(&self) -> i32;
+    /// Returns the value of the given cell, which must be of the type of its column.
+    fn cell_value(&self, row: i32, column: i32) -> TableValue;
+    /// Called when the user changes the given cell through the table. The default
+    /// implementation ignores the change.
+    fn set_cell_value(&mut self, _row: i32, _column: i32, _value: TableValue) {}
+    /// Called when the user clicks the button of the given row in a button column, `column`
+    /// being the model column holding the button's text. The default implementation does
+    /// nothing.
+    fn button_clicked(&mut self, _row: i32, _column: i32) {}
+}
+
+#[repr(C)]
+struct RustTableModelHandler {
+    ui_table_model_handler: uiTableModelHandler,
+    trait_object: Box<dyn TableModel>,
+}
+
+impl RustTableModelHandler {
+    fn new(_ctx: &UI, trait_object: Box<dyn TableModel>) -> Box<RustTableModelHandler> {
+        return Box::new(RustTableModelHandler {
+            ui_table_model_handler: uiTableModelHandler {
+                NumColumns: num_columns,
+                ColumnType: column_type,
+                NumRows: num_rows,
+                CellValue: cell_value,
+                SetCellValue: set_cell_value,
+            },
+            trait_object,
+        });
+
+        extern "C" fn num_columns(
+            handler: *mut uiTableModelHandler,
+            _model: *mut uiTableModel,
+        ) -> c_int {
+            unsafe { (*(handler as *mut RustTableModelHandler)).trait_object.num_columns() }
+        }
+
+        extern "C" fn column_type(
+            handler: *mut uiTableModelHandler,
+            _model: *mut uiTableModel,
+            column: c_int,
+        ) -> uiTableValueType {
+            unsafe {
+                (*(handler as *mut RustTableModelHandler))
+                    .trait_object
+                    .column_type(column)
+            }
+        }
+
+        extern "C" fn num_rows(handler: *mut uiTableModelHandler, _model: *mut uiTableModel) -> c_int {
+            unsafe { (*(handler as *mut RustTableModelHandler)).trait_object.num_rows() }
+        }
+
+        extern "C" fn cell_value(
+            handler: *mut uiTableModelHandler,
+            _model: *mut uiTableModel,
+            row: c_int,
+            column: c_int,
+        ) -> *mut uiTableValue {
+            unsafe {
+                // libUI takes ownership of the returned value.
+                (*(handler as *mut RustTableModelHandler))
+                    .trait_object
+                    .cell_value(row, column)
+                    .to_ui_table_value()
+            }
+        }
+
+        extern "C" fn set_cell_value(
+            handler: *mut uiTableModelHandler,
+            _model: *mut uiTableModel,
+            row: c_int,
+            column: c_int,
+            value: *const uiTableValue,
+        ) {
+            unsafe {
+                // libUI gives no value for clicks on a button.
+                if value.is_null() {
+                    (*(handler as *mut RustTableModelHandler))
+                        .trait_object
+                        .button_clicked(row, column);
+                    return;
+                }
+                if let Some(value) = TableValue::from_ui_table_value(value) {
+                    (*(handler as *mut RustTableModelHandler))
+                        .trait_object
+                        .set_cell_value(row, column, value);
+                }
+            }
+        }
+    }
+}
+
+/// Whether the cells of a table column can be changed by the user. Edits are given to
+/// [`TableModel::set_cell_value`](trait.TableModel.html#tymethod.set_cell_value). For button
+/// columns, this is whether the buttons can be clicked.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Editable {
+    /// The cells can't be edited.
+    Never,
+    /// All the cells can be edited.
+    Always,
+    /// Whether each cell can be edited is read from the given column of the model, whose values
+    /// must be `TableValue::Int`s: non-zero for editable cells.
+    Column(i32),
+}
+
+/// Copy the selected rows out of the table's selection, and free it.
+unsafe fn table_selection(table: *mut uiTable) -> Vec<i32> {
+    let selection = ui_sys::uiTableGetSelection(table);
+    let rows = if (*selection).NumRows > 0 {
+        slice::from_raw_parts((*selection).Rows, (*selection).NumRows as usize).to_vec()
+    } else {
+        Vec::new()
+    };
+    ui_sys::uiFreeTableSelection(selection);
+    rows
+}
+
+impl Editable {
+    fn to_model_column(self) -> c_int {
+        match self {
+            Editable::Never => ui_sys::uiTableModelColumnNeverEditable,
+            Editable::Always => ui_sys::uiTableModelColumnAlwaysEditable,
+            Editable::Column(column) => column,
+        }
+    }
+}
+
+define_control!{
+    /// A list of rows, displayed in columns, whose data comes from a
+    /// [`TableModel`](trait.TableModel.html).
+    ///
+    /// Each column of the table is appended with the index of the model column it shows, whose
+    /// values must be of the type the table column expects: strings for text columns, images
+    /// for image columns, and ints for checkbox and progress bar columns; buttons show the
+    /// strings of their column. Whether cells can be edited is given separately,
+    /// as an [`Editable`](enum.Editable.html) which can itself name a model column, so that
+    /// an index is never mistaken for one of libUI's special values.
+    ///
+    /// ```
+    /// use iui::prelude::*;
+    /// use iui::controls::{Editable, Table, TableModel, TableValue, TableValueType};
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// // An in-memory model with a name and an editable email address in each row, shared
+    /// // with the rest of the application.
+    /// struct Contacts {
+    ///     rows: Rc<RefCell<Vec<Vec<String>>>>,
+    /// }
+    ///
+    /// impl TableModel for Contacts {
+    ///     fn num_columns(&self) -> i32 {
+    ///         2
+    ///     }
+    ///
+    ///     fn column_type(&self, _column: i32) -> TableValueType {
+    ///         TableValueType::String
+    ///     }
+    ///
+    ///     fn num_rows(&self) -> i32 {
+    ///         self.rows.borrow().len() as i32
+    ///     }
+    ///
+    ///     fn cell_value(&self, row: i32, column: i32) -> TableValue {
+    ///         TableValue::String(self.rows.borrow()[row as usize][column as usize].clone())
+    ///     }
+    ///
+    ///     fn set_cell_value(&mut self, row: i32, column: i32, value: TableValue) {
+    ///         if let TableValue::String(text) = value {
+    ///             self.rows.borrow_mut()[row as usize][column as usize] = text;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let rows = Rc::new(RefCell::new(vec![
+    ///     vec!["Ada".to_string(), "ada@example.com".to_string()],
+    ///     vec!["Grace".to_string(), "grace@example.com".to_string()],
+    /// ]));
+    /// let mut table = Table::new(&ui, Box::new(Contacts { rows: rows.clone() }));
+    /// table.append_text_column(&ui, "Name", 0, Editable::Never);
+    /// table.append_text_column(&ui, "Email", 1, Editable::Always);
+    ///
+    /// // An edit of the email column reaches the model, as the user's would.
+    /// table.set_cell_value(&ui, 1, 1, TableValue::String("grace@navy.mil".to_string()));
+    /// assert_eq!(rows.borrow()[1][1], "grace@navy.mil");
+    /// assert_eq!(rows.borrow()[1][0], "Grace");
+    ///
+    /// let mut window = Window::new(&ui, "Contacts", 300, 200, WindowType::NoMenubar);
+    /// window.set_child(&ui, table);
+    /// window.show(&ui);
+    /// ui.event_loop().next_tick(&ui);
+    /// ```
+    rust_type: Table,
+    sys_type: uiTable
+}
+
+impl Table {
+    /// Creates a new table showing the data of `model`. The table has no columns until some
+    /// are appended.
+    pub fn new(ctx: &UI, model: Box<dyn TableModel>) -> Table {
+        let mut handler = RustTableModelHandler::new(ctx, model);
+        unsafe {
+            let ui_table_model = ui_sys::uiNewTableModel(
+                &mut *handler as *mut RustTableModelHandler as *mut uiTableModelHandler,
+            );
+            let mut params = ui_sys::uiTableParams {
+                Model: ui_table_model,
+                RowBackgroundColorModelColumn: -1,
+            };
+            let table = Table::from_raw(ui_sys::uiNewTable(&mut params));
+            forget_control(table.uiTable as *mut uiControl);
+            let stale = TABLE_MODELS.with(|models| {
+                models.borrow_mut().insert(
+                    table.uiTable,
+                    TableModelState {
+                        ui_table_model,
+                        handler,
+                    },
+                )
+            });
+            // Left by a table destroyed without its model being freed.
+            if let Some(stale) = stale {
+                ui_sys::uiFreeTableModel(stale.ui_table_model);
+            }
+            table
+        }
+    }
+
+    /// Appends a column showing the strings of the model column `value_column`, which can be
+    /// edited by the user according to `editable`.
+    pub fn append_text_column(&mut self, _ctx: &UI, name: &str, value_column: i32, editable: Editable) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendTextColumn(
+                self.uiTable,
+                c_name.as_ptr(),
+                value_column,
+                editable.to_model_column(),
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    /// Appends a column showing the images of the model column `image_column`, whose values
+    /// must be `TableValue::Image`s. Images can't be edited.
+    pub fn append_image_column(&mut self, _ctx: &UI, name: &str, image_column: i32) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendImageColumn(self.uiTable, c_name.as_ptr(), image_column)
+        }
+    }
+
+    /// Appends a column showing, in each cell, the image of the model column `image_column`
+    /// followed by the string of the model column `text_column`. Only the text can be edited,
+    /// according to `editable`.
+    pub fn append_image_text_column(
+        &mut self,
+        _ctx: &UI,
+        name: &str,
+        image_column: i32,
+        text_column: i32,
+        editable: Editable,
+    ) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendImageTextColumn(
+                self.uiTable,
+                c_name.as_ptr(),
+                image_column,
+                text_column,
+                editable.to_model_column(),
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    /// Appends a column of checkboxes, checked for the rows whose value in the model column
+    /// `checkbox_column` is a non-zero `TableValue::Int`. When the user toggles an editable
+    /// checkbox, the model is given `TableValue::Int(1)` for checked or `TableValue::Int(0)`
+    /// for unchecked.
+    pub fn append_checkbox_column(
+        &mut self,
+        _ctx: &UI,
+        name: &str,
+        checkbox_column: i32,
+        editable: Editable,
+    ) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendCheckboxColumn(
+                self.uiTable,
+                c_name.as_ptr(),
+                checkbox_column,
+                editable.to_model_column(),
+            )
+        }
+    }
+
+    /// Appends a column showing, in each cell, a checkbox for the model column
+    /// `checkbox_column`, as in [`append_checkbox_column`](#method.append_checkbox_column),
+    /// followed by the string of the model column `text_column`. The checkbox and the text can
+    /// each be edited according to their own `Editable`.
+    pub fn append_checkbox_text_column(
+        &mut self,
+        _ctx: &UI,
+        name: &str,
+        checkbox_column: i32,
+        checkbox_editable: Editable,
+        text_column: i32,
+        text_editable: Editable,
+    ) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendCheckboxTextColumn(
+                self.uiTable,
+                c_name.as_ptr(),
+                checkbox_column,
+                checkbox_editable.to_model_column(),
+                text_column,
+                text_editable.to_model_column(),
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    /// Appends a column of progress bars, showing the values of the model column
+    /// `progress_column`. The values must be `TableValue::Int`s: a percentage from 0 to 100, or
+    /// -1 for a bar showing progress of an unknown amount. Progress bars can't be edited, and
+    /// only move when the table is told that their row changed, with
+    /// [`model_row_changed`](#method.model_row_changed).
+    pub fn append_progress_bar_column(&mut self, _ctx: &UI, name: &str, progress_column: i32) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendProgressBarColumn(self.uiTable, c_name.as_ptr(), progress_column)
+        }
+    }
+
+    /// Appends a column of buttons, labelled with the strings of the model column
+    /// `button_column`. Clicks are given to
+    /// [`TableModel::button_clicked`](trait.TableModel.html#method.button_clicked), for the
+    /// buttons which can be clicked according to `clickable`; the others are shown disabled.
+    pub fn append_button_column(
+        &mut self,
+        _ctx: &UI,
+        name: &str,
+        button_column: i32,
+        clickable: Editable,
+    ) {
+        unsafe {
+            let c_name = ffi_tools::to_c_string(name);
+            ui_sys::uiTableAppendButtonColumn(
+                self.uiTable,
+                c_name.as_ptr(),
+                button_column,
+                clickable.to_model_column(),
+            )
+        }
+    }
+
+    /// Tells the table that a row has been inserted into its model at index `row`. The model
+    /// must already count the new row in `num_rows`.
+    ///
+    /// This and the other `model_row_*` notifications must be called on the UI thread; work
+    /// done on another thread can notify the table through [`UI::queue_main`](../struct.UI.html#method.queue_main).
+    /// They do nothing once the table is destroyed.
+    pub fn model_row_inserted(&mut self, _ctx: &UI, row: i32) {
+        if let Some(model) = self.ui_table_model() {
+            unsafe { ui_sys::uiTableModelRowInserted(model, row) }
+        }
+    }
+
+    /// Tells the table that the values of the given row of its model have changed, so that it
+    /// reads them again and redraws the row, e.g. to move a progress bar column.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Table, TableModel, TableValue, TableValueType};
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// // A model showing the progress of each job, shared with the code doing the jobs.
+    /// struct Jobs {
+    ///     progress: Rc<RefCell<Vec<i32>>>,
+    /// }
+    ///
+    /// impl TableModel for Jobs {
+    ///     fn num_columns(&self) -> i32 {
+    ///         1
+    ///     }
+    ///
+    ///     fn column_type(&self, _column: i32) -> TableValueType {
+    ///         TableValueType::Int
+    ///     }
+    ///
+    ///     fn num_rows(&self) -> i32 {
+    ///         self.progress.borrow().len() as i32
+    ///     }
+    ///
+    ///     fn cell_value(&self, row: i32, _column: i32) -> TableValue {
+    ///         TableValue::Int(self.progress.borrow()[row as usize])
+    ///     }
+    /// }
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let progress = Rc::new(RefCell::new(vec![0, 0]));
+    /// let mut table = Table::new(&ui, Box::new(Jobs { progress: progress.clone() }));
+    /// table.append_progress_bar_column(&ui, "Progress", 0);
+    ///
+    /// // The first job moves forward, and a third one is added.
+    /// progress.borrow_mut()[0] = 50;
+    /// table.model_row_changed(&ui, 0);
+    /// progress.borrow_mut().push(0);
+    /// table.model_row_inserted(&ui, 2);
+    /// ```
+    pub fn model_row_changed(&mut self, _ctx: &UI, row: i32) {
+        if let Some(model) = self.ui_table_model() {
+            unsafe { ui_sys::uiTableModelRowChanged(model, row) }
+        }
+    }
+
+    /// Changes the given cell as the user would by editing it: `value` is handed to the
+    /// model's [`set_cell_value`](trait.TableModel.html#method.set_cell_value) the way libUI
+    /// hands it edits, then the table reads the row again. This does nothing once the table is
+    /// destroyed.
+    pub fn set_cell_value(&mut self, _ctx: &UI, row: i32, column: i32, value: TableValue) {
+        // The model may use the table, so it is not called while the state is borrowed.
+        let state = TABLE_MODELS.with(|models| {
+            models.borrow_mut().get_mut(&self.uiTable).map(|state| {
+                let handler = &mut *state.handler as *mut RustTableModelHandler;
+                (handler, state.ui_table_model)
+            })
+        });
+        if let Some((handler, model)) = state {
+            unsafe {
+                let ui_value = value.to_ui_table_value();
+                ((*handler).ui_table_model_handler.SetCellValue)(
+                    handler as *mut uiTableModelHandler,
+                    model,
+                    row,
+                    column,
+                    ui_value,
+                );
+                ui_sys::uiFreeTableValue(ui_value);
+                ui_sys::uiTableModelRowChanged(model, row);
+            }
+        }
+    }
+
+    /// Tells the table that the row at index `row` has been removed from its model. The model
+    /// must already leave the row out of `num_rows`.
+    pub fn model_row_deleted(&mut self, _ctx: &UI, row: i32) {
+        if let Some(model) = self.ui_table_model() {
+            unsafe { ui_sys::uiTableModelRowDeleted(model, row) }
+        }
+    }
+
+    // The native model created for this table by `Table::new`, or `None` once the table is
+    // destroyed and the model freed.
+    fn ui_table_model(&self) -> Option<*mut uiTableModel> {
+        TABLE_MODELS.with(|models| {
+            models
+                .borrow()
+                .get(&self.uiTable)
+                .map(|state| state.ui_table_model)
+        })
+    }
+
+    /// Returns the indices of the selected rows.
+    pub fn selection(&self, _ctx: &UI) -> Vec<i32> {
+        unsafe { table_selection(self.uiTable) }
+    }
+
+    /// Selects the given rows, and only them. Giving more rows than the selection mode allows
+    /// is a programming error.
+    pub fn set_selection(&mut self, _ctx: &UI, rows: &[i32]) {
+        let mut rows = rows.to_vec();
+        let mut selection = ui_sys::uiTableSelection {
+            NumRows: rows.len() as c_int,
+            Rows: rows.as_mut_ptr(),
+        };
+        unsafe { ui_sys::uiTableSetSelection(self.uiTable, &mut selection) }
+    }
+
+    /// Returns how many rows the user can select.
+    pub fn selection_mode(&self, _ctx: &UI) -> TableSelectionMode {
+        unsafe { ui_sys::uiTableGetSelectionMode(self.uiTable) }
+    }
+
+    /// Sets how many rows the user can select.
+    pub fn set_selection_mode(&mut self, _ctx: &UI, mode: TableSelectionMode) {
+        unsafe { ui_sys::uiTableSetSelectionMode(self.uiTable, mode) }
+    }
+
+    /// Registers a callback for when the selected rows change, which is given the new
+    /// selection.
+    pub fn on_selection_changed<F: FnMut(&[i32]) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: SelectionChangedCallback = Box::new(callback);
+            ui_sys::uiTableOnSelectionChanged(
+                self.uiTable,
+                c_callback,
+                ffi_tools::register_callback(self.uiTable, "selection_changed", data),
+            );
+        }
+
+        extern "C" fn c_callback(table: *mut uiTable, data: *mut c_void) {
+            unsafe {
+                let rows = table_selection(table);
+                (*(data as *mut SelectionChangedCallback))(&rows);
+            }
+        }
+    }
+}
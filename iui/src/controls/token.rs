@@ -0,0 +1,136 @@
+use super::Control;
+use ui::UI;
+use ui_sys::uiControl;
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+// The controls that `ControlToken`s refer to, with their `uiControl`, keyed by the number each
+// token holds.
+type TokenControls = HashMap<usize, (usize, Box<dyn Any>)>;
+
+thread_local! {
+    static TOKEN_CONTROLS: RefCell<TokenControls> = RefCell::new(HashMap::new());
+    static NEXT_TOKEN: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Forgets the controls held for tokens. Called when the UI is torn down.
+pub(crate) fn clear_token_state() {
+    let controls = TOKEN_CONTROLS.with(|controls| controls.replace(HashMap::new()));
+    drop(controls);
+}
+
+/// Forgets the clones of `control` held for tokens, as it is about to be destroyed.
+pub(crate) fn forget_tokens_of(control: *mut uiControl) {
+    let removed: Vec<_> = TOKEN_CONTROLS.with(|controls| {
+        let mut controls = controls.borrow_mut();
+        let keys: Vec<usize> = controls
+            .iter()
+            .filter(|&(_, &(ui_control, _))| ui_control == control as usize)
+            .map(|(&key, _)| key)
+            .collect();
+        keys.iter().filter_map(|key| controls.remove(key)).collect()
+    });
+    // Dropping a window's last handle touches other state, so it happens after the borrow.
+    drop(removed);
+}
+
+/// A reference to a control which can be sent to other threads, and turned back into the
+/// control by a function given to [`UI::queue_main`](../struct.UI.html#method.queue_main).
+///
+/// Controls wrap pointers into `libui`, which may only be touched from the GUI thread, so no
+/// control type is `Send`. Neither is `UI`, which every function using a control needs.
+/// A token is `Send` because it holds nothing but a number: the control itself stays on the
+/// GUI thread, and [`get`](#method.get) looks it up again, which takes a `&UI` and so can only
+/// happen there. This is the only way to refer to a particular control from another thread.
+///
+/// A token keeps a clone of its control, dropped along with the token, when the control is
+/// destroyed, for example together with its window, or when the UI is torn down.
+///
+/// ```
+/// use iui::prelude::*;
+/// use iui::controls::{ControlToken, Label};
+/// use std::thread;
+///
+/// let ui = UI::init().unwrap();
+/// let mut window = Window::new(&ui, "Worker", 200, 100, WindowType::NoMenubar);
+/// let label = Label::new(&ui, "Working...");
+/// window.set_child(&ui, label.clone());
+///
+/// let token = ControlToken::new(&ui, &label);
+/// thread::spawn(move || {
+///     let factorial: u64 = (1..21).product();
+///     UI::queue_main(move || {
+///         // Functions given to `queue_main` run on the GUI thread, where the `UI` is.
+///         let ui = UI::current().unwrap();
+///         let mut label = token.get(&ui).unwrap();
+///         label.set_text(&ui, &format!("20! = {}", factorial));
+///         ui.quit();
+///     });
+/// });
+///
+/// ui.main();
+/// ```
+pub struct ControlToken<T> {
+    key: usize,
+    _control: PhantomData<fn() -> T>,
+}
+
+impl<T: Clone + Into<Control> + 'static> ControlToken<T> {
+    /// Creates a token referring to `control`.
+    pub fn new(_ctx: &UI, control: &T) -> ControlToken<T> {
+        let key = NEXT_TOKEN.with(|next| {
+            let key = next.get();
+            next.set(key + 1);
+            key
+        });
+        let ui_control = control.clone().into().as_ui_control() as usize;
+        TOKEN_CONTROLS.with(|controls| {
+            controls
+                .borrow_mut()
+                .insert(key, (ui_control, Box::new(control.clone())))
+        });
+        ControlToken {
+            key,
+            _control: PhantomData,
+        }
+    }
+
+    /// Returns the control this token refers to, or `None` if it has since been destroyed, or
+    /// the UI it was created with torn down.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{ControlToken, Label};
+    /// # let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Worker", 200, 100, WindowType::NoMenubar);
+    /// let label = Label::new(&ui, "Working...");
+    /// window.set_child(&ui, label.clone());
+    /// let token = ControlToken::new(&ui, &label);
+    /// assert!(token.get(&ui).is_some());
+    ///
+    /// unsafe { window.destroy() };
+    /// assert!(token.get(&ui).is_none());
+    /// ```
+    pub fn get(&self, _ctx: &UI) -> Option<T> {
+        TOKEN_CONTROLS.with(|controls| {
+            controls
+                .borrow()
+                .get(&self.key)
+                .and_then(|(_, control)| control.downcast_ref::<T>())
+                .cloned()
+        })
+    }
+}
+
+impl<T> Drop for ControlToken<T> {
+    fn drop(&mut self) {
+        // The token may be dropped on any thread, so the control is forgotten on the GUI thread.
+        let key = self.key;
+        UI::queue_main(move || {
+            TOKEN_CONTROLS.with(|controls| controls.borrow_mut().remove(&key));
+        });
+    }
+}
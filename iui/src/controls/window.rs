@@ -1,16 +1,59 @@
 //! Functionality related to creating, managing, and destroying GUI windows.
 
-use controls::Control;
-use libc::{c_int, c_void};
+use controls::{self, file_drop, icon, screen, Control, Image};
+use error::UIError;
+use ffi_tools;
+use libc::{c_char, c_int, c_void};
 use std::cell::RefCell;
-use std::ffi::{CStr, CString};
+use std::collections::HashMap;
+use std::ffi::CStr;
 use std::mem;
 use std::path::PathBuf;
+use std::ptr;
 use ui::UI;
 use ui_sys::{self, uiControl, uiWindow};
 
 thread_local! {
-    static WINDOWS: RefCell<Vec<Window>> = RefCell::new(Vec::new())
+    // The windows which have not been destroyed yet, in the order they were created.
+    static WINDOWS: RefCell<Vec<*mut uiWindow>> = const { RefCell::new(Vec::new()) };
+    // How many `Window` handles to each window exist.
+    static HANDLES: RefCell<HashMap<*mut uiWindow, usize>> = RefCell::new(HashMap::new());
+    static WINDOW_STATES: RefCell<HashMap<*mut uiWindow, WindowState>> = RefCell::new(HashMap::new())
+}
+
+/// A callback given to `Window::on_content_size_changed`.
+type ContentSizeChangedCallback = Box<dyn FnMut(&mut Window, i32, i32)>;
+
+/// A callback given to `Window::on_files_dropped`.
+type FilesDroppedCallback = Box<dyn FnMut(&mut Window, &[PathBuf])>;
+
+/// State kept for a window on top of what `libui` stores, shared by every handle to the window.
+#[derive(Default)]
+struct WindowState {
+    /// The content size and position the window had before it was made fullscreen.
+    windowed_geometry: Option<((c_int, c_int), (c_int, c_int))>,
+    /// The smallest content size the user can give the window.
+    min_size: Option<(c_int, c_int)>,
+    /// The largest content size the user can give the window.
+    max_size: Option<(c_int, c_int)>,
+    /// Whether the native window has been made to accept dropped files.
+    accepts_files: bool,
+}
+
+impl WindowState {
+    /// Returns the closest content size to `size` that the window is allowed to have.
+    fn constrain(&self, size: (c_int, c_int)) -> (c_int, c_int) {
+        let (mut width, mut height) = size;
+        if let Some((max_width, max_height)) = self.max_size {
+            width = width.min(max_width);
+            height = height.min(max_height);
+        }
+        if let Some((min_width, min_height)) = self.min_size {
+            width = width.max(min_width);
+            height = height.max(min_height);
+        }
+        (width, height)
+    }
 }
 
 /// A `Window` can either have a menubar or not; this enum represents that decision.\
@@ -22,8 +65,77 @@ pub enum WindowType {
 
 define_control!{
     /// Contains a single child control and displays it and its children in a window on the screen.
+    ///
+    /// # Ownership
+    ///
+    /// A window owns the controls inside it: they are destroyed along with it, and a control
+    /// can only be put in one place, so it is never destroyed twice. Handles to those controls
+    /// must not be used once the window is gone.
+    ///
+    /// The window itself is destroyed exactly once, by whichever comes first:
+    ///
+    /// - the last `Window` handle to it being dropped; every clone is a handle, including those
+    ///   captured by callbacks, so a window whose callbacks refer to it lives until the UI does;
+    /// - its [`on_closing`](#method.on_closing) callback returning `true`;
+    /// - the unsafe [`destroy`](#method.destroy);
+    /// - the `UI` being dropped, which destroys every window left.
+    ///
+    /// A window which is turned into a [`Control`](struct.Control.html) is kept until the `UI`
+    /// is dropped.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Button, VerticalBox};
+    /// # let ui = UI::init().unwrap();
+    /// for i in 0..100 {
+    ///     let mut window = Window::new(&ui, &format!("Window {}", i), 200, 100, WindowType::NoMenubar);
+    ///     let mut vbox = VerticalBox::new(&ui);
+    ///     vbox.append(&ui, Button::new(&ui, "OK"), LayoutStrategy::Compact);
+    ///     window.set_child(&ui, vbox);
+    ///     let handle = window.clone();
+    ///     drop(window);
+    ///     // `handle` is the last handle, so the window and its children are destroyed here.
+    /// }
+    /// ```
     rust_type: Window,
-    sys_type: uiWindow
+    sys_type: uiWindow,
+    owned_handles
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        let ptr = self.uiWindow;
+        // Handles may be dropped while the thread is exiting, once the state is gone.
+        let last = HANDLES
+            .try_with(|handles| {
+                let mut handles = handles.borrow_mut();
+                let last = match handles.get_mut(&ptr) {
+                    Some(count) => {
+                        *count -= 1;
+                        *count == 0
+                    }
+                    None => false,
+                };
+                if last {
+                    handles.remove(&ptr);
+                }
+                last
+            })
+            .unwrap_or(false);
+        let alive = last
+            && WINDOWS
+                .try_with(|windows| windows.borrow().contains(&ptr))
+                .unwrap_or(false);
+        if alive {
+            unsafe { Window::destroy_raw(ptr) }
+        }
+    }
+}
+
+impl Clone for Window {
+    fn clone(&self) -> Window {
+        unsafe { Window::from_raw(self.uiWindow) }
+    }
 }
 
 impl Window {
@@ -36,15 +148,15 @@ impl Window {
             WindowType::NoMenubar => false,
         };
         let mut window = unsafe {
-            let c_string = CString::new(title.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(title);
             let window = Window::from_raw(ui_sys::uiNewWindow(
                 c_string.as_ptr(),
                 width,
                 height,
                 has_menubar as c_int,
             ));
-
-            WINDOWS.with(|windows| windows.borrow_mut().push(window.clone()));
+            controls::forget_control(window.uiWindow as *mut uiControl);
+            WINDOWS.with(|windows| windows.borrow_mut().push(window.uiWindow));
 
             window
         };
@@ -53,6 +165,7 @@ impl Window {
         let ui = _ctx.clone();
         window.on_closing(_ctx, move |_| {
             ui.quit();
+            false
         });
 
         // Windows, by default, draw margins
@@ -63,14 +176,13 @@ impl Window {
 
     /// Get the current title of the window.
     pub fn title(&self, _ctx: &UI) -> String {
-        unsafe {
-            CStr::from_ptr(ui_sys::uiWindowTitle(self.uiWindow))
-                .to_string_lossy()
-                .into_owned()
-        }
+        unsafe { ffi_tools::copy_and_free_text(ui_sys::uiWindowTitle(self.uiWindow)) }
     }
 
     /// Get a reference to the current title of the window.
+    ///
+    /// libui returns a fresh copy of the text each time, which this never frees.
+    #[deprecated(note = "use `title`, which does not leak the string libui returns")]
     pub fn title_ref(&self, _ctx: &UI) -> &CStr {
         unsafe { &CStr::from_ptr(ui_sys::uiWindowTitle(self.uiWindow)) }
     }
@@ -78,39 +190,297 @@ impl Window {
     /// Set the window's title to the given string.
     pub fn set_title(&mut self, _ctx: &UI, title: &str) {
         unsafe {
-            let c_string = CString::new(title.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(title);
             ui_sys::uiWindowSetTitle(self.uiWindow, c_string.as_ptr())
         }
     }
 
-    /// Set a callback to be run when the window closes.
+    /// Get the position of the window on the screen, as `(x, y)`.
+    ///
+    /// Coordinates are in pixels, measured from the top-left corner of the primary display, and
+    /// refer to the top-left corner of the window's frame. On multi-monitor setups, windows on
+    /// other displays may have coordinates that are larger than the primary display, or
+    /// negative if the display is above or to the left of it.
+    pub fn position(&self, _ctx: &UI) -> (i32, i32) {
+        let mut x: c_int = 0;
+        let mut y: c_int = 0;
+        unsafe { ui_sys::uiWindowPosition(self.uiWindow, &mut x, &mut y) }
+        (x, y)
+    }
+
+    /// Move the window to the given position on the screen, using the same coordinates as
+    /// [`position`](struct.Window.html#method.position).
+    ///
+    /// Some platforms keep windows from being placed off-screen, so the window may end up
+    /// somewhere else if the position is not on any display (for instance, because a monitor
+    /// has been disconnected since the position was saved).
+    pub fn set_position(&mut self, _ctx: &UI, x: i32, y: i32) {
+        unsafe { ui_sys::uiWindowSetPosition(self.uiWindow, x, y) }
+    }
+
+    /// Move the window to the center of the display it is on, or of the primary display if it
+    /// has not been shown yet.
+    ///
+    /// `libui` cannot tell which display a window is on, so the display's size is found through
+    /// the native toolkit, with GTK+ on Linux and the BSDs, and on Windows; the window is then
+    /// moved with [`set_position`](struct.Window.html#method.set_position), centering its
+    /// content area. On macOS, the window centers itself, a little above the middle. This
+    /// returns an [`Unsupported`](../enum.UIError.html#variant.Unsupported) error on any other
+    /// platform, or if the display cannot be found. Like `set_position`, it has no effect where
+    /// windows cannot be placed by applications, as under Wayland.
+    ///
+    /// If this moves the window, the callback given to
+    /// [`on_position_changed`](struct.Window.html#method.on_position_changed) is run, so that
+    /// code which tracks the window's position does not miss the move.
+    ///
+    /// ```no_run
+    /// # use iui::prelude::*;
+    /// # let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Centered", 400, 300, WindowType::NoMenubar);
+    /// if let Err(err) = window.center(&ui) {
+    ///     println!("Left where it was: {:?}", err);
+    /// }
+    /// window.show(&ui);
+    /// ```
+    pub fn center(&mut self, _ctx: &UI) -> Result<(), UIError> {
+        let old_position = self.position(_ctx);
+        unsafe {
+            let handle = ui_sys::uiControlHandle(self.uiWindow as *mut uiControl);
+            screen::center_window(handle, self.uiWindow)?;
+        }
+        if self.position(_ctx) != old_position {
+            let callback = unsafe {
+                ffi_tools::registered_callback::<_, Box<dyn FnMut(&mut Window)>>(
+                    self.uiWindow,
+                    "position_changed",
+                )
+            };
+            if let Some(callback) = callback {
+                callback(self);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set a callback to be run when the user moves the window.
+    ///
+    /// The callback is not run for moves made with
+    /// [`set_position`](struct.Window.html#method.set_position), but it is run when
+    /// [`center`](struct.Window.html#method.center) moves the window. Setting a new callback
+    /// replaces the previous one.
+    pub fn on_position_changed<F: FnMut(&mut Window) + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: Box<dyn FnMut(&mut Window)> = Box::new(callback);
+            ui_sys::uiWindowOnPositionChanged(
+                self.uiWindow,
+                position_changed_callback,
+                ffi_tools::register_callback(self.uiWindow, "position_changed", data),
+            );
+        }
+    }
+
+    /// Get the size of the window's content area, without its frame and title bar, as
+    /// `(width, height)`.
+    pub fn content_size(&self, _ctx: &UI) -> (i32, i32) {
+        let mut size: (c_int, c_int) = (0, 0);
+        unsafe { ui_sys::uiWindowContentSize(self.uiWindow, &mut size.0, &mut size.1) }
+        size
+    }
+
+    /// Set a callback to be run when the size of the window's content area changes, for
+    /// instance because the user resized the window. It is given the new width and height,
+    /// once the window has taken that size.
+    ///
+    /// While the user drags the window's border, the callback may be run for each step of the
+    /// drag, so it should be quick. Setting a new callback replaces the previous one.
+    pub fn on_content_size_changed<F: FnMut(&mut Window, i32, i32) + 'static>(
+        &mut self,
+        _ctx: &UI,
+        callback: F,
+    ) {
+        let data: ContentSizeChangedCallback = Box::new(callback);
+        ffi_tools::register_callback(self.uiWindow, "content_size_changed", data);
+        self.watch_content_size();
+    }
+
+    /// Check whether or not the user can resize the window.
+    pub fn resizeable(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiWindowResizeable(self.uiWindow) != 0 }
+    }
+
+    /// Allow or prevent the user from resizing the window. A window which can't be resized
+    /// keeps the content size it has, as set by the program, and its border can't be dragged.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # let ui = UI::init().unwrap();
+    /// let mut about = Window::new(&ui, "About", 300, 150, WindowType::NoMenubar);
+    /// about.set_resizeable(&ui, false);
+    /// assert!(!about.resizeable(&ui));
+    /// ```
+    pub fn set_resizeable(&mut self, _ctx: &UI, resizeable: bool) {
+        unsafe { ui_sys::uiWindowSetResizeable(self.uiWindow, resizeable as c_int) }
+    }
+
+    /// Set the smallest content size the user can resize the window to. The window is enlarged
+    /// right away if it is smaller.
+    ///
+    /// `libui` has no way to limit the size of a window on any platform, so the window is put
+    /// back within its limits whenever the user resizes it beyond them: its border can still be
+    /// dragged past them, but it snaps back once it is. If the minimum is larger than the maximum, the
+    /// minimum wins. Pass `(0, 0)` to remove the limit.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Form", 400, 300, WindowType::NoMenubar);
+    /// window.set_min_size(&ui, 320, 240);
+    /// window.set_max_size(&ui, 800, 600);
+    /// ```
+    pub fn set_min_size(&mut self, ctx: &UI, width: i32, height: i32) {
+        self.with_state(|state| state.min_size = Some((width, height)));
+        self.apply_size_limits(ctx);
+    }
+
+    /// Set the largest content size the user can resize the window to. The window is shrunk
+    /// right away if it is larger.
     ///
-    /// This is often used on the main window of an application to quit
-    /// the application when the window is closed.
-    pub fn on_closing<F: FnMut(&mut Window)>(&mut self, _ctx: &UI, mut callback: F) {
+    /// This is enforced the same way as [`set_min_size`](#method.set_min_size). Pass
+    /// `(i32::MAX, i32::MAX)` to remove the limit.
+    pub fn set_max_size(&mut self, ctx: &UI, width: i32, height: i32) {
+        self.with_state(|state| state.max_size = Some((width, height)));
+        self.apply_size_limits(ctx);
+    }
+
+    // Puts the window within its size limits, and keeps it there from now on.
+    fn apply_size_limits(&mut self, ctx: &UI) {
+        self.watch_content_size();
+        if self.fullscreen(ctx) {
+            return;
+        }
+        let size = self.content_size(ctx);
+        let constrained = self.with_state(|state| state.constrain(size));
+        if constrained != size {
+            unsafe { ui_sys::uiWindowSetContentSize(self.uiWindow, constrained.0, constrained.1) }
+        }
+    }
+
+    // Makes libui report changes to the content size to `content_size_changed_callback`, which
+    // applies the size constraints of the window and runs the user's callback.
+    fn watch_content_size(&self) {
         unsafe {
-            let mut data: Box<Box<dyn FnMut(&mut Window) -> bool>> = Box::new(Box::new(|window| {
-                callback(window);
-                false
-            }));
+            ui_sys::uiWindowOnContentSizeChanged(
+                self.uiWindow,
+                content_size_changed_callback,
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    /// Set a callback to be run when the user asks to close the window, for instance with its
+    /// close button. The callback decides what happens: if it returns `true`, the window is
+    /// destroyed, and if it returns `false`, the close is cancelled and the window stays open
+    /// (which is how "Save changes?" prompts can veto closing).
+    ///
+    /// The default callback quits the application and returns `false`; the window is then
+    /// destroyed with the `UI`. Setting a new callback replaces the previous one.
+    ///
+    /// The callback is only run for closes requested by the user, or by
+    /// [`close`](#method.close). Destroying the window programmatically, or quitting the
+    /// application, does not run it. It is also unrelated to
+    /// [`UI::on_should_quit`](../struct.UI.html#method.on_should_quit), which handles the
+    /// application's Quit menu item rather than individual windows.
+    pub fn on_closing<F: FnMut(&mut Window) -> bool + 'static>(&mut self, _ctx: &UI, callback: F) {
+        unsafe {
+            let data: Box<dyn FnMut(&mut Window) -> bool> = Box::new(callback);
             ui_sys::uiWindowOnClosing(
                 self.uiWindow,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(&mut Window) -> bool> as *mut c_void,
+                ffi_tools::register_callback(self.uiWindow, "closing", data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(window: *mut uiWindow, data: *mut c_void) -> i32 {
             unsafe {
-                let mut window = Window { uiWindow: window };
-                mem::transmute::<*mut c_void, Box<Box<dyn FnMut(&mut Window) -> bool>>>(data)(
-                    &mut window,
-                ) as i32
+                let mut window = Window::from_raw(window);
+                let close = (*(data as *mut Box<dyn FnMut(&mut Window) -> bool>))(&mut window);
+                if close {
+                    // Destroy the window here rather than have libui do it once we return, so
+                    // that what it leaves behind can be freed afterwards.
+                    window.destroy();
+                }
+                0
             }
         }
     }
 
+    /// Ask the window to close, as the user would with its close button: its
+    /// [`on_closing`](#method.on_closing) callback is run, and the window is destroyed if the
+    /// callback returns `true`. Returns whether the window was closed, in which case the window
+    /// must no longer be used. A window which is already destroyed counts as closed.
+    pub fn close(&mut self, _ctx: &UI) -> bool {
+        if !WINDOWS.with(|windows| windows.borrow().contains(&self.uiWindow)) {
+            return true;
+        }
+        let closing = unsafe {
+            ffi_tools::registered_callback::<_, Box<dyn FnMut(&mut Window) -> bool>>(
+                self.uiWindow,
+                "closing",
+            )
+        };
+        let close = match closing {
+            Some(callback) => callback(self),
+            None => true,
+        };
+        if close {
+            unsafe { self.destroy() };
+        }
+        close
+    }
+
+    /// Check whether or not the window is fullscreen.
+    pub fn fullscreen(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiWindowFullscreen(self.uiWindow) != 0 }
+    }
+
+    /// Make the window fullscreen, or return it to a normal window.
+    ///
+    /// When the window leaves fullscreen, it gets back the size and position it had before
+    /// entering it. Margins are not affected; a fullscreen window keeps whatever was set with
+    /// [`set_margined`](struct.Window.html#method.set_margined).
+    pub fn set_fullscreen(&mut self, _ctx: &UI, fullscreen: bool) {
+        if fullscreen == self.fullscreen(_ctx) {
+            return;
+        }
+        unsafe {
+            if fullscreen {
+                let geometry = (self.content_size(_ctx), self.position(_ctx));
+                self.with_state(|state| state.windowed_geometry = Some(geometry));
+                ui_sys::uiWindowSetFullscreen(self.uiWindow, true as c_int);
+            } else {
+                ui_sys::uiWindowSetFullscreen(self.uiWindow, false as c_int);
+                if let Some(((width, height), (x, y))) =
+                    self.with_state(|state| state.windowed_geometry.take())
+                {
+                    ui_sys::uiWindowSetContentSize(self.uiWindow, width, height);
+                    ui_sys::uiWindowSetPosition(self.uiWindow, x, y);
+                }
+            }
+        }
+    }
+
+    /// Check whether or not the window is borderless.
+    pub fn borderless(&self, _ctx: &UI) -> bool {
+        unsafe { ui_sys::uiWindowBorderless(self.uiWindow) != 0 }
+    }
+
+    /// Set whether or not the window is drawn without borders and a title bar. As this also
+    /// removes the standard controls to move, resize and close the window, the application
+    /// should provide its own way to do so.
+    pub fn set_borderless(&mut self, _ctx: &UI, borderless: bool) {
+        unsafe { ui_sys::uiWindowSetBorderless(self.uiWindow, borderless as c_int) }
+    }
+
     /// Check whether or not this window has margins around the edges.
     pub fn margined(&self, _ctx: &UI) -> bool {
         unsafe { ui_sys::uiWindowMargined(self.uiWindow) != 0 }
@@ -121,64 +491,384 @@ impl Window {
         unsafe { ui_sys::uiWindowSetMargined(self.uiWindow, margined as c_int) }
     }
 
+    /// Sets the icon shown for the window in the title bar, the taskbar and the task switcher.
+    ///
+    /// `libui` has no function for this, so it is done through the native toolkit:
+    ///
+    /// - With GTK+, on Linux and the BSDs, every bitmap of `image` is given to the window, and
+    ///   the one to use is picked for each size the icon is shown at.
+    /// - On Windows, the bitmaps closest in size to the system's large and small icons are used.
+    /// - On macOS, windows have no icons of their own: the Dock shows the icon of the
+    ///   application bundle, set in its `Info.plist`. This returns an
+    ///   [`Unsupported`](../enum.UIError.html#variant.Unsupported) error there, and on any other
+    ///   platform.
+    ///
+    /// An image without any bitmaps leaves the icon unchanged.
+    ///
+    /// ```no_run
+    /// # use iui::prelude::*;
+    /// # use iui::controls::Image;
+    /// # let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Branded", 200, 100, WindowType::NoMenubar);
+    /// let mut icon = Image::from_rgba(&ui, 16, 16, &[0, 128, 255, 255].repeat(16 * 16), 1.0).unwrap();
+    /// icon.append(&ui, 32, 32, &[0, 128, 255, 255].repeat(32 * 32)).unwrap();
+    /// icon.append(&ui, 64, 64, &[0, 128, 255, 255].repeat(64 * 64)).unwrap();
+    /// if let Err(err) = window.set_icon(&ui, &icon) {
+    ///     println!("No icon: {:?}", err);
+    /// }
+    /// ```
+    pub fn set_icon(&mut self, _ctx: &UI, image: &Image) -> Result<(), UIError> {
+        unsafe {
+            let handle = ui_sys::uiControlHandle(self.uiWindow as *mut uiControl);
+            image.with_bitmaps(|bitmaps| icon::set_window_icon(handle, bitmaps))
+        }
+    }
+
+    /// Set a callback to be run with the paths of the files the user drops onto the window,
+    /// for example to open them. The paths are those of local files, in the order the platform
+    /// gives them; anything else dropped, like a link from a browser, is ignored.
+    ///
+    /// `libui` has no drag and drop support, so the window is made to accept files through the
+    /// native toolkit, with GTK+ on Linux and the BSDs, and on Windows. This returns an
+    /// [`Unsupported`](../enum.UIError.html#variant.Unsupported) error on macOS and any other
+    /// platform, leaving the window as it was. Controls which accept drops themselves, like
+    /// entries with GTK+, get the files dropped onto them instead of the window.
+    ///
+    /// The callback is run on the GUI thread, like any other. Setting a new callback replaces
+    /// the previous one.
+    ///
+    /// ```no_run
+    /// # use iui::prelude::*;
+    /// # let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Viewer", 400, 300, WindowType::NoMenubar);
+    /// let accepted = window.on_files_dropped(&ui, |_, paths| {
+    ///     for path in paths {
+    ///         println!("Opening {}", path.display());
+    ///     }
+    /// });
+    /// if accepted.is_err() {
+    ///     println!("Use File > Open instead");
+    /// }
+    /// ```
+    pub fn on_files_dropped<F: FnMut(&mut Window, &[PathBuf]) + 'static>(
+        &mut self,
+        _ctx: &UI,
+        callback: F,
+    ) -> Result<(), UIError> {
+        if !self.with_state(|state| state.accepts_files) {
+            unsafe {
+                let handle = ui_sys::uiControlHandle(self.uiWindow as *mut uiControl);
+                file_drop::accept_files(handle, self.uiWindow)?;
+            }
+            self.with_state(|state| state.accepts_files = true);
+        }
+        let data: FilesDroppedCallback = Box::new(callback);
+        ffi_tools::register_callback(self.uiWindow, "files_dropped", data);
+        Ok(())
+    }
+
     /// Sets the window's child widget. The window can only have one child widget at a time.
     pub fn set_child<T: Into<Control>>(&mut self, _ctx: &UI, child: T) {
-        unsafe { ui_sys::uiWindowSetChild(self.uiWindow, child.into().as_ui_control()) }
+        let child = child.into().as_ui_control();
+        unsafe { ui_sys::uiWindowSetChild(self.uiWindow, child) }
+        controls::disown_all(self.uiWindow as *mut uiControl);
+        controls::adopt(self.uiWindow as *mut uiControl, child, None);
     }
 
     /// Allow the user to select an existing file.
-    pub fn open_file(&self, _ctx: &UI) -> Option<PathBuf> {
-        let ptr = unsafe { ui_sys::uiOpenFile(self.uiWindow) };
-        if ptr.is_null() {
-            return None;
-        };
-        let path_string: String = unsafe { CStr::from_ptr(ptr).to_string_lossy().into() };
-        Some(path_string.into())
+    /// Returns `None` if the user cancels the dialog.
+    ///
+    /// Paths are kept as they are on Unix. Elsewhere, `libui` returns them as UTF-8, and this
+    /// fails with [`Utf8`](../enum.UIError.html#variant.Utf8) if a path it returns is not.
+    pub fn open_file(&self, _ctx: &UI) -> Result<Option<PathBuf>, UIError> {
+        unsafe { path_from_ui_text(ui_sys::uiOpenFile(self.uiWindow)) }
     }
 
     /// Allow the user to select a new or existing file.
-    pub fn save_file(&self, _ctx: &UI) -> Option<PathBuf> {
-        let ptr = unsafe { ui_sys::uiSaveFile(self.uiWindow) };
-        if ptr.is_null() {
-            return None;
-        };
-        let path_string: String = unsafe { CStr::from_ptr(ptr).to_string_lossy().into() };
-        Some(path_string.into())
+    /// Returns `None` if the user cancels the dialog, and fails as
+    /// [`open_file`](#method.open_file) does.
+    pub fn save_file(&self, _ctx: &UI) -> Result<Option<PathBuf>, UIError> {
+        unsafe { path_from_ui_text(ui_sys::uiSaveFile(self.uiWindow)) }
+    }
+
+    /// Allow the user to select an existing folder, with the dialog libui-ng shows for it.
+    /// Returns `None` if the user cancels the dialog, and fails as
+    /// [`open_file`](#method.open_file) does.
+    pub fn open_folder(&self, _ctx: &UI) -> Result<Option<PathBuf>, UIError> {
+        unsafe { path_from_ui_text(ui_sys::uiOpenFolder(self.uiWindow)) }
     }
 
     /// Open a generic message box to show a message to the user.
     /// Returns when the user acknowledges the message.
+    ///
+    /// The message box is modal to this window and runs its own event loop while it is open, so
+    /// it is safe to call from within callbacks such as a button's `on_clicked`. `libui`'s
+    /// message boxes are informational only: they have a single button and cannot ask the user
+    /// to confirm or cancel. For a yes/no question, build a small window with your own buttons.
     pub fn modal_msg(&self, _ctx: &UI, title: &str, description: &str) {
         unsafe {
-            let c_title = CString::new(title.as_bytes().to_vec()).unwrap();
-            let c_description = CString::new(description.as_bytes().to_vec()).unwrap();
+            let c_title = ffi_tools::to_c_string(title);
+            let c_description = ffi_tools::to_c_string(description);
             ui_sys::uiMsgBox(self.uiWindow, c_title.as_ptr(), c_description.as_ptr())
         }
     }
 
     /// Open an error-themed message box to show a message to the user.
     /// Returns when the user acknowledges the message.
+    ///
+    /// Like [`modal_msg`](struct.Window.html#method.modal_msg), this blocks until dismissed, can
+    /// be called from within callbacks, and cannot return a choice made by the user.
     pub fn modal_err(&self, _ctx: &UI, title: &str, description: &str) {
         unsafe {
-            let c_title = CString::new(title.as_bytes().to_vec()).unwrap();
-            let c_description = CString::new(description.as_bytes().to_vec()).unwrap();
+            let c_title = ffi_tools::to_c_string(title);
+            let c_description = ffi_tools::to_c_string(description);
             ui_sys::uiMsgBoxError(self.uiWindow, c_title.as_ptr(), c_description.as_ptr())
         }
     }
 
+    /// Create an `iui` struct for this control from the raw pointer for it. The new handle
+    /// counts towards keeping the window alive like any other.
+    ///
+    /// # Safety
+    /// The given pointer must point to a valid control or memory unsafety may result.
+    pub unsafe fn from_raw(window: *mut uiWindow) -> Window {
+        HANDLES.with(|handles| *handles.borrow_mut().entry(window).or_insert(0) += 1);
+        Window { uiWindow: window }
+    }
+
     pub unsafe fn destroy_all_windows() {
-        WINDOWS.with(|windows| {
-            let mut windows = windows.borrow_mut();
-            for window in windows.drain(..) {
-                window.destroy();
+        let windows = WINDOWS.with(|windows| mem::take(&mut *windows.borrow_mut()));
+        for window in windows {
+            Window::destroy_raw(window);
+        }
+    }
+
+    // Returns every window which has not been destroyed, in the order they were created.
+    pub(crate) fn all_windows() -> Vec<Window> {
+        let windows = WINDOWS.with(|windows| windows.borrow().clone());
+        windows
+            .into_iter()
+            .map(|window| unsafe { Window::from_raw(window) })
+            .collect()
+    }
+
+    // Hides the window `control` is in, if it is shown, returning it so that it can be shown
+    // again by `show_again`.
+    pub(crate) fn hide_window_of(control: *mut uiControl) -> Option<Window> {
+        let mut root = control;
+        loop {
+            let parent = unsafe { ui_sys::uiControlParent(root) };
+            if parent.is_null() {
+                break;
             }
-        })
+            root = parent;
+        }
+        let window = root as *mut uiWindow;
+        let tracked = WINDOWS.with(|windows| windows.borrow().contains(&window));
+        if !tracked || unsafe { ui_sys::uiControlVisible(root) } == 0 {
+            return None;
+        }
+        unsafe {
+            ui_sys::uiControlHide(root);
+            Some(Window::from_raw(window))
+        }
+    }
+
+    // Shows a window hidden by `hide_window_of`, unless it was destroyed since.
+    pub(crate) fn show_again(self) {
+        let alive = WINDOWS.with(|windows| windows.borrow().contains(&self.uiWindow));
+        if alive {
+            unsafe { ui_sys::uiControlShow(self.uiWindow as *mut uiControl) }
+        }
+    }
+
+    // Runs `f` on the extra state of this window, creating it if needed.
+    fn with_state<R, F: FnOnce(&mut WindowState) -> R>(&self, f: F) -> R {
+        WINDOW_STATES.with(|states| f(states.borrow_mut().entry(self.uiWindow).or_default()))
+    }
+
+    // Stops tracking this window, and destroys it along with the controls in it.
+    unsafe fn destroy_raw(window: *mut uiWindow) {
+        WINDOWS.with(|windows| windows.borrow_mut().retain(|&tracked| tracked != window));
+        WINDOW_STATES.with(|states| states.borrow_mut().remove(&window));
+        controls::destroy_tree(window as *mut uiControl);
     }
 
     /// Destroys a Window. Any use of the control after this is use-after-free; therefore, this
-    /// is marked unsafe.
+    /// is marked unsafe. Dropping the window's handles afterwards is fine.
+    ///
+    /// This does not run the window's [`on_closing`](struct.Window.html#method.on_closing)
+    /// callback.
     pub unsafe fn destroy(&self) {
-        // Don't check for initialization here since this can be run during deinitialization.
-        ui_sys::uiControlDestroy(self.uiWindow as *mut ui_sys::uiControl)
+        // Windows which are already gone, e.g. with the `UI`, are left alone.
+        if WINDOWS.with(|windows| windows.borrow().contains(&self.uiWindow)) {
+            // Don't check for initialization here since this can be run during deinitialization.
+            Window::destroy_raw(self.uiWindow)
+        }
+    }
+}
+
+/// Gathers the settings of a new [`Window`](struct.Window.html), which is only created, with
+/// all of them applied, by [`build`](#method.build).
+///
+/// Settings which are not given default to an untitled 640 by 480 window without a menubar,
+/// with margins, which can be resized.
+///
+/// ```
+/// # use iui::prelude::*;
+/// # use iui::controls::WindowBuilder;
+/// # let ui = UI::init().unwrap();
+/// let about = WindowBuilder::new()
+///     .title("About")
+///     .size(300, 150)
+///     .resizeable(false)
+///     .build(&ui);
+/// assert_eq!(about.title(&ui), "About");
+/// assert!(!about.resizeable(&ui));
+/// ```
+#[derive(Clone, Debug)]
+pub struct WindowBuilder {
+    title: String,
+    width: c_int,
+    height: c_int,
+    menubar: bool,
+    margined: bool,
+    resizeable: bool,
+}
+
+impl Default for WindowBuilder {
+    fn default() -> WindowBuilder {
+        WindowBuilder {
+            title: String::new(),
+            width: 640,
+            height: 480,
+            menubar: false,
+            margined: true,
+            resizeable: true,
+        }
+    }
+}
+
+impl WindowBuilder {
+    /// Start describing a window with the default settings.
+    pub fn new() -> WindowBuilder {
+        WindowBuilder::default()
+    }
+
+    /// Set the title of the window.
+    pub fn title(mut self, title: &str) -> WindowBuilder {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Set the size of the window's content area.
+    pub fn size(mut self, width: c_int, height: c_int) -> WindowBuilder {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set whether the window has a menubar, showing the menus created before it.
+    pub fn menubar(mut self, menubar: bool) -> WindowBuilder {
+        self.menubar = menubar;
+        self
+    }
+
+    /// Set whether the window draws margins around its child.
+    pub fn margined(mut self, margined: bool) -> WindowBuilder {
+        self.margined = margined;
+        self
+    }
+
+    /// Set whether the user can resize the window; see
+    /// [`Window::set_resizeable`](struct.Window.html#method.set_resizeable).
+    pub fn resizeable(mut self, resizeable: bool) -> WindowBuilder {
+        self.resizeable = resizeable;
+        self
+    }
+
+    /// Create the window. Like `Window::new`, it quits the application when closed until it
+    /// is given an `on_closing` callback, and is hidden until shown.
+    pub fn build(&self, ctx: &UI) -> Window {
+        let window_type = if self.menubar {
+            WindowType::HasMenubar
+        } else {
+            WindowType::NoMenubar
+        };
+        let mut window = Window::new(ctx, &self.title, self.width, self.height, window_type);
+        window.set_margined(ctx, self.margined);
+        // Last, as this fixes the window at the size it has been given.
+        if !self.resizeable {
+            window.set_resizeable(ctx, false);
+        }
+        window
+    }
+}
+
+/// Copy a path returned by one of libui's file or folder dialogs, and free libui's copy. A null pointer,
+/// meaning the dialog was cancelled, gives `None`.
+unsafe fn path_from_ui_text(ptr: *mut c_char) -> Result<Option<PathBuf>, UIError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    let path = path_from_bytes(CStr::from_ptr(ptr).to_bytes());
+    ui_sys::uiFreeText(ptr);
+    path.map(Some)
+}
+
+// Paths are arbitrary bytes on Unix, so keep them as-is even when they are not valid UTF-8.
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> Result<PathBuf, UIError> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    Ok(PathBuf::from(OsStr::from_bytes(bytes)))
+}
+
+// Elsewhere, libui hands out UTF-8.
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> Result<PathBuf, UIError> {
+    match ::std::str::from_utf8(bytes) {
+        Ok(path) => Ok(PathBuf::from(path)),
+        Err(error) => Err(UIError::Utf8 { error }),
+    }
+}
+
+/// Runs the `on_files_dropped` callback of `window`, called by the native drop handlers.
+pub(crate) fn files_dropped(window: *mut uiWindow, paths: &[PathBuf]) {
+    unsafe {
+        let callback =
+            ffi_tools::registered_callback::<_, FilesDroppedCallback>(window, "files_dropped");
+        if let Some(callback) = callback {
+            callback(&mut Window::from_raw(window), paths);
+        }
+    }
+}
+
+extern "C" fn content_size_changed_callback(window: *mut uiWindow, _data: *mut c_void) {
+    unsafe {
+        let mut window = Window::from_raw(window);
+        let mut size: (c_int, c_int) = (0, 0);
+        ui_sys::uiWindowContentSize(window.uiWindow, &mut size.0, &mut size.1);
+        if ui_sys::uiWindowFullscreen(window.uiWindow) == 0 {
+            let constrained = window.with_state(|state| state.constrain(size));
+            if constrained != size {
+                size = constrained;
+                ui_sys::uiWindowSetContentSize(window.uiWindow, size.0, size.1);
+            }
+        }
+        let callback = ffi_tools::registered_callback::<_, ContentSizeChangedCallback>(
+            window.uiWindow,
+            "content_size_changed",
+        );
+        if let Some(callback) = callback {
+            callback(&mut window, size.0, size.1);
+        }
+    }
+}
+
+extern "C" fn position_changed_callback(window: *mut uiWindow, data: *mut c_void) {
+    unsafe {
+        let mut window = Window::from_raw(window);
+        (*(data as *mut Box<dyn FnMut(&mut Window)>))(&mut window);
     }
 }
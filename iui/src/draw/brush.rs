@@ -1,20 +1,27 @@
 use std::marker::PhantomData;
 use std::ptr;
 use ui::UI;
+use util_types::Color;
 use ui_sys::{uiDrawBrush, uiDrawBrushType};
 
+/// A color a gradient passes through: `Pos` is where, from `0.0` at its start to `1.0` at its
+/// end, and `R`, `G`, `B` and `A` are the color's components, from `0.0` to `1.0`.
 pub use ui_sys::uiDrawBrushGradientStop as BrushGradientStop;
 
 /// Used to determine how a given stroke or fill is drawn.
 #[derive(Clone, Debug)]
 pub enum Brush {
+    /// A single color.
     Solid(SolidBrush),
+    /// A gradient along a line.
     LinearGradient(LinearGradientBrush),
+    /// A gradient spreading out from a point to a circle.
     RadialGradient(RadialGradientBrush),
+    /// Not implemented by libUI yet; drawing with it does nothing useful.
     Image,
 }
 
-/// A reference to a DrawBrush
+/// A brush as described to libUI, borrowing the `Brush` it was made from.
 #[derive(Clone, Debug)]
 pub struct BrushRef<'a> {
     ui_draw_brush: uiDrawBrush,
@@ -22,6 +29,56 @@ pub struct BrushRef<'a> {
 }
 
 impl Brush {
+    /// Creates a brush painting with a single color.
+    pub fn solid(color: Color) -> Brush {
+        Brush::Solid(SolidBrush {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        })
+    }
+
+    /// Creates a brush painting a gradient along the line from `(start_x, start_y)` to
+    /// `(end_x, end_y)`, going through `stops` in order of position.
+    pub fn linear_gradient(
+        start_x: f64,
+        start_y: f64,
+        end_x: f64,
+        end_y: f64,
+        stops: Vec<BrushGradientStop>,
+    ) -> Brush {
+        Brush::LinearGradient(LinearGradientBrush {
+            start_x,
+            start_y,
+            end_x,
+            end_y,
+            stops,
+        })
+    }
+
+    /// Creates a brush painting a gradient from the point `(start_x, start_y)` to the circle of
+    /// the given center and radius, going through `stops` in order of position.
+    pub fn radial_gradient(
+        start_x: f64,
+        start_y: f64,
+        outer_circle_center_x: f64,
+        outer_circle_center_y: f64,
+        outer_radius: f64,
+        stops: Vec<BrushGradientStop>,
+    ) -> Brush {
+        Brush::RadialGradient(RadialGradientBrush {
+            start_x,
+            start_y,
+            outer_circle_center_x,
+            outer_circle_center_y,
+            outer_radius,
+            stops,
+        })
+    }
+
+    /// Describes this brush to libUI. The returned `BrushRef` points into the brush's gradient
+    /// stops, so it borrows the brush for as long as it is used.
     pub fn as_ui_draw_brush_ref(&self, _ctx: &UI) -> BrushRef {
         match *self {
             Brush::Solid(ref solid_brush) => BrushRef {
@@ -130,20 +187,31 @@ pub struct SolidBrush {
 /// A brush that paints a linear gradient.
 #[derive(Clone, Debug)]
 pub struct LinearGradientBrush {
+    /// Horizontal position of the start of the gradient.
     pub start_x: f64,
+    /// Vertical position of the start of the gradient.
     pub start_y: f64,
+    /// Horizontal position of the end of the gradient.
     pub end_x: f64,
+    /// Vertical position of the end of the gradient.
     pub end_y: f64,
+    /// The colors the gradient goes through, in order of position.
     pub stops: Vec<BrushGradientStop>,
 }
 
 /// A brush that paints a radial gradient.
 #[derive(Clone, Debug)]
 pub struct RadialGradientBrush {
+    /// Horizontal position of the point the gradient starts from.
     pub start_x: f64,
+    /// Vertical position of the point the gradient starts from.
     pub start_y: f64,
+    /// Horizontal position of the center of the circle the gradient ends on.
     pub outer_circle_center_x: f64,
+    /// Vertical position of the center of the circle the gradient ends on.
     pub outer_circle_center_y: f64,
+    /// Radius of the circle the gradient ends on.
     pub outer_radius: f64,
+    /// The colors the gradient goes through, in order of position.
     pub stops: Vec<BrushGradientStop>,
 }
@@ -1,10 +1,13 @@
-use draw::{Brush, Path, StrokeParams, Transform};
+use draw::{Brush, Path, StrokeParams, TextLayout, Transform};
+use std::cell::Cell;
 use ui::UI;
 use ui_sys::{self, uiDrawContext};
 
 /// Drawing context, used to draw custom content on the screen.
 pub struct DrawContext {
     ui_draw_context: *mut uiDrawContext,
+    /// The number of saves not yet matched by a restore.
+    saves: Cell<usize>,
 }
 
 impl DrawContext {
@@ -15,7 +18,8 @@ impl DrawContext {
     /// If libui is not initialized, behavior will be inconsistent.
     pub unsafe fn from_ui_draw_context(ui_draw_context: *mut uiDrawContext) -> DrawContext {
         DrawContext {
-            ui_draw_context: ui_draw_context,
+            ui_draw_context,
+            saves: Cell::new(0),
         }
     }
 
@@ -41,18 +45,148 @@ impl DrawContext {
         }
     }
 
+    /// Draw the given TextLayout on this DrawContext, with its top-left corner at the given
+    /// point.
+    ///
+    /// ```no_run
+    /// # use iui::prelude::*;
+    /// # use iui::controls::AreaDrawParams;
+    /// use iui::Color;
+    /// use iui::draw::{AttributedString, FontDescriptor, TextAlign, TextAttribute, TextLayout, TextWeight};
+    ///
+    /// # fn draw(ui: &UI, params: &AreaDrawParams) {
+    /// let mut string = AttributedString::new(ui, "Hello, ").unwrap();
+    /// let start = string.len(ui);
+    /// string.append_unattributed(ui, "world").unwrap();
+    /// let end = string.len(ui);
+    /// string.set_attribute(ui, &TextAttribute::Weight(TextWeight::BOLD), start, end);
+    /// string.set_attribute(ui, &TextAttribute::Color(Color::new(0.8, 0.1, 0.1, 1.0)), start, end);
+    ///
+    /// let font = FontDescriptor::new("Helvetica", 14.0);
+    /// let layout = TextLayout::new(ui, &string, &font, params.area_width, TextAlign::Left);
+    /// params.context.draw_text(ui, &layout, 0.0, 0.0);
+    /// # }
+    /// ```
+    pub fn draw_text(&self, _ctx: &UI, layout: &TextLayout, x: f64, y: f64) {
+        unsafe { ui_sys::uiDrawText(self.ui_draw_context, layout.ptr(), x, y) }
+    }
+
     /// Transform this DrawContext by the given Transform.
     pub fn transform(&self, _ctx: &UI, txform: &Transform) {
         unsafe { ui_sys::uiDrawTransform(self.ui_draw_context, txform.ptr()) }
     }
 
-    /// Open a modal allowing the user to save the contents of this DrawContext.
+    /// Restrict all further drawing to the inside of the given Path, in addition to any clip
+    /// already in place: clips can only ever be narrowed, until the next `restore`.
+    pub fn clip(&self, _ctx: &UI, path: &Path) {
+        unsafe { ui_sys::uiDrawClip(self.ui_draw_context, path.ptr()) }
+    }
+
+    /// Save the current transform and clip of this DrawContext, so that they can be brought
+    /// back with `restore`. Saves nest: each `restore` undoes the changes made since the
+    /// matching `save`. Saves left unmatched when the draw handler returns are restored then,
+    /// so clips and transforms never leak out of a draw.
+    ///
+    /// ```no_run
+    /// # use iui::prelude::*;
+    /// # use iui::controls::AreaDrawParams;
+    /// # use iui::Color;
+    /// # use iui::draw::{Brush, FillMode, Path};
+    /// # fn draw(ui: &UI, params: &AreaDrawParams) {
+    /// let context = &params.context;
+    /// context.save(ui);
+    ///
+    /// // Only the left half of the area can be drawn on until `restore`.
+    /// let half = Path::new(ui, FillMode::Winding);
+    /// half.add_rectangle(ui, 0.0, 0.0, params.area_width / 2.0, params.area_height);
+    /// half.end(ui);
+    /// context.clip(ui, &half);
+    ///
+    /// let all = Path::new(ui, FillMode::Winding);
+    /// all.add_rectangle(ui, 0.0, 0.0, params.area_width, params.area_height);
+    /// all.end(ui);
+    /// context.fill(ui, &all, &Brush::solid(Color::new(1.0, 0.0, 0.0, 1.0)));
+    ///
+    /// context.restore(ui);
+    /// # }
+    /// ```
     pub fn save(&self, _ctx: &UI) {
+        self.saves.set(self.saves.get() + 1);
         unsafe { ui_sys::uiDrawSave(self.ui_draw_context) }
     }
 
-    /// Open a modal allowing the user to load the contents of a DrawContext onto this one.
+    /// Bring back the transform and clip saved by the last unmatched `save`. Does nothing if
+    /// every `save` has been matched already, as the platforms do not agree on what that does.
     pub fn restore(&self, _ctx: &UI) {
-        unsafe { ui_sys::uiDrawRestore(self.ui_draw_context) }
+        if self.saves.get() > 0 {
+            self.saves.set(self.saves.get() - 1);
+            unsafe { ui_sys::uiDrawRestore(self.ui_draw_context) }
+        }
+    }
+
+    /// Returns the number of calls to `save` which have not been matched by a `restore` yet.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Area, AreaDrawParams, AreaHandler};
+    /// # use iui::draw::{FillMode, Path};
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// // Draws two tiles, each clipped to its own half of the area, recording how many saves
+    /// // are pending at each point.
+    /// struct Tiles {
+    ///     ui: UI,
+    ///     depths: Rc<RefCell<Vec<usize>>>,
+    /// }
+    ///
+    /// impl AreaHandler for Tiles {
+    ///     fn draw(&mut self, _area: &Area, params: &AreaDrawParams) {
+    ///         let (ui, context) = (&self.ui, &params.context);
+    ///         let mut depths = self.depths.borrow_mut();
+    ///         context.save(ui);
+    ///         for tile in 0..2 {
+    ///             context.save(ui);
+    ///             let half = Path::new(ui, FillMode::Winding);
+    ///             half.add_rectangle(ui, tile as f64 * 50.0, 0.0, 50.0, 50.0);
+    ///             half.end(ui);
+    ///             context.clip(ui, &half);
+    ///             depths.push(context.saved(ui));
+    ///             context.restore(ui);
+    ///         }
+    ///         context.restore(ui);
+    ///         depths.push(context.saved(ui));
+    ///         // A restore too many is ignored.
+    ///         context.restore(ui);
+    ///         depths.push(context.saved(ui));
+    ///     }
+    /// }
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let depths = Rc::new(RefCell::new(Vec::new()));
+    /// let mut window = Window::new(&ui, "Tiles", 100, 50, WindowType::NoMenubar);
+    /// let tiles = Tiles { ui: ui.clone(), depths: depths.clone() };
+    /// window.set_child(&ui, Area::new(&ui, Box::new(tiles)));
+    /// window.show(&ui);
+    ///
+    /// // Run the event loop until the area has been drawn.
+    /// let mut event_loop = ui.event_loop();
+    /// let deadline = Instant::now() + Duration::from_secs(10);
+    /// while depths.borrow().is_empty() && Instant::now() < deadline {
+    ///     event_loop.step(&ui, false);
+    /// }
+    /// assert_eq!(depths.borrow()[..4], [2, 2, 0, 0]);
+    /// ```
+    pub fn saved(&self, _ctx: &UI) -> usize {
+        self.saves.get()
+    }
+
+    /// Restore every save which has not been matched, once the draw handler has returned.
+    pub(crate) fn restore_all(&self) {
+        while self.saves.get() > 0 {
+            self.saves.set(self.saves.get() - 1);
+            unsafe { ui_sys::uiDrawRestore(self.ui_draw_context) }
+        }
     }
 }
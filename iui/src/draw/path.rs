@@ -2,8 +2,19 @@ use libc::c_int;
 use ui::UI;
 use ui_sys::{self, uiDrawPath};
 
+/// How the inside of a path is decided when filling it: `Winding` (the nonzero rule) fills every
+/// area the path winds around, while `Alternate` (the even-odd rule) leaves holes where figures
+/// overlap.
 pub use ui_sys::uiDrawFillMode as FillMode;
 
+/// A shape made of one or more figures, each a sequence of lines and curves, which can be
+/// filled or stroked with [`DrawContext`](struct.DrawContext.html).
+///
+/// A path is built by starting a figure with `new_figure` or `new_figure_with_arc`, extending it
+/// with `line_to`, `arc_to` and `bezier_to`, and optionally closing it with `close_figure`;
+/// rectangles can be added as figures of their own. Once built, it must be finished with `end`
+/// before being drawn, and can no longer be changed. The native path is freed when the `Path`
+/// is dropped.
 pub struct Path {
     ui_draw_path: *mut uiDrawPath,
 }
@@ -15,6 +26,7 @@ impl Drop for Path {
 }
 
 impl Path {
+    /// Creates a new, empty path, filled according to `fill_mode`.
     pub fn new(_ctx: &UI, fill_mode: FillMode) -> Path {
         unsafe {
             Path {
@@ -23,10 +35,14 @@ impl Path {
         }
     }
 
+    /// Starts a new figure at the given point.
     pub fn new_figure(&self, _ctx: &UI, x: f64, y: f64) {
         unsafe { ui_sys::uiDrawPathNewFigure(self.ui_draw_path, x, y) }
     }
 
+    /// Starts a new figure with a circular arc around the given center. Angles are in radians,
+    /// with `start_angle` measured from the positive x axis; the arc spans `sweep` radians,
+    /// clockwise unless `negative` is set.
     pub fn new_figure_with_arc(
         &self,
         _ctx: &UI,
@@ -50,10 +66,14 @@ impl Path {
         }
     }
 
+    /// Adds a straight line from the current point to the given point.
     pub fn line_to(&self, _ctx: &UI, x: f64, y: f64) {
         unsafe { ui_sys::uiDrawPathLineTo(self.ui_draw_path, x, y) }
     }
 
+    /// Adds a circular arc to the figure, taking the same parameters as
+    /// [`new_figure_with_arc`](#method.new_figure_with_arc). A line is drawn from the current
+    /// point to the start of the arc.
     pub fn arc_to(
         &self,
         _ctx: &UI,
@@ -77,6 +97,8 @@ impl Path {
         }
     }
 
+    /// Adds a cubic Bézier curve from the current point to `(end_x, end_y)`, with the control
+    /// points `(c1x, c1y)` and `(c2x, c2y)`.
     pub fn bezier_to(
         &self,
         _ctx: &UI,
@@ -90,14 +112,17 @@ impl Path {
         unsafe { ui_sys::uiDrawPathBezierTo(self.ui_draw_path, c1x, c1y, c2x, c2y, end_x, end_y) }
     }
 
+    /// Closes the current figure with a line back to its starting point.
     pub fn close_figure(&self, _ctx: &UI) {
         unsafe { ui_sys::uiDrawPathCloseFigure(self.ui_draw_path) }
     }
 
+    /// Adds a rectangle as a new, closed figure.
     pub fn add_rectangle(&self, _ctx: &UI, x: f64, y: f64, width: f64, height: f64) {
         unsafe { ui_sys::uiDrawPathAddRectangle(self.ui_draw_path, x, y, width, height) }
     }
 
+    /// Finishes the path, after which it can be drawn but no longer changed.
     pub fn end(&self, _ctx: &UI) {
         unsafe { ui_sys::uiDrawPathEnd(self.ui_draw_path) }
     }
@@ -1,21 +1,52 @@
 use libc::c_double;
 use std::marker::PhantomData;
 use ui::UI;
-use ui_sys::uiDrawStrokeParams;
+use ui_sys::{uiDrawDefaultMiterLimit, uiDrawStrokeParams};
 
+/// How the ends of a stroke are drawn: cut `Flat` at the end, with a `Round` cap, or with a
+/// `Square` cap extending past the end by half the thickness.
 pub use ui_sys::uiDrawLineCap as LineCap;
+/// How the corners of a stroke are drawn: `Miter` extends the edges until they meet, `Round`
+/// rounds the corner off, and `Bevel` cuts it flat.
 pub use ui_sys::uiDrawLineJoin as LineJoin;
 
+/// The style of the line drawn by [`DrawContext::stroke`](struct.DrawContext.html#method.stroke).
+///
+/// The default is a solid line one point thick, with flat ends and mitered corners.
 #[derive(Clone, Debug)]
 pub struct StrokeParams {
+    /// How the ends of the line are drawn.
     pub cap: LineCap,
+    /// How the corners of the line are drawn.
     pub join: LineJoin,
+    /// The width of the line, in points.
     pub thickness: f64,
+    /// For `LineJoin::Miter`, how far a corner may extend, as a multiple of half the thickness,
+    /// before it is beveled instead. Usually
+    /// [`DEFAULT_MITER_LIMIT`](constant.DEFAULT_MITER_LIMIT.html).
     pub miter_limit: f64,
+    /// The lengths of the alternating dashes and gaps of the line, in points, starting with a
+    /// dash; the pattern repeats along the line. Empty for a solid line.
     pub dashes: Vec<f64>,
+    /// How far into the dash pattern the line starts, in points.
     pub dash_phase: f64,
 }
 
+impl Default for StrokeParams {
+    fn default() -> StrokeParams {
+        StrokeParams {
+            cap: LineCap::Flat,
+            join: LineJoin::Miter,
+            thickness: 1.0,
+            miter_limit: uiDrawDefaultMiterLimit,
+            dashes: Vec::new(),
+            dash_phase: 0.0,
+        }
+    }
+}
+
+/// Stroke parameters as described to libUI, borrowing the dashes of the `StrokeParams` they
+/// were made from.
 #[derive(Clone, Debug)]
 pub struct StrokeParamsRef<'a> {
     ui_draw_stroke_params: uiDrawStrokeParams,
@@ -23,6 +54,8 @@ pub struct StrokeParamsRef<'a> {
 }
 
 impl StrokeParams {
+    /// Describes these parameters to libUI. The returned `StrokeParamsRef` points into
+    /// `dashes`, so it borrows the parameters for as long as it is used.
     pub fn as_stroke_params_ref(&self, _ctx: &UI) -> StrokeParamsRef {
         StrokeParamsRef {
             ui_draw_stroke_params: uiDrawStrokeParams {
@@ -0,0 +1,502 @@
+//! Types describing fonts and styled text, for use with text drawing and the `FontButton`
+//! control.
+
+use error::UIError;
+use ffi_tools;
+use libc::c_char;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use ui::UI;
+use ui_sys::{self, uiAttribute, uiAttributedString, uiDrawTextLayout, uiFontDescriptor};
+use util_types::Color;
+
+pub use ui_sys::uiDrawTextAlign as TextAlign;
+pub use ui_sys::uiTextItalic as TextItalic;
+pub use ui_sys::uiTextStretch as TextStretch;
+pub use ui_sys::uiUnderline as Underline;
+pub use ui_sys::uiUnderlineColor as UnderlineColor;
+
+/// How heavy the strokes of a font are, from 0 to 1000. Any value in that range is valid;
+/// the constants name the common ones, and the closest weight the font has is used.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct TextWeight(pub u32);
+
+impl TextWeight {
+    pub const MINIMUM: TextWeight = TextWeight(ui_sys::uiTextWeightMinimum);
+    pub const THIN: TextWeight = TextWeight(ui_sys::uiTextWeightThin);
+    pub const ULTRA_LIGHT: TextWeight = TextWeight(ui_sys::uiTextWeightUltraLight);
+    pub const LIGHT: TextWeight = TextWeight(ui_sys::uiTextWeightLight);
+    pub const BOOK: TextWeight = TextWeight(ui_sys::uiTextWeightBook);
+    pub const NORMAL: TextWeight = TextWeight(ui_sys::uiTextWeightNormal);
+    pub const MEDIUM: TextWeight = TextWeight(ui_sys::uiTextWeightMedium);
+    pub const SEMI_BOLD: TextWeight = TextWeight(ui_sys::uiTextWeightSemiBold);
+    pub const BOLD: TextWeight = TextWeight(ui_sys::uiTextWeightBold);
+    pub const ULTRA_BOLD: TextWeight = TextWeight(ui_sys::uiTextWeightUltraBold);
+    pub const HEAVY: TextWeight = TextWeight(ui_sys::uiTextWeightHeavy);
+    pub const ULTRA_HEAVY: TextWeight = TextWeight(ui_sys::uiTextWeightUltraHeavy);
+    pub const MAXIMUM: TextWeight = TextWeight(ui_sys::uiTextWeightMaximum);
+}
+
+/// Describes a font: its family, size and style. When text is drawn with a descriptor, the
+/// installed font which matches it most closely is used.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FontDescriptor {
+    /// The name of the font family, like "Helvetica".
+    pub family: String,
+    /// The size of the font, in points.
+    pub size: f64,
+    pub weight: TextWeight,
+    pub italic: TextItalic,
+    pub stretch: TextStretch,
+}
+
+impl FontDescriptor {
+    /// Describe the regular style of the given font family, at the given size in points.
+    pub fn new(family: &str, size: f64) -> FontDescriptor {
+        FontDescriptor {
+            family: family.to_owned(),
+            size,
+            weight: TextWeight::NORMAL,
+            italic: TextItalic::Normal,
+            stretch: TextStretch::Normal,
+        }
+    }
+
+    /// Copy the fields out of a descriptor filled in by libui. The descriptor is not freed.
+    pub(crate) unsafe fn from_ui_font_descriptor(desc: &uiFontDescriptor) -> FontDescriptor {
+        FontDescriptor {
+            family: CStr::from_ptr(desc.Family).to_string_lossy().into_owned(),
+            size: desc.Size,
+            weight: TextWeight(desc.Weight),
+            italic: desc.Italic,
+            stretch: desc.Stretch,
+        }
+    }
+}
+
+/// A set of OpenType features, like ligatures or tabular figures, to turn on or off in text,
+/// given to it with `TextAttribute::Features`.
+///
+/// Each feature is named by its four-letter tag from the OpenType specification, and has a
+/// value: `0` turns it off and `1` on, while features offering several alternates take the
+/// number of the alternate. Features the font does not have are ignored.
+///
+/// ```
+/// # use iui::prelude::*;
+/// # use iui::draw::{AttributedString, OpenTypeFeatures, TextAttribute};
+/// # let ui = UI::init().unwrap();
+/// let mut features = OpenTypeFeatures::new();
+/// // Tabular figures, so that the digits of numbers in a column line up.
+/// features.add(*b"tnum", 1);
+/// // No ligatures.
+/// features.add(*b"liga", 0);
+/// assert_eq!(features.get(*b"tnum"), Some(1));
+///
+/// let mut total = AttributedString::new(&ui, "Total: ").unwrap();
+/// total.append_attributed(&ui, "1,234.50", &[TextAttribute::Features(features)]).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct OpenTypeFeatures {
+    features: BTreeMap<[u8; 4], u32>,
+}
+
+impl OpenTypeFeatures {
+    /// Creates an empty set of features, which leaves every feature at the font's default.
+    pub fn new() -> OpenTypeFeatures {
+        OpenTypeFeatures::default()
+    }
+
+    /// Sets the value of the feature with the given tag, replacing any previous value.
+    pub fn add(&mut self, tag: [u8; 4], value: u32) {
+        self.features.insert(tag, value);
+    }
+
+    /// Removes the feature with the given tag, leaving it at the font's default.
+    pub fn remove(&mut self, tag: [u8; 4]) {
+        self.features.remove(&tag);
+    }
+
+    /// Returns the value of the feature with the given tag, if it was added.
+    pub fn get(&self, tag: [u8; 4]) -> Option<u32> {
+        self.features.get(&tag).cloned()
+    }
+
+    /// Returns the tag and value of each feature, sorted by tag.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = ([u8; 4], u32)> + 'a {
+        self.features.iter().map(|(&tag, &value)| (tag, value))
+    }
+
+    /// Create the libUI attribute for these features. The caller owns it.
+    fn to_ui_attribute(&self) -> *mut uiAttribute {
+        unsafe {
+            let otf = ui_sys::uiNewOpenTypeFeatures();
+            for (tag, value) in self.iter() {
+                ui_sys::uiOpenTypeFeaturesAdd(
+                    otf,
+                    tag[0] as c_char,
+                    tag[1] as c_char,
+                    tag[2] as c_char,
+                    tag[3] as c_char,
+                    value,
+                );
+            }
+            // The attribute gets its own copy of the features.
+            let attribute = ui_sys::uiNewFeaturesAttribute(otf);
+            ui_sys::uiFreeOpenTypeFeatures(otf);
+            attribute
+        }
+    }
+}
+
+/// A style which can be given to a range of an [`AttributedString`](struct.AttributedString.html).
+#[derive(Clone, PartialEq, Debug)]
+pub enum TextAttribute {
+    /// The font family, like "Helvetica".
+    Family(String),
+    /// The font size, in points.
+    Size(f64),
+    Weight(TextWeight),
+    Italic(TextItalic),
+    Stretch(TextStretch),
+    /// The color of the text itself.
+    Color(Color),
+    /// The color behind the text.
+    Background(Color),
+    Underline(Underline),
+    /// The color of the underline: either the platform's color for the given kind of
+    /// underline, or, for `UnderlineColor::Custom`, the given color, which is ignored otherwise.
+    UnderlineColor(UnderlineColor, Color),
+    /// OpenType features to turn on or off, such as ligatures or small capitals.
+    Features(OpenTypeFeatures),
+}
+
+impl TextAttribute {
+    /// Create the libUI attribute for this style. The caller owns it.
+    fn to_ui_attribute(&self) -> *mut uiAttribute {
+        unsafe {
+            match *self {
+                TextAttribute::Family(ref family) => {
+                    let c_family = ffi_tools::to_c_string(family);
+                    ui_sys::uiNewFamilyAttribute(c_family.as_ptr())
+                }
+                TextAttribute::Size(size) => ui_sys::uiNewSizeAttribute(size),
+                TextAttribute::Weight(weight) => ui_sys::uiNewWeightAttribute(weight.0),
+                TextAttribute::Italic(italic) => ui_sys::uiNewItalicAttribute(italic),
+                TextAttribute::Stretch(stretch) => ui_sys::uiNewStretchAttribute(stretch),
+                TextAttribute::Color(color) => {
+                    let Color { r, g, b, a } = color.clamped();
+                    ui_sys::uiNewColorAttribute(r, g, b, a)
+                }
+                TextAttribute::Background(color) => {
+                    let Color { r, g, b, a } = color.clamped();
+                    ui_sys::uiNewBackgroundAttribute(r, g, b, a)
+                }
+                TextAttribute::Underline(underline) => ui_sys::uiNewUnderlineAttribute(underline),
+                TextAttribute::UnderlineColor(kind, color) => {
+                    let Color { r, g, b, a } = color.clamped();
+                    ui_sys::uiNewUnderlineColorAttribute(kind, r, g, b, a)
+                }
+                TextAttribute::Features(ref features) => features.to_ui_attribute(),
+            }
+        }
+    }
+}
+
+/// A common style for [`AttributedString::styled`](struct.AttributedString.html#method.styled)
+/// and [`append_styled`](struct.AttributedString.html#method.append_styled), standing for the
+/// `TextAttribute` it converts into.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Style {
+    /// `TextAttribute::Weight(TextWeight::BOLD)`.
+    Bold,
+    /// `TextAttribute::Italic(TextItalic::Italic)`.
+    Italic,
+    /// `TextAttribute::Color`.
+    Color(Color),
+    /// `TextAttribute::Underline(Underline::Single)`.
+    Underline,
+    /// `TextAttribute::Size`, in points.
+    Size(f64),
+}
+
+impl From<Style> for TextAttribute {
+    fn from(style: Style) -> TextAttribute {
+        match style {
+            Style::Bold => TextAttribute::Weight(TextWeight::BOLD),
+            Style::Italic => TextAttribute::Italic(TextItalic::Italic),
+            Style::Color(color) => TextAttribute::Color(color),
+            Style::Underline => TextAttribute::Underline(Underline::Single),
+            Style::Size(size) => TextAttribute::Size(size),
+        }
+    }
+}
+
+/// A string of text, with styles given to ranges of it, which can be laid out with a
+/// [`TextLayout`](struct.TextLayout.html) and drawn.
+///
+/// Positions in the string are byte indices, as for `str`, and must lie on character
+/// boundaries. Since `libui` would end the text at a NUL byte, moving those positions, text
+/// with one is rejected with [`NulInString`](../enum.UIError.html#variant.NulInString) rather
+/// than cut short. The native string is freed when the `AttributedString` is dropped.
+pub struct AttributedString {
+    ui_attributed_string: *mut uiAttributedString,
+}
+
+impl Drop for AttributedString {
+    fn drop(&mut self) {
+        unsafe { ui_sys::uiFreeAttributedString(self.ui_attributed_string) }
+    }
+}
+
+impl AttributedString {
+    /// Creates a new attributed string with the given text, without any styles.
+    ///
+    /// Fails if `text` contains a NUL byte, or with
+    /// [`NativeCallFailed`](../enum.UIError.html#variant.NativeCallFailed) if `libui` could not
+    /// create the string.
+    pub fn new(_ctx: &UI, text: &str) -> Result<AttributedString, UIError> {
+        let c_string = attributed_c_string(text)?;
+        let ui_attributed_string = unsafe { ui_sys::uiNewAttributedString(c_string.as_ptr()) };
+        if ui_attributed_string.is_null() {
+            return Err(UIError::NativeCallFailed {
+                function: "uiNewAttributedString",
+            });
+        }
+        Ok(AttributedString {
+            ui_attributed_string,
+        })
+    }
+
+    /// Returns the text of the string.
+    pub fn text(&self, _ctx: &UI) -> String {
+        unsafe {
+            // The text is owned by the attributed string, and must not be freed.
+            CStr::from_ptr(ui_sys::uiAttributedStringString(self.ui_attributed_string))
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    /// Returns the length of the text, in bytes.
+    pub fn len(&self, _ctx: &UI) -> usize {
+        unsafe { ui_sys::uiAttributedStringLen(self.ui_attributed_string) }
+    }
+
+    /// Appends text to the end of the string, without any styles. Fails if `text` contains a
+    /// NUL byte.
+    pub fn append_unattributed(&mut self, _ctx: &UI, text: &str) -> Result<(), UIError> {
+        let c_string = attributed_c_string(text)?;
+        unsafe {
+            ui_sys::uiAttributedStringAppendUnattributed(self.ui_attributed_string, c_string.as_ptr())
+        }
+        Ok(())
+    }
+
+    /// Appends text to the end of the string, with the given styles, so that styled text can
+    /// be built up piece by piece without working out byte ranges. Fails if `text` contains a
+    /// NUL byte.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::Color;
+    /// # use iui::draw::{AttributedString, TextAttribute, TextItalic, TextWeight};
+    /// # let ui = UI::init().unwrap();
+    /// let mut greeting = AttributedString::new(&ui, "").unwrap();
+    /// greeting.append_attributed(&ui, "Grüße", &[TextAttribute::Weight(TextWeight::BOLD)]).unwrap();
+    /// greeting.append_unattributed(&ui, ", ").unwrap();
+    /// greeting.append_attributed(
+    ///     &ui,
+    ///     "wörld",
+    ///     &[TextAttribute::Italic(TextItalic::Italic), TextAttribute::Color(Color::from_rgb8(204, 0, 0))],
+    /// ).unwrap();
+    /// assert_eq!(greeting.text(&ui), "Grüße, wörld");
+    ///
+    /// assert!(greeting.append_unattributed(&ui, "\0").is_err());
+    /// ```
+    pub fn append_attributed(
+        &mut self,
+        ctx: &UI,
+        text: &str,
+        attributes: &[TextAttribute],
+    ) -> Result<(), UIError> {
+        let start = self.len(ctx);
+        self.append_unattributed(ctx, text)?;
+        let end = self.len(ctx);
+        if end > start {
+            for attribute in attributes {
+                self.set_attribute(ctx, attribute, start, end);
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a new attributed string with the given text, all of it in the given styles.
+    /// Fails if `text` contains a NUL byte.
+    ///
+    /// Together with [`append_styled`](#method.append_styled), this builds rich text one
+    /// segment at a time, without working out the byte range of each segment:
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::Color;
+    /// # use iui::draw::{AttributedString, Style};
+    /// # let ui = UI::init().unwrap();
+    /// let red = Color::from_rgb8(204, 0, 0);
+    /// let mut warning = AttributedString::styled(&ui, "Achtung", &[Style::Bold, Style::Size(18.0)]).unwrap();
+    /// warning.append_unattributed(&ui, ": the disk is ").unwrap();
+    /// warning.append_styled(&ui, "größtenteils", &[Style::Italic, Style::Color(red)]).unwrap();
+    /// warning.append_styled(&ui, " full", &[Style::Underline]).unwrap();
+    /// assert_eq!(warning.text(&ui), "Achtung: the disk is größtenteils full");
+    /// ```
+    pub fn styled(ctx: &UI, text: &str, styles: &[Style]) -> Result<AttributedString, UIError> {
+        let mut string = AttributedString::new(ctx, "")?;
+        string.append_styled(ctx, text, styles)?;
+        Ok(string)
+    }
+
+    /// Appends text to the end of the string in the given styles, which only apply to the
+    /// appended text. Fails if `text` contains a NUL byte.
+    pub fn append_styled(&mut self, ctx: &UI, text: &str, styles: &[Style]) -> Result<(), UIError> {
+        let attributes: Vec<TextAttribute> = styles.iter().map(|&style| style.into()).collect();
+        self.append_attributed(ctx, text, &attributes)
+    }
+
+    /// Inserts text at the given position, without any styles. The styles after it are moved
+    /// along with the text they apply to. Fails if `text` contains a NUL byte.
+    ///
+    /// # Panics
+    /// Panics if `at` is past the end of the string or not on a character boundary.
+    pub fn insert_at_unattributed(&mut self, ctx: &UI, text: &str, at: usize) -> Result<(), UIError> {
+        self.check_position(ctx, at);
+        let c_string = attributed_c_string(text)?;
+        unsafe {
+            ui_sys::uiAttributedStringInsertAtUnattributed(
+                self.ui_attributed_string,
+                c_string.as_ptr(),
+                at,
+            )
+        }
+        Ok(())
+    }
+
+    /// Deletes the text from `start` up to, but not including, `end`.
+    ///
+    /// # Panics
+    /// Panics if the range is reversed, goes past the end of the string, or does not lie on
+    /// character boundaries.
+    pub fn delete(&mut self, ctx: &UI, start: usize, end: usize) {
+        self.check_range(ctx, start, end);
+        unsafe { ui_sys::uiAttributedStringDelete(self.ui_attributed_string, start, end) }
+    }
+
+    /// Gives a style to the text from `start` up to, but not including, `end`. It replaces
+    /// any style of the same kind which that text already had, e.g. another color.
+    ///
+    /// # Panics
+    /// Panics if the range is reversed, goes past the end of the string, or does not lie on
+    /// character boundaries.
+    pub fn set_attribute(&mut self, ctx: &UI, attribute: &TextAttribute, start: usize, end: usize) {
+        self.check_range(ctx, start, end);
+        unsafe {
+            // The string takes ownership of the attribute.
+            ui_sys::uiAttributedStringSetAttribute(
+                self.ui_attributed_string,
+                attribute.to_ui_attribute(),
+                start,
+                end,
+            )
+        }
+    }
+
+    /// Return the underlying pointer for this AttributedString.
+    pub fn ptr(&self) -> *mut uiAttributedString {
+        self.ui_attributed_string
+    }
+
+    fn check_position(&self, ctx: &UI, at: usize) {
+        assert!(
+            self.text(ctx).is_char_boundary(at),
+            "position {} is not a character boundary of the attributed string",
+            at
+        );
+    }
+
+    fn check_range(&self, ctx: &UI, start: usize, end: usize) {
+        assert!(start <= end, "range {}..{} of the attributed string is reversed", start, end);
+        self.check_position(ctx, start);
+        self.check_position(ctx, end);
+    }
+}
+
+/// Convert text for an `AttributedString`, failing on a NUL byte rather than cutting the text
+/// short there.
+fn attributed_c_string(text: &str) -> Result<CString, UIError> {
+    match text.find('\0') {
+        Some(position) => Err(UIError::NulInString { position }),
+        None => Ok(ffi_tools::to_c_string(text)),
+    }
+}
+
+/// An `AttributedString` laid out in lines, ready to be drawn with
+/// [`DrawContext::draw_text`](struct.DrawContext.html#method.draw_text).
+///
+/// The layout borrows the string it was made from, which can't be changed while the layout
+/// exists. The native layout is freed when the `TextLayout` is dropped.
+pub struct TextLayout<'a> {
+    ui_draw_text_layout: *mut uiDrawTextLayout,
+    phantom: PhantomData<&'a AttributedString>,
+}
+
+impl<'a> Drop for TextLayout<'a> {
+    fn drop(&mut self) {
+        unsafe { ui_sys::uiDrawFreeTextLayout(self.ui_draw_text_layout) }
+    }
+}
+
+impl<'a> TextLayout<'a> {
+    /// Lays out `string`, wrapping it to lines at most `width` points wide and aligning them
+    /// within that width. Text without a font style of its own uses `default_font`.
+    pub fn new(
+        _ctx: &UI,
+        string: &'a AttributedString,
+        default_font: &FontDescriptor,
+        width: f64,
+        align: TextAlign,
+    ) -> TextLayout<'a> {
+        unsafe {
+            let family = ffi_tools::to_c_string(&default_font.family);
+            let mut ui_font_descriptor = uiFontDescriptor {
+                Family: family.as_ptr() as *mut c_char,
+                Size: default_font.size,
+                Weight: default_font.weight.0,
+                Italic: default_font.italic,
+                Stretch: default_font.stretch,
+            };
+            let mut params = ui_sys::uiDrawTextLayoutParams {
+                String: string.ptr(),
+                DefaultFont: &mut ui_font_descriptor,
+                Width: width,
+                Align: align,
+            };
+            TextLayout {
+                ui_draw_text_layout: ui_sys::uiDrawNewTextLayout(&mut params),
+                phantom: PhantomData,
+            }
+        }
+    }
+
+    /// Returns the width and height of the laid out text, in points.
+    pub fn extents(&self, _ctx: &UI) -> (f64, f64) {
+        unsafe {
+            let mut extents = (0.0, 0.0);
+            ui_sys::uiDrawTextLayoutExtents(self.ui_draw_text_layout, &mut extents.0, &mut extents.1);
+            extents
+        }
+    }
+
+    /// Return the underlying pointer for this TextLayout.
+    pub fn ptr(&self) -> *mut uiDrawTextLayout {
+        self.ui_draw_text_layout
+    }
+}
@@ -1,8 +1,12 @@
-use std::mem::MaybeUninit;
 use std::ops::Mul;
 use ui_sys::{self, uiDrawMatrix};
 
-/// A transformation which can be applied to the contents of a DrawContext.
+/// An affine transformation which can be applied to the contents of a DrawContext, combining
+/// translations, scalings, rotations and skews.
+///
+/// The operations modify the transform in place; building one applied to the `DrawContext`
+/// with [`DrawContext::transform`](struct.DrawContext.html#method.transform) makes it
+/// straightforward to draw in zoomed or rotated coordinates.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Transform {
     ui_matrix: uiDrawMatrix,
@@ -18,10 +22,15 @@ impl Transform {
 
     /// Create a new Transform that does nothing.
     pub fn identity() -> Transform {
-        unsafe {
-            let mut matrix = MaybeUninit::uninit().assume_init();
-            ui_sys::uiDrawMatrixSetIdentity(&mut matrix);
-            Transform::from_ui_matrix(&matrix)
+        Transform {
+            ui_matrix: uiDrawMatrix {
+                M11: 1.0,
+                M12: 0.0,
+                M21: 0.0,
+                M22: 1.0,
+                M31: 0.0,
+                M32: 0.0,
+            },
         }
     }
 
@@ -35,7 +44,8 @@ impl Transform {
         unsafe { ui_sys::uiDrawMatrixScale(&mut self.ui_matrix, x_center, y_center, x, y) }
     }
 
-    /// Modify this Transform to rotate around the given center by the given angle.
+    /// Modify this Transform to rotate around the given center by the given angle, in radians.
+    /// Positive angles rotate clockwise, since the y axis points down.
     pub fn rotate(&mut self, x: f64, y: f64, angle: f64) {
         unsafe { ui_sys::uiDrawMatrixRotate(&mut self.ui_matrix, x, y, angle) }
     }
@@ -45,25 +55,76 @@ impl Transform {
         unsafe { ui_sys::uiDrawMatrixSkew(&mut self.ui_matrix, x, y, xamount, yamount) }
     }
 
-    /// Compose this Transform with another, creating a Transform which represents both operations.
+    /// Compose this Transform with another, creating a Transform which represents both operations:
+    /// this one, followed by `src`. `a * b` is the same as `a.compose(&b)`.
     pub fn compose(&mut self, src: &Transform) {
         unsafe { ui_sys::uiDrawMatrixMultiply(&mut self.ui_matrix, src.ptr()) }
     }
 
     /// Returns true if inverting this Transform is possible.
     pub fn invertible(&self) -> bool {
-        unsafe {
-            ui_sys::uiDrawMatrixInvertible(
-                &self.ui_matrix as *const uiDrawMatrix as *mut uiDrawMatrix,
-            ) != 0
-        }
+        self.determinant() != 0.0
     }
 
     /// Attempts to invert the Transform, returning true if it succeeded and false if it failed.
     pub fn invert(&mut self) -> bool {
-        unsafe { ui_sys::uiDrawMatrixInvert(&mut self.ui_matrix) != 0 }
+        match self.inverse() {
+            Some(inverse) => {
+                *self = inverse;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the inverse of this Transform, which undoes it, or `None` if it can't be
+    /// inverted (e.g. because it scales by zero).
+    ///
+    /// ```
+    /// use iui::draw::Transform;
+    ///
+    /// let mut zoom = Transform::identity();
+    /// zoom.scale(0.0, 0.0, 2.0, 2.0);
+    /// zoom.translate(10.0, 20.0);
+    ///
+    /// let point = (3.0, 4.0);
+    /// let on_area = zoom.transform_point(point);
+    /// let back = zoom.inverse().unwrap().transform_point(on_area);
+    /// assert!((back.0 - point.0).abs() < 1e-9 && (back.1 - point.1).abs() < 1e-9);
+    ///
+    /// assert_eq!(Transform::identity().inverse(), Some(Transform::identity()));
+    ///
+    /// let mut flatten = Transform::identity();
+    /// flatten.scale(0.0, 0.0, 0.0, 1.0);
+    /// assert!(flatten.inverse().is_none());
+    /// ```
+    pub fn inverse(&self) -> Option<Transform> {
+        // This is computed here rather than by libUI, which on Mac OS reports transforms that
+        // are their own inverse, like the identity, as not invertible.
+        let det = self.determinant();
+        if det == 0.0 {
+            return None;
+        }
+        let m = &self.ui_matrix;
+        Some(Transform {
+            ui_matrix: uiDrawMatrix {
+                M11: m.M22 / det,
+                M12: -m.M12 / det,
+                M21: -m.M21 / det,
+                M22: m.M11 / det,
+                M31: (m.M21 * m.M32 - m.M22 * m.M31) / det,
+                M32: (m.M12 * m.M31 - m.M11 * m.M32) / det,
+            },
+        })
+    }
+
+    fn determinant(&self) -> f64 {
+        self.ui_matrix.M11 * self.ui_matrix.M22 - self.ui_matrix.M12 * self.ui_matrix.M21
     }
 
+    /// Apply this Transform to a point, e.g. to find where something drawn at that point ends up
+    /// on the area. Inverting the Transform first goes the other way, from a point of the area
+    /// (such as the position of the mouse) to one in the transformed coordinates.
     pub fn transform_point(&self, mut point: (f64, f64)) -> (f64, f64) {
         unsafe {
             ui_sys::uiDrawMatrixTransformPoint(
@@ -75,6 +136,7 @@ impl Transform {
         }
     }
 
+    /// Apply this Transform to a size or distance, which is not affected by translations.
     pub fn transform_size(&self, mut size: (f64, f64)) -> (f64, f64) {
         unsafe {
             ui_sys::uiDrawMatrixTransformSize(
@@ -86,6 +148,7 @@ impl Transform {
         }
     }
 
+    /// Return the underlying pointer for this Transform.
     pub fn ptr(&self) -> *mut uiDrawMatrix {
         &self.ui_matrix as *const uiDrawMatrix as *mut uiDrawMatrix
     }
@@ -1,6 +1,18 @@
 //! Error types for this crate.
 
 /// The error type returned by functions in this crate which might fail.
+///
+/// Operations which cross into `libui` with data it could reject, or which depend on it
+/// succeeding, return it instead of panicking: the file dialogs, creating an
+/// [`Image`](controls/struct.Image.html) from pixel data, building an
+/// [`AttributedString`](draw/struct.AttributedString.html), and parsing a
+/// [`Color`](struct.Color.html) among them. Dialogs the user cancels return `Ok(None)`, as that
+/// is not an error. Other text is cut short at its first NUL byte on the way into `libui` (see
+/// the [crate documentation](index.html)), rather than failing.
+///
+/// The functions which still panic do so only on programming errors, like slicing a `str`
+/// does: positions in an `AttributedString` which are not on character boundaries, for
+/// instance.
 #[derive(Fail, Debug)]
 pub enum UIError {
     /// Signifies that the underlying library was unable to properly hook into the platform's GUI APIs.
@@ -10,11 +22,54 @@ pub enum UIError {
     /// one already existed.
     #[fail(display = "cannot initialize multiple instances of the libui toolkit")]
     MultipleInitError(),
-    /// Signifies that an attempt was made to remove a tab from a tab group that was out of bounds.
+    /// Signifies that an attempt was made to insert or remove a tab at an index of a tab group
+    /// that was out of bounds.
     #[fail(
-        display = "cannot remove index {} from tab group: there are only {} tabs in the group",
+        display = "index {} is out of bounds for tab group: there are only {} tabs in the group",
         index,
         n
     )]
     TabGroupIndexOutOfBounds { index: u64, n: u64 },
+    /// Signifies that an attempt was made to remove a child from a container at an index that
+    /// was out of bounds.
+    #[fail(
+        display = "cannot remove child {} from container: there are only {} children",
+        index,
+        n
+    )]
+    ChildIndexOutOfBounds { index: u64, n: u64 },
+    /// Signifies that the requested operation cannot be performed by the underlying library on
+    /// this platform.
+    #[fail(display = "{} is not supported by the underlying libui toolkit", operation)]
+    Unsupported { operation: &'static str },
+    /// Signifies that the pixel data given for an image does not have the length its size
+    /// requires, 4 bytes per pixel.
+    #[fail(
+        display = "image data is {} bytes long, but an image of that size needs {} bytes",
+        len,
+        expected
+    )]
+    ImageSizeMismatch { len: usize, expected: usize },
+    /// Signifies that an image has more pixels across or down than `libui` can take.
+    #[fail(display = "a {}x{} pixel image is too large", width, height)]
+    ImageTooLarge { width: u32, height: u32 },
+    /// Signifies that a string could not be parsed as a color.
+    #[fail(display = "{:?} is not a color of the form #RRGGBB or #RRGGBBAA", color)]
+    InvalidColor { color: String },
+    /// Signifies that text could not be put on the clipboard, for example because another
+    /// application had it open.
+    #[fail(display = "the clipboard is not available")]
+    ClipboardUnavailable(),
+    /// Signifies that text contains a NUL byte, at the given byte position, where `libui` would
+    /// end it.
+    #[fail(display = "text contains a NUL byte at position {}", position)]
+    NulInString { position: usize },
+    /// Signifies that text returned by `libui`, such as a path picked in a file dialog, is not
+    /// valid UTF-8 where it has to be.
+    #[fail(display = "libui returned invalid UTF-8: {}", error)]
+    Utf8 { error: ::std::str::Utf8Error },
+    /// Signifies that a function of `libui` failed, such as one returning a null pointer instead
+    /// of the object it was asked to create.
+    #[fail(display = "{} failed", function)]
+    NativeCallFailed { function: &'static str },
 }
@@ -1,10 +1,26 @@
 //! Utilities to manage the state of the interface to the libUI bindings.
-use libc::c_void;
+use libc::{c_char, c_int, c_void};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
 use std::mem;
 use std::sync::atomic::{AtomicBool, Ordering};
+use ui_sys;
 
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+/// Callbacks keyed by the address of the object they are attached to and the event they handle.
+type CallbackMap = HashMap<(usize, &'static str), Box<dyn Any>>;
+
+thread_local! {
+    // Callbacks handed to libUI, keyed by the address of the object they are attached to and the
+    // name of the event they handle.
+    static CALLBACKS: RefCell<CallbackMap> = RefCell::new(HashMap::new());
+    // Callbacks which have been replaced but may still be running, waiting to be dropped.
+    static RETIRED_CALLBACKS: RefCell<Vec<Box<dyn Any>>> = RefCell::new(Vec::new());
+}
+
 /// Set the global flag stating that libUI is initialized.
 ///
 /// # Unsafety
@@ -33,7 +49,106 @@ pub fn is_initialized() -> bool {
     INITIALIZED.load(Ordering::SeqCst)
 }
 
-// Transmute a void-void callback into a Box<Box<FnMut()>>> and call it
-pub extern "C" fn void_void_callback(data: *mut c_void) {
-    unsafe { mem::transmute::<*mut c_void, Box<Box<dyn FnMut()>>>(data)() }
+/// Take ownership of `callback` as the handler for `event` on the libUI object `owner`, and
+/// return a pointer to it which can be given to libUI as the callback's data.
+///
+/// Any callback previously registered for the same event on the same object is not dropped
+/// immediately, since it may be the one currently running (and registering its replacement);
+/// it is kept until the next call to `collect_retired_callbacks`.
+pub fn register_callback<P, T: 'static>(owner: *mut P, event: &'static str, callback: T) -> *mut c_void {
+    let mut data = Box::new(callback);
+    let ptr = &mut *data as *mut T as *mut c_void;
+    let previous = CALLBACKS.with(|callbacks| {
+        callbacks
+            .borrow_mut()
+            .insert((owner as usize, event), data as Box<dyn Any>)
+    });
+    if let Some(previous) = previous {
+        RETIRED_CALLBACKS.with(|retired| retired.borrow_mut().push(previous));
+    }
+    ptr
+}
+
+/// Release every callback registered on the libUI object `owner`, for instance because it has
+/// been destroyed. The callbacks are dropped by the next `collect_retired_callbacks`.
+pub fn unregister_callbacks<P>(owner: *mut P) {
+    let removed: Vec<Box<dyn Any>> = CALLBACKS.with(|callbacks| {
+        let mut callbacks = callbacks.borrow_mut();
+        let keys: Vec<_> = callbacks
+            .keys()
+            .filter(|&&(key_owner, _)| key_owner == owner as usize)
+            .cloned()
+            .collect();
+        keys.iter().filter_map(|key| callbacks.remove(key)).collect()
+    });
+    RETIRED_CALLBACKS.with(|retired| retired.borrow_mut().extend(removed));
+}
+
+/// Return the callback currently registered for `event` on the libUI object `owner`, so that it
+/// can be invoked in response to a change libUI does not report itself. Returns `None` if there
+/// is none, or if it is not a `T`, as happens when a control was destroyed without its callbacks
+/// being unregistered and another kind of control now has the same address.
+///
+/// # Safety
+/// The reference must not be used once the callback is replaced or unregistered, which may
+/// happen while it runs.
+pub unsafe fn registered_callback<'a, P, T: 'static>(
+    owner: *mut P,
+    event: &'static str,
+) -> Option<&'a mut T> {
+    CALLBACKS.with(|callbacks| {
+        callbacks
+            .borrow_mut()
+            .get_mut(&(owner as usize, event))
+            .and_then(|data| data.downcast_mut::<T>())
+            .map(|callback| &mut *(callback as *mut T))
+    })
+}
+
+/// Drop the callbacks which have been replaced or unregistered. This must only be called when
+/// no callback is running, e.g. between steps of the event loop.
+pub fn collect_retired_callbacks() {
+    let retired = RETIRED_CALLBACKS.with(|retired| mem::take(&mut *retired.borrow_mut()));
+    drop(retired);
+}
+
+/// Drop every registered callback. Called when the UI is torn down, after which libUI can no
+/// longer call any of them.
+pub fn clear_callbacks() {
+    let callbacks = CALLBACKS.with(|callbacks| mem::take(&mut *callbacks.borrow_mut()));
+    drop(callbacks);
+    collect_retired_callbacks();
+}
+
+/// Copy a string returned by libUI into an owned `String`, replacing invalid UTF-8, and free
+/// libUI's copy with `uiFreeText`.
+///
+/// Every libUI getter which returns a `char *` (`uiEntryText`, `uiWindowTitle`, ...) hands over
+/// a fresh allocation that the caller owns, so this must be used for all of them; strings
+/// borrowed from libUI objects, like `uiAttributedStringString`, must not go through it.
+///
+/// # Unsafety
+/// `ptr` must be a valid, non-null string allocated by libUI, and must not be used afterwards.
+pub unsafe fn copy_and_free_text(ptr: *mut c_char) -> String {
+    let text = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    ui_sys::uiFreeText(ptr);
+    text
+}
+
+/// Convert `text` into a C string to be given to libUI.
+///
+/// C strings end at the first NUL byte, so rather than failing on text containing one, the
+/// text is cut short there, as C code would read it anyway. This makes every function taking
+/// text safe to call with untrusted input.
+pub fn to_c_string(text: &str) -> CString {
+    let end = text.find('\0').unwrap_or(text.len());
+    CString::new(&text.as_bytes()[..end]).expect("the text was cut at its first NUL")
+}
+
+/// Convert `value` into a C `int` to be given to libUI, saturating at the bounds of `int`.
+///
+/// libUI takes `int`s where this crate takes 64-bit integers, as it once took `intmax_t`s;
+/// values out of range are moved to the nearest one that fits rather than wrapped around.
+pub fn to_c_int(value: i64) -> c_int {
+    value.clamp(c_int::MIN.into(), c_int::MAX.into()) as c_int
 }
@@ -24,26 +24,51 @@
 //! the UI, so do _not_ spin off your UI interactions into an alternative thread. You're likely to
 //! have problems on Mac OS.
 //!
+//! Text is handed to `libui` as C strings, which end at the first NUL byte, so any text given
+//! to this crate is cut short at its first `'\0'` instead of causing a panic:
+//!
+//! ```
+//! # use iui::prelude::*;
+//! # use iui::controls::Label;
+//! # let ui = UI::init().unwrap();
+//! let label = Label::new(&ui, "visible\0hidden");
+//! assert_eq!(label.text(&ui), "visible");
+//! ```
+//!
+//! The text of a [`draw::AttributedString`](draw/struct.AttributedString.html) is the exception:
+//! its styles are placed by byte position, which cutting the text short would move, so a NUL
+//! byte in it is an error.
+//!
 //! For code examples, see the [examples](https://github.com/LeoTindall/libui-rs/blob/master/iui/examples/) 
 //! directory.
 
+#[macro_use]
+extern crate bitflags;
 #[macro_use]
 extern crate failure;
 extern crate libc;
 extern crate ui_sys;
 
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
 pub mod controls;
 pub mod draw;
 mod error;
 mod ffi_tools;
 pub mod menus;
 mod ui;
+mod util_types;
 
 pub use error::UIError;
 pub use ui::{EventLoop, UI};
+pub use util_types::Color;
 
 /// Common imports are packaged into this module. It's meant to be glob-imported: `use iui::prelude::*`.
 pub mod prelude {
+    pub use controls::ControlExt;
     pub use controls::LayoutStrategy;
     pub use controls::{NumericEntry, TextEntry};
     pub use controls::{Window, WindowType};
@@ -0,0 +1,95 @@
+//! Macros for declaring layouts.
+
+/// Builds a [`VerticalBox`](controls/struct.VerticalBox.html) out of a comma-separated list
+/// of controls, appending them from top to bottom.
+///
+/// The first argument is the `UI` context, followed by a `;`. Each child is any expression
+/// producing a control, so boxes can be nested by writing `hbox!` or `vbox!` as a child:
+///
+/// - `stretchy child` appends the child with `LayoutStrategy::Stretchy`; without it the child
+///   is appended with `LayoutStrategy::Compact`.
+/// - `name = child` also assigns the child to `name`, so that it can still be used once it is
+///   in the box, for example to register callbacks. `name` must be declared beforehand.
+///
+/// ```
+/// # #[macro_use] extern crate iui;
+/// # use iui::prelude::*;
+/// # use iui::controls::{Button, Entry, Label};
+/// # fn main() {
+/// # let ui = UI::init().unwrap();
+/// let (name, mut ok, cancel);
+/// let form = vbox![ui;
+///     Label::new(&ui, "Name:"),
+///     stretchy name = Entry::new(&ui),
+///     hbox![ui;
+///         ok = Button::new(&ui, "OK"),
+///         cancel = Button::new(&ui, "Cancel"),
+///     ],
+/// ];
+///
+/// ok.on_clicked(&ui, {
+///     let ui = ui.clone();
+///     move |_| println!("Hello, {}", name.value(&ui))
+/// });
+/// # let _ = (form, cancel);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! vbox {
+    ($ctx:expr; $($children:tt)*) => {{
+        let mut parent = $crate::controls::VerticalBox::new(&$ctx);
+        $crate::__iui_append_children!(parent, $ctx; $($children)* ,);
+        parent
+    }};
+}
+
+/// Builds a [`HorizontalBox`](controls/struct.HorizontalBox.html) out of a comma-separated
+/// list of controls, appending them from left to right.
+///
+/// It takes the same arguments as [`vbox!`](macro.vbox.html).
+///
+/// ```
+/// # #[macro_use] extern crate iui;
+/// # use iui::prelude::*;
+/// # use iui::controls::{Button, Entry};
+/// # fn main() {
+/// # let ui = UI::init().unwrap();
+/// let search = hbox![ui; stretchy Entry::new(&ui), Button::new(&ui, "Search")];
+/// # let _ = search;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! hbox {
+    ($ctx:expr; $($children:tt)*) => {{
+        let mut parent = $crate::controls::HorizontalBox::new(&$ctx);
+        $crate::__iui_append_children!(parent, $ctx; $($children)* ,);
+        parent
+    }};
+}
+
+/// Appends each child of a `vbox!` or `hbox!` to the box `$parent`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __iui_append_children {
+    // The caller always adds a trailing comma, so there may be one or two left at the end.
+    ($parent:ident, $ctx:expr;) => {};
+    ($parent:ident, $ctx:expr; ,) => {};
+    ($parent:ident, $ctx:expr; stretchy $name:ident = $child:expr, $($rest:tt)*) => {
+        $name = $child;
+        $parent.append(&$ctx, $name.clone(), $crate::controls::LayoutStrategy::Stretchy);
+        $crate::__iui_append_children!($parent, $ctx; $($rest)*);
+    };
+    ($parent:ident, $ctx:expr; stretchy $child:expr, $($rest:tt)*) => {
+        $parent.append(&$ctx, $child, $crate::controls::LayoutStrategy::Stretchy);
+        $crate::__iui_append_children!($parent, $ctx; $($rest)*);
+    };
+    ($parent:ident, $ctx:expr; $name:ident = $child:expr, $($rest:tt)*) => {
+        $name = $child;
+        $parent.append(&$ctx, $name.clone(), $crate::controls::LayoutStrategy::Compact);
+        $crate::__iui_append_children!($parent, $ctx; $($rest)*);
+    };
+    ($parent:ident, $ctx:expr; $child:expr, $($rest:tt)*) => {
+        $parent.append(&$ctx, $child, $crate::controls::LayoutStrategy::Compact);
+        $crate::__iui_append_children!($parent, $ctx; $($rest)*);
+    };
+}
@@ -1,14 +1,84 @@
 //! Menus that appear at the top of windows, and the items that go in them.
 
 use controls::Window;
+use error::UIError;
+use ffi_tools;
 use libc::{c_int, c_void};
-use std::ffi::CString;
-use std::mem;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use ui_sys::{self, uiMenu, uiMenuItem, uiWindow};
 use UI;
 
+thread_local! {
+    static MENU_ITEMS: RefCell<HashMap<*mut uiMenuItem, MenuItemState>> = RefCell::new(HashMap::new());
+    static SPECIAL_ITEMS: Cell<SpecialItems> = Cell::new(SpecialItems::default())
+}
+
+bitflags! {
+    /// Modifier keys that can be part of a menu item's keyboard shortcut.
+    ///
+    /// Use `COMMAND` for the platform's primary shortcut modifier: it is shown as Cmd on Mac OS
+    /// and as Ctrl everywhere else, so `COMMAND` with `S` reads as the native "Save" shortcut on
+    /// every platform.
+    pub struct Modifiers: u8 {
+        /// The Control key, on every platform.
+        const CTRL = 1 << 0;
+        /// The Alt key (Option on Mac OS).
+        const ALT = 1 << 1;
+        /// The Shift key.
+        const SHIFT = 1 << 2;
+        /// The Super key (Windows key on Windows, Command on Mac OS).
+        const SUPER = 1 << 3;
+        /// The platform's primary shortcut modifier: Command on Mac OS and Control elsewhere.
+        const COMMAND = 1 << 4;
+    }
+}
+
+impl Modifiers {
+    /// Renders these modifiers and the given key as a human-readable shortcut, like `Ctrl+S`,
+    /// using the names the current platform uses for its keys.
+    fn accelerator_text(self, key: char) -> String {
+        let mac = cfg!(target_os = "macos");
+        let mut text = String::new();
+        if self.contains(Modifiers::CTRL) || (!mac && self.contains(Modifiers::COMMAND)) {
+            text.push_str("Ctrl+");
+        }
+        if self.contains(Modifiers::ALT) {
+            text.push_str(if mac { "Option+" } else { "Alt+" });
+        }
+        if self.contains(Modifiers::SHIFT) {
+            text.push_str("Shift+");
+        }
+        if self.contains(Modifiers::SUPER) || (mac && self.contains(Modifiers::COMMAND)) {
+            text.push_str(if mac { "Cmd+" } else { "Super+" });
+        }
+        text.extend(key.to_uppercase());
+        text
+    }
+}
+
+/// Which of the application-wide items `libui` allows only one of have been appended so far.
+#[derive(Clone, Copy, Default)]
+struct SpecialItems {
+    quit: bool,
+    preferences: bool,
+    about: bool,
+}
+
+/// A callback given to `MenuItem::on_clicked`.
+type ClickedCallback = Box<dyn FnMut(&MenuItem, &Window)>;
+
+/// State which `libui` does not let us read back from a `uiMenuItem`. It is kept here, keyed by
+/// the item, so that every handle to the same item sees the same values.
+struct MenuItemState {
+    text: Option<String>,
+    enabled: bool,
+    quit: bool,
+}
+
 /// A `MenuItem` represents an item that is shown in a `Menu`. Note that, unlike many controls,
-/// the text on `MenuItem`s cannot be changed after creation.
+/// the text on `MenuItem`s cannot be changed after creation; see
+/// [`set_text`](struct.MenuItem.html#method.set_text).
 #[derive(Clone)]
 pub struct MenuItem {
     ui_menu_item: *mut uiMenuItem,
@@ -16,6 +86,14 @@ pub struct MenuItem {
 
 /// A `Menu` represents one of the top-level menus at the top of a window. As that bar is unique
 /// per application, creating a new `Menu` shows it on all windows that support displaying menus.
+///
+/// Menus cannot be nested: `libui` has no notion of submenus, so a `Menu` can only hold plain
+/// items, check items and separators. To group related actions (for example, several export
+/// formats), either give them their own top-level `Menu` or append them as consecutive items
+/// fenced off with [`append_separator`](struct.Menu.html#method.append_separator).
+///
+/// Menus and their items belong to the application and are freed with the `UI`, so a `Menu` or
+/// `MenuItem` is a plain handle: clones refer to the same menu, and dropping them frees nothing.
 #[derive(Clone)]
 pub struct Menu {
     ui_menu: *mut uiMenu,
@@ -25,12 +103,26 @@ impl MenuItem {
     /// Enables the item, allowing it to be selected. This is the default state of a menu item.
     pub fn enable(&self, _ctx: &UI) {
         unsafe { ui_sys::uiMenuItemEnable(self.ui_menu_item) }
+        self.set_enabled_state(true);
     }
 
     /// Disables the item, preventing it from being selected and providing a visual cue to the
     /// user that it cannot be selected.
     pub fn disable(&self, _ctx: &UI) {
         unsafe { ui_sys::uiMenuItemDisable(self.ui_menu_item) }
+        self.set_enabled_state(false);
+    }
+
+    /// Returns `true` if the item is enabled and can be selected, and `false` if it has been
+    /// disabled. All clones of a `MenuItem` share this state.
+    pub fn enabled(&self, _ctx: &UI) -> bool {
+        MENU_ITEMS.with(|items| {
+            items
+                .borrow()
+                .get(&self.ui_menu_item)
+                .map(|state| state.enabled)
+                .unwrap_or(true)
+        })
     }
 
     /// Returns `true` if the menu item is checked, and false if it is not checked (or not checkable).
@@ -45,16 +137,49 @@ impl MenuItem {
         unsafe { ui_sys::uiMenuItemSetChecked(self.ui_menu_item, checked as c_int) }
     }
 
+    /// Returns the text the item was created with, or `None` if its label is not known to
+    /// this crate (for instance, if the item was created from a raw pointer).
+    pub fn text(&self, _ctx: &UI) -> Option<String> {
+        MENU_ITEMS.with(|items| {
+            items
+                .borrow()
+                .get(&self.ui_menu_item)
+                .and_then(|state| state.text.clone())
+        })
+    }
+
+    /// Changes the text shown on the item.
+    ///
+    /// `libui` does not currently provide a way to relabel a menu item on any platform, so
+    /// this always returns an [`Unsupported`](../enum.UIError.html#variant.Unsupported) error
+    /// and leaves the item unchanged. To show a dynamic label, append one item per label and
+    /// enable or disable them as needed.
+    pub fn set_text(&self, _ctx: &UI, _text: &str) -> Result<(), UIError> {
+        Err(UIError::Unsupported {
+            operation: "changing the text of a menu item",
+        })
+    }
+
     /// Sets the function to be executed when the item is clicked/selected.
-    pub fn on_clicked<F: FnMut(&MenuItem, &Window)>(&self, _ctx: &UI, callback: F) {
+    ///
+    /// This cannot be used on the item returned by
+    /// [`Menu::append_quit_item`](struct.Menu.html#method.append_quit_item); use
+    /// [`UI::on_should_quit`](../struct.UI.html#method.on_should_quit) instead.
+    ///
+    /// Setting a new function replaces the previous one, which is then dropped. All functions
+    /// are dropped when the `UI` is torn down.
+    pub fn on_clicked<F: FnMut(&MenuItem, &Window) + 'static>(&self, _ctx: &UI, callback: F) {
+        debug_assert!(
+            !self.is_quit_item(),
+            "The Quit menu item is handled by UI::on_should_quit and cannot have its own on_clicked handler."
+        );
         unsafe {
-            let mut data: Box<Box<dyn FnMut(&MenuItem, &Window)>> = Box::new(Box::new(callback));
+            let data: ClickedCallback = Box::new(callback);
             ui_sys::uiMenuItemOnClicked(
                 self.ui_menu_item,
                 c_callback,
-                &mut *data as *mut Box<dyn FnMut(&MenuItem, &Window)> as *mut c_void,
+                ffi_tools::register_callback(self.ui_menu_item, "clicked", data),
             );
-            mem::forget(data);
         }
 
         extern "C" fn c_callback(
@@ -67,10 +192,7 @@ impl MenuItem {
                     ui_menu_item: menu_item,
                 };
                 let window = Window::from_raw(window);
-                mem::transmute::<*mut c_void, &mut Box<dyn FnMut(&MenuItem, &Window)>>(data)(
-                    &menu_item, &window,
-                );
-                mem::forget(window);
+                (*(data as *mut ClickedCallback))(&menu_item, &window);
             }
         }
     }
@@ -79,13 +201,51 @@ impl MenuItem {
     pub unsafe fn from_raw(raw: *mut uiMenuItem) -> Self {
         MenuItem { ui_menu_item: raw }
     }
+
+    // Creates a `MenuItem` from a freshly appended raw item, recording its text.
+    fn new_appended(raw: *mut uiMenuItem, text: Option<&str>, quit: bool) -> Self {
+        MENU_ITEMS.with(|items| {
+            items.borrow_mut().insert(
+                raw,
+                MenuItemState {
+                    text: text.map(|text| text.to_owned()),
+                    enabled: true,
+                    quit,
+                },
+            )
+        });
+        MenuItem { ui_menu_item: raw }
+    }
+
+    fn set_enabled_state(&self, enabled: bool) {
+        MENU_ITEMS.with(|items| {
+            if let Some(state) = items.borrow_mut().get_mut(&self.ui_menu_item) {
+                state.enabled = enabled;
+            }
+        })
+    }
+
+    fn is_quit_item(&self) -> bool {
+        MENU_ITEMS.with(|items| {
+            items
+                .borrow()
+                .get(&self.ui_menu_item)
+                .is_some_and(|state| state.quit)
+        })
+    }
+}
+
+/// Forgets all menu item state. Called when the UI is torn down, as the items go with it.
+pub(crate) fn clear_menu_state() {
+    MENU_ITEMS.with(|items| items.borrow_mut().clear());
+    SPECIAL_ITEMS.with(|special| special.set(SpecialItems::default()));
 }
 
 impl Menu {
     /// Creates a new menu with the given name to be displayed in the menubar at the top of the window.
     pub fn new(_ctx: &UI, name: &str) -> Menu {
         unsafe {
-            let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
+            let c_string = ffi_tools::to_c_string(name);
             Menu {
                 ui_menu: ui_sys::uiNewMenu(c_string.as_ptr()),
             }
@@ -95,23 +255,107 @@ impl Menu {
     /// Adds a new item with the given name to the menu.
     pub fn append_item(&self, name: &str) -> MenuItem {
         unsafe {
-            let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
-            MenuItem {
-                ui_menu_item: ui_sys::uiMenuAppendItem(self.ui_menu, c_string.as_ptr()),
-            }
+            let c_string = ffi_tools::to_c_string(name);
+            MenuItem::new_appended(
+                ui_sys::uiMenuAppendItem(self.ui_menu, c_string.as_ptr()),
+                Some(name),
+                false,
+            )
+        }
+    }
+
+    /// Adds a new item with the given name to the menu, and displays the given keyboard shortcut
+    /// next to it (for example, `Ctrl+S`, or `Cmd+S` on Mac OS for `Modifiers::COMMAND`).
+    ///
+    /// `libui` has no way of binding keys to menu items, so this only shows the shortcut as a
+    /// tab-separated hint in the item's text: pressing the keys does not select the item. If the
+    /// shortcut should work, handle the key press yourself, for instance in an `Area`. How the
+    /// hint is laid out is up to the platform; some toolkits show the tab literally.
+    ///
+    /// [`MenuItem::text`](struct.MenuItem.html#method.text) returns only `name`, without the hint.
+    pub fn append_item_with_accel(&self, name: &str, modifiers: Modifiers, key: char) -> MenuItem {
+        let label = format!("{}\t{}", name, modifiers.accelerator_text(key));
+        unsafe {
+            let c_string = ffi_tools::to_c_string(&label);
+            MenuItem::new_appended(
+                ui_sys::uiMenuAppendItem(self.ui_menu, c_string.as_ptr()),
+                Some(name),
+                false,
+            )
         }
     }
 
     /// Adds a new togglable (checkbox) item with the given name to the menu.
     pub fn append_check_item(&self, name: &str) -> MenuItem {
         unsafe {
-            let c_string = CString::new(name.as_bytes().to_vec()).unwrap();
-            MenuItem {
-                ui_menu_item: ui_sys::uiMenuAppendCheckItem(self.ui_menu, c_string.as_ptr()),
-            }
+            let c_string = ffi_tools::to_c_string(name);
+            MenuItem::new_appended(
+                ui_sys::uiMenuAppendCheckItem(self.ui_menu, c_string.as_ptr()),
+                Some(name),
+                false,
+            )
         }
     }
 
+    /// Adds the application's Quit item to the menu. Its text is provided by the platform, and
+    /// on Mac OS it is placed in the application menu rather than in this menu.
+    ///
+    /// Only one Quit item may exist in an application. Selecting it does not run an
+    /// `on_clicked` handler; instead, the handler given to
+    /// [`UI::on_should_quit`](../struct.UI.html#method.on_should_quit) decides whether the
+    /// application quits.
+    ///
+    /// # Panics
+    /// In debug builds, panics if a Quit item has already been added.
+    pub fn append_quit_item(&self) -> MenuItem {
+        SPECIAL_ITEMS.with(|special| {
+            let mut items = special.get();
+            debug_assert!(!items.quit, "Only one Quit menu item may be added to an application.");
+            items.quit = true;
+            special.set(items);
+        });
+        unsafe { MenuItem::new_appended(ui_sys::uiMenuAppendQuitItem(self.ui_menu), None, true) }
+    }
+
+    /// Adds the application's Preferences item to the menu. Its text is provided by the
+    /// platform, and on Mac OS it is placed in the application menu rather than in this menu.
+    ///
+    /// Only one Preferences item may exist in an application.
+    ///
+    /// # Panics
+    /// In debug builds, panics if a Preferences item has already been added.
+    pub fn append_preferences_item(&self) -> MenuItem {
+        SPECIAL_ITEMS.with(|special| {
+            let mut items = special.get();
+            debug_assert!(
+                !items.preferences,
+                "Only one Preferences menu item may be added to an application."
+            );
+            items.preferences = true;
+            special.set(items);
+        });
+        unsafe {
+            MenuItem::new_appended(ui_sys::uiMenuAppendPreferencesItem(self.ui_menu), None, false)
+        }
+    }
+
+    /// Adds the application's About item to the menu. Its text is provided by the platform, and
+    /// on Mac OS it is placed in the application menu rather than in this menu.
+    ///
+    /// Only one About item may exist in an application.
+    ///
+    /// # Panics
+    /// In debug builds, panics if an About item has already been added.
+    pub fn append_about_item(&self) -> MenuItem {
+        SPECIAL_ITEMS.with(|special| {
+            let mut items = special.get();
+            debug_assert!(!items.about, "Only one About menu item may be added to an application.");
+            items.about = true;
+            special.set(items);
+        });
+        unsafe { MenuItem::new_appended(ui_sys::uiMenuAppendAboutItem(self.ui_menu), None, false) }
+    }
+
     /// Adds a seperator to the menu.
     pub fn append_separator(&self) {
         unsafe { ui_sys::uiMenuAppendSeparator(self.ui_menu) }
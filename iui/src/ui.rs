@@ -3,14 +3,40 @@ use ffi_tools;
 use libc::{c_int, c_void};
 use ui_sys;
 
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::mem;
-use std::rc::Rc;
+use std::ptr;
+use std::rc::{Rc, Weak};
+use std::sync::Mutex;
 use std::thread::sleep;
 use std::time::Duration;
 
-use controls::Window;
+use controls::{self, Control, Window};
+use menus;
+
+/// Functions given to `UI::queue_main` which have not run yet, or `None` while libUI is not
+/// initialized. libUI is only handed the key of each function, so that the functions still
+/// pending when the UI is torn down can be dropped.
+static MAIN_QUEUE: Mutex<Option<MainQueue>> = Mutex::new(None);
+
+struct MainQueue {
+    next_key: usize,
+    pending: HashMap<usize, Box<dyn FnOnce() + Send>>,
+}
+
+thread_local! {
+    // Functions given to `UI::on_timer` whose timers are still running, keyed by the value handed
+    // to libUI as the timer's data.
+    static TIMERS: RefCell<HashMap<usize, Box<dyn FnMut() -> bool>>> = RefCell::new(HashMap::new());
+    static NEXT_TIMER: Cell<usize> = const { Cell::new(0) };
+    // Whether an event loop is running, i.e. whether `uiQuit` has a loop to stop.
+    static LOOP_RUNNING: Cell<bool> = const { Cell::new(false) };
+    // The UI initialized on this thread, if any, for `UI::current`.
+    static CURRENT_UI: RefCell<Weak<UIToken>> = const { RefCell::new(Weak::new()) };
+}
 
 /// RAII guard for the UI; when dropped, it uninits libUI.
 struct UIToken {
@@ -24,9 +50,21 @@ impl Drop for UIToken {
             ffi_tools::is_initialized(),
             "Attempted to uninit libUI in UIToken destructor when libUI was not initialized!"
         );
+        // Stop accepting queued functions, and drop the ones that will never run.
+        let pending = MAIN_QUEUE.lock().unwrap().take();
+        drop(pending);
         unsafe {
             Window::destroy_all_windows();
+            controls::clear_table_state();
             ui_sys::uiUninit();
+            menus::clear_menu_state();
+            controls::clear_entry_state();
+            controls::clear_area_state();
+            controls::clear_children();
+            controls::clear_token_state();
+            ffi_tools::clear_callbacks();
+            TIMERS.with(|timers| timers.borrow_mut().clear());
+            LOOP_RUNNING.with(|running| running.set(false));
             ffi_tools::unset_initialized();
         }
     }
@@ -81,9 +119,13 @@ impl UI {
             if err.is_null() {
                 // Success! We can safely give the user a token allowing them to do UI things.
                 ffi_tools::set_initialized();
-                Ok(UI {
-                    token: Rc::new(UIToken { _pd: PhantomData }),
-                })
+                *MAIN_QUEUE.lock().unwrap() = Some(MainQueue {
+                    next_key: 0,
+                    pending: HashMap::new(),
+                });
+                let token = Rc::new(UIToken { _pd: PhantomData });
+                CURRENT_UI.with(|current| *current.borrow_mut() = Rc::downgrade(&token));
+                Ok(UI { token })
             } else {
                 // Error occurred; copy the string describing it, then free that memory.
                 let error_string = CStr::from_ptr(err).to_string_lossy().into_owned();
@@ -95,6 +137,25 @@ impl UI {
         }
     }
 
+    /// Returns a handle to the UI if it is initialized and this is its GUI thread, the thread
+    /// which called `init`.
+    ///
+    /// This is how functions given to [`queue_main`](struct.UI.html#method.queue_main), which
+    /// can't capture a `UI` since it is not `Send`, get one once they run on the GUI thread.
+    ///
+    /// ```
+    /// # use iui::UI;
+    /// # use std::thread;
+    /// let ui = UI::init().unwrap();
+    /// assert!(UI::current().is_some());
+    /// assert!(thread::spawn(|| UI::current().is_none()).join().unwrap());
+    /// ```
+    pub fn current() -> Option<UI> {
+        CURRENT_UI
+            .with(|current| current.borrow().upgrade())
+            .map(|token| UI { token })
+    }
+
     /// Hands control of this thread to the UI toolkit, allowing it to display the UI and respond to events.
     /// Does not return until the UI [quit](struct.UI.html#method.quit)s.
     ///
@@ -106,6 +167,7 @@ impl UI {
     /// Returns an `EventLoop`, a struct that allows you to step over iterations or events in the UI.
     pub fn event_loop(&self) -> EventLoop {
         unsafe { ui_sys::uiMainSteps() };
+        LOOP_RUNNING.with(|running| running.set(true));
         return EventLoop {
             _pd: PhantomData,
             callback: None,
@@ -114,45 +176,314 @@ impl UI {
 
     /// Running this function causes the UI to quit, exiting from [main](struct.UI.html#method.main) and no longer showing any widgets.
     ///
-    /// Run in every window's default `on_closing` callback.
+    /// Run in every window's default `on_closing` callback. It can be called from any callback,
+    /// e.g. that of a button; the event loop stops once the callback returns, and functions
+    /// still waiting in [`queue_main`](struct.UI.html#method.queue_main) are dropped along
+    /// with the `UI`. Calling this when no event loop is running, such as before `main`, does
+    /// nothing.
+    ///
+    /// ```no_run
+    /// use iui::prelude::*;
+    /// use iui::controls::Button;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Quit", 200, 100, WindowType::NoMenubar);
+    /// let mut button = Button::new(&ui, "Quit");
+    /// let ui_handle = ui.clone();
+    /// button.on_clicked(&ui, move |_| ui_handle.quit());
+    /// window.set_child(&ui, button);
+    /// window.show(&ui);
+    ///
+    /// ui.main();
+    /// ```
     pub fn quit(&self) {
-        unsafe { ui_sys::uiQuit() }
+        if LOOP_RUNNING.with(|running| running.get()) {
+            unsafe { ui_sys::uiQuit() }
+        }
     }
 
-    /// Queues a function to be executed on the GUI threa when next possible. Returns
+    /// Queues a function to be executed on the GUI thread when next possible. Returns
     /// immediately, not waiting for the function to be executed.
     ///
-    /// # Example
+    /// This can be called from any thread, which makes it the way for background threads to
+    /// update the interface: controls can only be used on the GUI thread, where the function
+    /// runs. Functions run in the order they were queued, each exactly once. If the UI is not
+    /// initialized, or is torn down before a function gets to run, the function is dropped
+    /// without running.
+    ///
+    /// Controls can't be moved into the function, as they are not `Send`; a
+    /// [`ControlToken`](controls/struct.ControlToken.html) can, and the function gets the control
+    /// back from it, along with the UI from [`current`](struct.UI.html#method.current). See
+    /// `ControlToken` for an example.
+    pub fn queue_main<F: FnOnce() + Send + 'static>(callback: F) {
+        let mut queue = MAIN_QUEUE.lock().unwrap();
+        if let Some(ref mut queue) = *queue {
+            let key = queue.next_key;
+            queue.next_key += 1;
+            queue.pending.insert(key, Box::new(callback));
+            // The lock is held until libUI has the function, so the UI can't be torn down in
+            // between.
+            unsafe { ui_sys::uiQueueMain(c_callback, key as *mut c_void) }
+        }
+
+        extern "C" fn c_callback(data: *mut c_void) {
+            let callback = MAIN_QUEUE
+                .lock()
+                .unwrap()
+                .as_mut()
+                .and_then(|queue| queue.pending.remove(&(data as usize)));
+            if let Some(callback) = callback {
+                callback();
+            }
+        }
+    }
+
+    /// Calls `callback` every `ms` milliseconds until it returns `false`, at which point the timer
+    /// stops and the callback is dropped.
+    ///
+    /// The timer fires on the GUI thread, from within the event loop: the callback only runs
+    /// while the loop is running (through `main`, or by stepping an `EventLoop`), and is delayed
+    /// for as long as the thread is busy handling other events. The resolution is that of the
+    /// platform's timers, which count milliseconds but are not precise to the millisecond, so
+    /// the interval should be treated as a minimum. A timer which is still running when the UI
+    /// is dropped is stopped, and its callback dropped.
+    ///
+    /// ```no_run
+    /// use iui::prelude::*;
+    /// use iui::controls::Label;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Countdown", 200, 100, WindowType::NoMenubar);
+    /// let mut label = Label::new(&ui, "10");
+    /// window.set_child(&ui, label.clone());
+    /// window.show(&ui);
+    ///
+    /// let mut remaining = 10;
+    /// let ui_handle = ui.clone();
+    /// ui.on_timer(1000, move || {
+    ///     remaining -= 1;
+    ///     label.set_text(&ui_handle, &remaining.to_string());
+    ///     remaining > 0
+    /// });
+    ///
+    /// ui.main();
+    /// ```
+    pub fn on_timer<F: FnMut() -> bool + 'static>(&self, ms: u32, callback: F) {
+        start_timer(ms, callback)
+    }
+
+    /// Returns a function which calls `callback` with the last value given to it, once `ms`
+    /// milliseconds have passed without it being called again, so that a burst of calls
+    /// results in a single one. Dropping the function cancels a pending call.
+    ///
+    /// Like [`on_timer`](#method.on_timer), the call is made from the event loop.
+    /// [`Entry::on_changed_debounced`](controls/struct.Entry.html#method.on_changed_debounced)
+    /// gives such a function to `on_changed`.
     ///
     /// ```
+    /// # use iui::prelude::*;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use std::thread;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let calls = Rc::new(RefCell::new(Vec::new()));
+    /// let mut changed = ui.debounce(50, {
+    ///     let calls = calls.clone();
+    ///     move |text: String| calls.borrow_mut().push(text)
+    /// });
+    ///
+    /// // A burst of changes, as typing "abc" makes.
+    /// for text in &["a", "ab", "abc"] {
+    ///     changed(text.to_string());
+    /// }
+    /// assert!(calls.borrow().is_empty());
+    ///
+    /// // Run the event loop until well past the delay.
+    /// let mut event_loop = ui.event_loop();
+    /// let deadline = Instant::now() + Duration::from_millis(500);
+    /// while Instant::now() < deadline {
+    ///     event_loop.step(&ui, false);
+    ///     thread::sleep(Duration::from_millis(1));
+    /// }
+    /// assert_eq!(*calls.borrow(), ["abc"]);
+    /// ```
+    pub fn debounce<T: 'static, F: FnMut(T) + 'static>(&self, ms: u32, callback: F) -> impl FnMut(T) {
+        struct Debounce<F> {
+            // Numbers the calls, so that a timer can tell whether another call followed it.
+            calls: Cell<u64>,
+            callback: RefCell<F>,
+        }
+
+        let debounce = Rc::new(Debounce {
+            calls: Cell::new(0),
+            callback: RefCell::new(callback),
+        });
+        move |value| {
+            let call = debounce.calls.get() + 1;
+            debounce.calls.set(call);
+            // Only the returned function owns the state, so dropping it cancels the timers.
+            let pending = Rc::downgrade(&debounce);
+            let mut value = Some(value);
+            start_timer(ms, move || {
+                if let Some(debounce) = pending.upgrade() {
+                    if debounce.calls.get() == call {
+                        if let Some(value) = value.take() {
+                            (*debounce.callback.borrow_mut())(value);
+                        }
+                    }
+                }
+                false
+            });
+        }
+    }
+
+    /// Runs `f`, which is meant to change many controls inside `container` at once, such as
+    /// populating a large form, so that the user does not see its window being laid out again
+    /// after each change.
+    ///
+    /// `libui` cannot suspend layout or drawing, so this is only worth it for windows which are
+    /// already shown: controls are best added before their window is first shown, which needs
+    /// no batching at all. The window holding `container` is hidden while `f` runs and shown
+    /// again afterwards, even if `f` panics, unless `f` destroys it; other windows are left
+    /// alone. The window briefly disappears instead of flickering. If `container` is not in a
+    /// shown window, `f` is simply run.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # use iui::controls::{Entry, Form};
+    /// # let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Settings", 400, 600, WindowType::NoMenubar);
+    /// let mut form = Form::new(&ui);
+    /// window.set_child(&ui, form.clone());
+    /// window.show(&ui);
+    ///
+    /// // Later, when the settings are reloaded.
+    /// ui.with_batched_layout(form.clone(), || {
+    ///     for i in 0..500 {
+    ///         form.append(&ui, &format!("Setting {}", i), Entry::new(&ui), LayoutStrategy::Compact);
+    ///     }
+    /// });
+    /// assert!(ui.is_shown(window));
+    /// ```
+    pub fn with_batched_layout<T: Into<Control>, R, F: FnOnce() -> R>(&self, container: T, f: F) -> R {
+        struct ShowOnDrop(Option<Window>);
+
+        impl Drop for ShowOnDrop {
+            fn drop(&mut self) {
+                if let Some(window) = self.0.take() {
+                    window.show_again();
+                }
+            }
+        }
+
+        let _hidden = ShowOnDrop(Window::hide_window_of(container.into().as_ui_control()));
+        f()
+    }
+
+    /// Returns a handle to every window which has not been destroyed yet, in the order they were
+    /// created.
+    ///
+    /// The handles keep the windows alive like any other, so they are best dropped once done
+    /// with rather than stored.
+    ///
+    /// ```
+    /// # use iui::prelude::*;
+    /// # let ui = UI::init().unwrap();
+    /// let first = Window::new(&ui, "First document", 400, 300, WindowType::NoMenubar);
+    /// let second = Window::new(&ui, "Second document", 400, 300, WindowType::NoMenubar);
+    /// let titles: Vec<String> = ui.windows().iter().map(|window| window.title(&ui)).collect();
+    /// assert_eq!(titles, ["First document", "Second document"]);
+    /// # drop((first, second));
+    /// ```
+    pub fn windows(&self) -> Vec<Window> {
+        Window::all_windows()
+    }
+
+    /// Asks every window to [`close`](controls/struct.Window.html#method.close), as if the user
+    /// had clicked each close button, and returns whether they all did. Windows whose
+    /// `on_closing` callback refuses stay open, and the others are still closed.
+    ///
+    /// Calling it from [`on_should_quit`](struct.UI.html#method.on_should_quit) lets every
+    /// document window veto quitting, for example to save its changes first:
+    ///
+    /// ```no_run
     /// use iui::prelude::*;
     ///
     /// let ui = UI::init().unwrap();
+    /// let mut documents = Vec::new();
+    /// for i in 0..3 {
+    ///     let mut document = Window::new(&ui, &format!("Document {}", i), 400, 300, WindowType::NoMenubar);
+    ///     // Ask whether to save changes here; returning `true` lets the window close.
+    ///     document.on_closing(&ui, |_| true);
+    ///     document.show(&ui);
+    ///     documents.push(document);
+    /// }
     ///
-    /// ui.queue_main(|| { println!("Runs first") } );
-    /// ui.queue_main(|| { println!("Runs second") } );
-    /// ui.quit();
+    /// let ui_handle = ui.clone();
+    /// ui.on_should_quit(move || ui_handle.close_windows());
+    /// ui.main();
     /// ```
-    pub fn queue_main<F: FnMut()>(&self, callback: F) {
-        unsafe {
-            let mut data: Box<Box<dyn FnMut()>> = Box::new(Box::new(callback));
-            ui_sys::uiQueueMain(
-                ffi_tools::void_void_callback,
-                &mut *data as *mut Box<dyn FnMut()> as *mut c_void,
-            );
-            mem::forget(data);
+    pub fn close_windows(&self) -> bool {
+        let mut all_closed = true;
+        for mut window in self.windows() {
+            if !window.close(self) {
+                all_closed = false;
+            }
         }
+        all_closed
     }
 
-    /// Set a callback to be run when the application quits.
-    pub fn on_should_quit<F: FnMut()>(&self, callback: F) {
+    /// Sets the function deciding whether the application may quit, replacing any previous one.
+    ///
+    /// It is called once each time the user asks the application to quit, through the Quit item
+    /// of the application menu (see
+    /// [`Menu::append_quit_item`](menus/struct.Menu.html#method.append_quit_item)) or the
+    /// platform's own means, like Cmd+Q on Mac OS. If it returns `true` the event loop is
+    /// stopped as if [`quit`](struct.UI.html#method.quit) had been called; if it returns `false`
+    /// the quit is cancelled. When no function is set, the application always quits.
+    ///
+    /// Closing windows is decided separately, by each window's
+    /// [`on_closing`](controls/struct.Window.html#method.on_closing) callback.
+    pub fn on_should_quit<F: FnMut() -> bool + 'static>(&self, callback: F) {
+        // The quit handler belongs to the application rather than to any libUI object.
+        let owner = ptr::null_mut::<c_void>();
+        let data: Box<dyn FnMut() -> bool> = Box::new(callback);
         unsafe {
-            let mut data: Box<Box<dyn FnMut()>> = Box::new(Box::new(callback));
-            ui_sys::uiOnShouldQuit(
-                ffi_tools::void_void_callback,
-                &mut *data as *mut Box<dyn FnMut()> as *mut c_void,
-            );
-            mem::forget(data);
+            ui_sys::uiOnShouldQuit(c_callback, ffi_tools::register_callback(owner, "should_quit", data));
+        }
+
+        extern "C" fn c_callback(data: *mut c_void) -> c_int {
+            unsafe { (*(data as *mut Box<dyn FnMut() -> bool>))() as c_int }
+        }
+    }
+}
+
+/// Starts a timer calling `callback` every `ms` milliseconds until it returns `false`; see
+/// `UI::on_timer`. Only to be used while the UI is initialized, from the GUI thread.
+pub(crate) fn start_timer<F: FnMut() -> bool + 'static>(ms: u32, callback: F) {
+    let key = NEXT_TIMER.with(|next| {
+        let key = next.get();
+        next.set(key + 1);
+        key
+    });
+    TIMERS.with(|timers| timers.borrow_mut().insert(key, Box::new(callback)));
+    unsafe { ui_sys::uiTimer(ms as c_int, c_callback, key as *mut c_void) }
+
+    extern "C" fn c_callback(data: *mut c_void) -> c_int {
+        let key = data as usize;
+        // The callback is taken out while it runs, so that it can start other timers.
+        let callback = TIMERS.with(|timers| timers.borrow_mut().remove(&key));
+        match callback {
+            Some(mut callback) => {
+                let again = callback();
+                if again {
+                    TIMERS.with(|timers| timers.borrow_mut().insert(key, callback));
+                }
+                again as c_int
+            }
+            None => false as c_int,
         }
     }
 }
@@ -178,30 +509,61 @@ impl EventLoop {
         self.callback = Some(Box::new(callback));
     }
 
-    /// Executes a tick in the event loop, returning immediately.
-    /// The `on_tick` callback is executed after the UI step.
+    /// Runs a single iteration of the event loop, then the `on_tick` callback, for integrating
+    /// libUI into a loop owned by the application.
     ///
-    /// Returns `true` if the application should continue running, and `false`
-    /// if it should quit.
-    pub fn next_tick(&mut self, _ctx: &UI) -> bool {
-        let result = unsafe { ui_sys::uiMainStep(false as c_int) == 1 };
+    /// With `wait` set to `false`, the events already pending are handled and the function
+    /// returns immediately, even if there were none; calling it regularly, e.g. once per frame of
+    /// a game loop, keeps the interface responsive. With `wait` set to `true`, the thread is
+    /// handed to the toolkit until at least one event arrives and has been handled, which avoids
+    /// spinning when the application has nothing else to do.
+    ///
+    /// Returns `true` if the application should continue running, and `false` if it should quit.
+    ///
+    /// ```no_run
+    /// use iui::prelude::*;
+    ///
+    /// let ui = UI::init().unwrap();
+    /// let mut window = Window::new(&ui, "Stepping", 200, 100, WindowType::NoMenubar);
+    /// window.show(&ui);
+    ///
+    /// let mut event_loop = ui.event_loop();
+    /// while event_loop.step(&ui, false) {
+    ///     // Do the application's own work between UI iterations.
+    /// }
+    /// ```
+    pub fn step(&mut self, _ctx: &UI, wait: bool) -> bool {
+        let result = unsafe { ui_sys::uiMainStep(wait as c_int) == 1 };
+        if !result {
+            LOOP_RUNNING.with(|running| running.set(false));
+        }
+        ffi_tools::collect_retired_callbacks();
         if let Some(ref mut c) = self.callback {
             c();
         }
         result
     }
 
+    /// Executes a tick in the event loop, returning immediately.
+    /// The `on_tick` callback is executed after the UI step.
+    ///
+    /// Returns `true` if the application should continue running, and `false`
+    /// if it should quit.
+    ///
+    /// Equivalent to [`step(ctx, false)`](#method.step).
+    pub fn next_tick(&mut self, ctx: &UI) -> bool {
+        self.step(ctx, false)
+    }
+
     /// Hands control to the event loop until the next UI event occurs.
     /// The `on_tick` callback is executed after the UI step.
     ///
     /// Returns `true` if the application should continue running, and `false`
     /// if it should quit.
-    pub fn next_event_tick(&mut self, _ctx: &UI) -> bool {
-        let result = unsafe { ui_sys::uiMainStep(true as c_int) == 1 };
-        if let Some(ref mut c) = self.callback {
-            c();
-        }
-        result
+    ///
+    /// Equivalent to [`step(ctx, true)`](#method.step).
+    pub fn next_event_tick(&mut self, ctx: &UI) -> bool {
+        self.step(ctx, true)
     }
 
     /// Hands control to the event loop until [`UI::quit()`](struct.UI.html#method.quit) is called,
@@ -222,7 +584,7 @@ impl EventLoop {
             if !self.next_tick(ctx) {
                 break;
             }
+            sleep(Duration::from_millis(delay_ms as u64));
         }
-        sleep(Duration::new(0, delay_ms * 1000000))
     }
 }
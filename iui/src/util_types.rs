@@ -1 +1,95 @@
 //! Types describing data returned by various libUI items.
+
+use error::UIError;
+
+/// A color, as red, green, blue and alpha channels from `0.0` to `1.0`.
+///
+/// This is the representation `libui` uses everywhere: the alpha channel is straight, not
+/// premultiplied, so the color channels are the same whatever the opacity. It is what
+/// [`ColorButton`](controls/struct.ColorButton.html) reports and what
+/// [`Brush::solid`](draw/enum.Brush.html#method.solid) paints with.
+///
+/// ```
+/// use iui::Color;
+///
+/// let orange = Color::from_hex("#FF8000").unwrap();
+/// assert_eq!(orange, Color::from_rgb8(255, 128, 0));
+/// assert_eq!(orange.to_hex(), "#FF8000FF");
+///
+/// let translucent = Color::from_hex("#FF800080").unwrap();
+/// let (r, g, b, a) = translucent.into();
+/// assert_eq!((r, g, b), (1.0, 128.0 / 255.0, 0.0));
+/// assert!((a - 0.5).abs() < 0.01);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl Color {
+    /// Create a color from its channels, each from `0.0` to `1.0`.
+    pub fn new(r: f64, g: f64, b: f64, a: f64) -> Color {
+        Color { r, g, b, a }
+    }
+
+    /// Create an opaque color from 8-bit channels.
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Color {
+        Color::from_rgba8(r, g, b, 255)
+    }
+
+    /// Create a color from 8-bit channels, `a` being the opacity.
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Color {
+        let channel = |value: u8| value as f64 / 255.0;
+        Color::new(channel(r), channel(g), channel(b), channel(a))
+    }
+
+    /// Parse a color written as `#RRGGBBAA`, or `#RRGGBB` for an opaque color, in hexadecimal
+    /// of either case. The leading `#` is optional.
+    pub fn from_hex(hex: &str) -> Result<Color, UIError> {
+        let invalid = || UIError::InvalidColor {
+            color: hex.to_string(),
+        };
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !(digits.len() == 6 || digits.len() == 8) || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+        let alpha = if digits.len() == 8 { channel(6)? } else { 255 };
+        Ok(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, alpha))
+    }
+
+    /// Write this color as `#RRGGBBAA`, in upper case hexadecimal. Channels outside of
+    /// `0.0..=1.0` are clamped.
+    pub fn to_hex(&self) -> String {
+        let clamped = self.clamped();
+        let channel = |value: f64| (value * 255.0).round() as u8;
+        format!(
+            "#{:02X}{:02X}{:02X}{:02X}",
+            channel(clamped.r),
+            channel(clamped.g),
+            channel(clamped.b),
+            channel(clamped.a)
+        )
+    }
+
+    /// This color with each channel clamped to `0.0..=1.0`, as `libui` expects them.
+    pub(crate) fn clamped(self) -> Color {
+        let clamp = |channel: f64| channel.clamp(0.0, 1.0);
+        Color::new(clamp(self.r), clamp(self.g), clamp(self.b), clamp(self.a))
+    }
+}
+
+impl From<(f64, f64, f64, f64)> for Color {
+    fn from((r, g, b, a): (f64, f64, f64, f64)) -> Color {
+        Color::new(r, g, b, a)
+    }
+}
+
+impl From<Color> for (f64, f64, f64, f64) {
+    fn from(color: Color) -> (f64, f64, f64, f64) {
+        (color.r, color.g, color.b, color.a)
+    }
+}
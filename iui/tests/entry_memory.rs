@@ -0,0 +1,32 @@
+//! Checks that reading the text of an entry frees libui's copy of it, by watching the resident
+//! memory of the process, which is only available through `/proc` on Linux.
+#![cfg(target_os = "linux")]
+
+extern crate iui;
+extern crate libc;
+
+use iui::controls::{Entry, TextEntry};
+use iui::prelude::*;
+use std::fs;
+
+// The resident memory of the process.
+fn resident_bytes() -> usize {
+    let statm = fs::read_to_string("/proc/self/statm").unwrap();
+    let pages: usize = statm.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    pages * page_size as usize
+}
+
+#[test]
+fn reading_an_entry_does_not_leak() {
+    let ui = UI::init().unwrap();
+    let text = "x".repeat(16 * 1024);
+    let mut entry = Entry::new(&ui);
+    entry.set_value(&ui, &text);
+    let before = resident_bytes();
+    // Leaking every copy would use 625 MiB.
+    for _ in 0..40_000 {
+        assert_eq!(entry.value(&ui).len(), text.len());
+    }
+    assert!(resident_bytes().saturating_sub(before) < 64 * 1024 * 1024);
+}
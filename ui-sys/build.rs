@@ -1,68 +1,321 @@
-extern crate cmake;
-
-use cmake::Config;
-
 use std::env;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn target_os() -> String {
-    env::var("CARGO_CFG_TARGET_OS").unwrap_or(String::new())
+    env::var("CARGO_CFG_TARGET_OS").unwrap_or_default()
+}
+
+fn target_arch() -> String {
+    env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default()
+}
+
+// The meson build type to build libui with: the one matching the configuration given by
+// LIBUI_BUILD_PROFILE, or release. The names are cmake's, which libui was once built with. Debug
+// builds are opt-in, as with MSVC a debug libui links against the debug C runtime, which can't
+// be mixed with the release runtime Rust links against.
+fn build_type() -> &'static str {
+    println!("cargo:rerun-if-env-changed=LIBUI_BUILD_PROFILE");
+    match env::var("LIBUI_BUILD_PROFILE") {
+        Ok(profile) => match profile.as_str() {
+            "Debug" => "debug",
+            "Release" => "release",
+            "RelWithDebInfo" => "debugoptimized",
+            "MinSizeRel" => "minsize",
+            _ => panic!(
+                "LIBUI_BUILD_PROFILE is {:?}, but must be one of Debug, Release, RelWithDebInfo \
+                 and MinSizeRel.",
+                profile
+            ),
+        },
+        Err(_) => "release",
+    }
+}
+
+// Runs `command`, panicking if it does not succeed. `what` describes what it does.
+fn run(command: &mut Command, what: &str) {
+    match command.status() {
+        Ok(ref status) if status.success() => {}
+        Ok(status) => panic!("{} failed with {}.", what, status),
+        Err(_) => panic!(
+            "{} failed: meson does not appear to be installed. libui-ng is built with meson and \
+             ninja, which must both be on the PATH, or meson be given in MESON.",
+            what
+        ),
+    }
+}
+
+// Returns the path of the first file named one of `names` in `dir` or its subdirectories.
+fn find_file(dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    let mut subdirs = Vec::new();
+    for entry in fs::read_dir(dir).ok()?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if names.iter().any(|name| entry.file_name() == **name) {
+            return Some(path);
+        }
+    }
+    subdirs.iter().filter_map(|subdir| find_file(subdir, names)).next()
+}
+
+// Build libui-ng from the submodule with meson, returning the directory holding the library,
+// under the name `libname` is linked by.
+fn build_libui(libname: &str, msvc: bool) -> PathBuf {
+    println!("cargo:rerun-if-env-changed=MESON");
+    let meson = env::var_os("MESON").unwrap_or_else(|| "meson".into());
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let build_dir = out_dir.join("build");
+
+    let mut setup = Command::new(&meson);
+    setup
+        .arg("setup")
+        .arg(&build_dir)
+        .arg("libui")
+        .arg(format!("--buildtype={}", build_type()))
+        .arg(format!(
+            "--default-library={}",
+            if cfg!(feature = "static") { "static" } else { "shared" }
+        ))
+        .arg("-Dtests=false")
+        .arg("-Dexamples=false");
+    // A build directory left by an earlier build is configured again, in case the options
+    // changed.
+    if build_dir.join("build.ninja").exists() {
+        setup.arg("--reconfigure");
+    }
+    // Build for the target's architecture rather than the host's, so that e.g. an arm64
+    // library is built for aarch64-apple-darwin even from an Intel Mac.
+    if target_os() == "macos" {
+        let arch = match target_arch().as_str() {
+            "aarch64" => "arm64".to_owned(),
+            arch => arch.to_owned(),
+        };
+        let flags = format!("-arch {}", arch);
+        for var in &["CFLAGS", "OBJCFLAGS", "LDFLAGS"] {
+            setup.env(var, &flags);
+        }
+    }
+    run(&mut setup, "Configuring libui-ng");
+
+    let mut compile = Command::new(&meson);
+    compile.arg("compile").arg("-C").arg(&build_dir);
+    // Build with as many jobs as Cargo allows.
+    if let Ok(jobs) = env::var("NUM_JOBS") {
+        compile.arg("-j").arg(jobs);
+    }
+    run(&mut compile, "Building libui-ng");
+
+    // meson names static libraries `libui.a` even with MSVC, whose linker looks for
+    // `libui.lib`, and shared ones' import libraries `ui.lib`. The library is copied to the
+    // name it is linked by, with an import library standing in for its DLL.
+    let built = if cfg!(feature = "static") {
+        find_file(&build_dir, &["libui.a", "ui.lib", "libui.lib"])
+    } else if msvc {
+        find_file(&build_dir, &["ui.lib", "libui.lib"])
+    } else {
+        find_file(&build_dir, &["libui.so", "libui.dylib", "libui.dll.a"])
+    }
+    .expect("meson did not build a libui library.");
+    let lib_dir = out_dir.join("lib");
+    fs::create_dir_all(&lib_dir).expect("could not create the library directory.");
+    let file_name = if msvc {
+        format!("{}.lib", libname)
+    } else if cfg!(feature = "static") {
+        format!("lib{}.a", libname)
+    } else {
+        built.file_name().unwrap().to_string_lossy().into_owned()
+    };
+    fs::copy(&built, lib_dir.join(file_name)).expect("could not copy the libui library.");
+    lib_dir
+}
+
+// Returns a command running the pkg-config for the target. That is the one given by PKG_CONFIG,
+// or when cross compiling, the target-prefixed one (e.g. `aarch64-linux-gnu-pkg-config`) if it is
+// installed. Otherwise it is the host's, which is allowed to answer for another target so that
+// it can be pointed at the target's libraries with PKG_CONFIG_SYSROOT_DIR and PKG_CONFIG_LIBDIR.
+fn pkg_config() -> Command {
+    println!("cargo:rerun-if-env-changed=PKG_CONFIG");
+    if let Some(program) = env::var_os("PKG_CONFIG") {
+        return Command::new(program);
+    }
+
+    let target = env::var("TARGET").unwrap();
+    let host = env::var("HOST").unwrap();
+    if target == host {
+        return Command::new("pkg-config");
+    }
+
+    // Cross toolchains are named after the target without its vendor, e.g. `i686-linux-gnu`.
+    let parts: Vec<&str> = target.split('-').collect();
+    let prefix = if parts.len() == 4 && parts[1] == "unknown" {
+        format!("{}-{}-{}", parts[0], parts[2], parts[3])
+    } else {
+        target.clone()
+    };
+    let prefixed = format!("{}-pkg-config", prefix);
+    if Command::new(&prefixed).arg("--version").output().is_ok() {
+        return Command::new(prefixed);
+    }
+
+    let mut command = Command::new("pkg-config");
+    command.env("PKG_CONFIG_ALLOW_CROSS", "1");
+    command
+}
+
+// Pass the library flags pkg-config printed on to Cargo.
+fn emit_link_flags(pkg_config_output: &[u8]) {
+    for flag in std::str::from_utf8(pkg_config_output)
+        .expect("invalid output from pkg-config.")
+        .split_whitespace()
+    {
+        if let Some(dir) = flag.strip_prefix("-L") {
+            println!("cargo:rustc-link-search=native={}", dir);
+        } else if let Some(lib) = flag.strip_prefix("-l") {
+            println!("cargo:rustc-link-lib={}", lib);
+        }
+    }
+}
+
+// Ask pkg-config how to link against the system's libui, passing its answer on to Cargo.
+// Returns false if pkg-config is not installed or does not know about libui.
+fn pkg_config_libui() -> bool {
+    let out = match pkg_config()
+        .arg("--libs-only-L")
+        .arg("--libs-only-l")
+        .arg("libui")
+        .output()
+    {
+        Ok(out) => out,
+        Err(_) => return false,
+    };
+    if !out.status.success() {
+        return false;
+    }
+
+    emit_link_flags(&out.stdout);
+    true
+}
+
+// Link against a libui installed on the system, found with pkg-config or, failing that, in the
+// directory given by LIBUI_LIB_DIR.
+fn link_system_libui() {
+    println!("cargo:rerun-if-env-changed=LIBUI_LIB_DIR");
+
+    if pkg_config_libui() {
+        return;
+    }
+
+    match env::var("LIBUI_LIB_DIR") {
+        Ok(dir) => {
+            let libname = if env::var("TARGET").unwrap().contains("msvc") { "libui" } else { "ui" };
+            println!("cargo:rustc-link-search=native={}", dir);
+            println!("cargo:rustc-link-lib={}", libname);
+        }
+        Err(_) => panic!(
+            "The `system` feature is enabled, but libui could not be found: \
+             pkg-config does not know about it and LIBUI_LIB_DIR is not set."
+        ),
+    }
+}
+
+// Init or update the libui submodule. Sources which are already there, e.g. vendored into a
+// release tarball, are used as they are when they are not a git checkout, or when git can't
+// update them, for instance in an offline build.
+fn fetch_libui() {
+    let have_sources = Path::new("libui/meson.build").exists();
+    if have_sources && !Path::new("libui/.git").exists() {
+        return;
+    }
+
+    let args: &[&str] = if have_sources {
+        &["submodule", "update", "--recursive"]
+    } else {
+        &["submodule", "update", "--init"]
+    };
+    match Command::new("git").args(args).status() {
+        Ok(ref status) if status.success() => {}
+        result => {
+            let problem = match result {
+                Ok(status) => format!("`git {}` failed with {}", args.join(" "), status),
+                Err(_) => "git does not appear to be installed".to_owned(),
+            };
+            if have_sources {
+                println!("cargo:warning={}; building the existing libui sources", problem);
+            } else {
+                panic!(
+                    "The libui sources are missing and could not be fetched: {}. Clone this \
+                     repository with --recursive, or put the libui-ng sources in ui-sys/libui.",
+                    problem
+                );
+            }
+        }
+    }
 }
 
 fn main() {
+    // Declaring any rerun-if-changed replaces Cargo's default of rerunning on any change in the
+    // package, so the build script has to be listed too.
+    println!("cargo:rerun-if-changed=build.rs");
+    // Rerun when the environment the link flags are derived from changes. The variables read by
+    // only some paths, like LIBUI_LIB_DIR, are declared where they are read.
+    for var in &[
+        "TARGET",
+        "CARGO_CFG_TARGET_OS",
+        "CARGO_CFG_TARGET_ARCH",
+        "PKG_CONFIG_PATH",
+        "PKG_CONFIG_LIBDIR",
+        "PKG_CONFIG_SYSROOT_DIR",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    // Use the system's libui instead of building one
+    if cfg!(feature = "system") {
+        link_system_libui();
+        return;
+    }
+
     // Fetch the submodule if needed
     if cfg!(feature = "fetch") {
-        // Init or update the submodule with libui if needed
-        if !Path::new("libui/.git").exists() {
-            Command::new("git")
-                .args(&["version"])
-                .status()
-                .expect("Git does not appear to be installed. Error");
-            Command::new("git")
-                .args(&["submodule", "update", "--init"])
-                .status()
-                .expect("Unable to init libui submodule. Error");
-        } else {
-            Command::new("git")
-                .args(&["submodule", "update", "--recursive"])
-                .status()
-                .expect("Unable to update libui submodule. Error");
-        }
+        fetch_libui();
     }
 
     // Deterimine if we're building for MSVC
     let target = env::var("TARGET").unwrap();
     let msvc = target.contains("msvc");
-    // Build libui if needed. Otherwise, assume it's in lib/
-    let mut dst;
+    let libname = if msvc { "libui" } else { "ui" };
+    // Build libui if needed. Otherwise, assume it's in LIBUI_LIB_DIR, or lib/ if that's unset
+    let dst;
     if cfg!(feature = "build") {
-        let mut cfg = Config::new("libui");
-        cfg.build_target("").profile("release");
-
-        if cfg!(feature = "static") {
-            cfg.define("BUILD_SHARED_LIBS", "OFF");
-            // When cross compiling, clang/gcc (correctly) errors on narrowing not allowed in c++11.
-            // Disable that until libui fixes that.
-            if target_os() == "windows" {
-                //cfg.cxxflag("-Wno-c++11-narrowing");
-            }
-        }
+        // Rebuild when the vendored sources change, e.g. after a submodule update
+        println!("cargo:rerun-if-changed=libui");
 
-        dst = cfg.build();
+        dst = build_libui(libname, msvc);
+    } else {
+        println!("cargo:rerun-if-env-changed=LIBUI_LIB_DIR");
+        dst = match env::var_os("LIBUI_LIB_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let mut dir = env::current_dir().expect("Unable to retrieve current directory location.");
+                dir.push("lib");
+                dir
+            }
+        };
 
-        let mut postfix = Path::new("build").join("out");
-        if msvc {
-            postfix = postfix.join("Release");
+        // On Linux, a shared libui packaged separately can be found with pkg-config when no
+        // local copy was given.
+        if target_os() == "linux"
+            && !cfg!(feature = "static")
+            && env::var_os("LIBUI_LIB_DIR").is_none()
+            && !dst.exists()
+            && pkg_config_libui()
+        {
+            return;
         }
-        dst = dst.join(&postfix);
-    } else {
-        dst = env::current_dir().expect("Unable to retrieve current directory location.");
-        dst.push("lib");
     }
 
-    let libname = if msvc { "libui" } else { "ui" };
-
     println!("cargo:rustc-link-search=native={}", dst.display());
     
     if cfg!(feature = "static") {
@@ -72,30 +325,34 @@ fn main() {
     }
 
     if cfg!(feature = "static") && target_os() == "linux" {
-        let out = Command::new("pkg-config")
-            .args(&["--libs", "gtk+-3.0"])
+        let out = pkg_config()
+            .arg("--libs-only-L")
+            .arg("--libs-only-l")
+            .arg("gtk+-3.0")
             .output()
             .expect("pkg-config does not appear to be installed.");
 
         if !out.status.success() {
-            panic!("couldn't find gtk+-3.0");
+            panic!(
+                "couldn't find gtk+-3.0 for {}. When cross compiling, install the target's \
+                 GTK+ development files, and point pkg-config at them with PKG_CONFIG_SYSROOT_DIR \
+                 and PKG_CONFIG_LIBDIR, or give a pkg-config for the target in PKG_CONFIG.",
+                target
+            );
         }
 
-        for lib in std::str::from_utf8(&out.stdout)
-            .expect("invalid output from pkg-config.")
-            .split(' ')
-        {
-            if lib.len() > 2 {
-                assert!(&lib[..2] == "-l");
-                println!("cargo:rustc-link-lib={}", &lib[2..]);
-            }
-        }
+        emit_link_flags(&out.stdout);
     } else if cfg!(feature = "static") && target_os() == "windows" {
         for lib in &[
             "user32", "gdi32", "comctl32", "ole32", "d2d1", "uxtheme", "dwrite",
         ] {
             println!("cargo:rustc-link-lib={}", lib);
         }
+    } else if cfg!(feature = "static") && target_os() == "macos" {
+        // The frameworks are the same on x86_64 and aarch64 Macs.
+        for framework in &["Cocoa", "CoreText", "CoreGraphics"] {
+            println!("cargo:rustc-link-lib=framework={}", framework);
+        }
     }
 
     if cfg!(all(not(target_os = "windows"), feature = "static")) {
@@ -106,6 +363,8 @@ fn main() {
         } {
             let prefix = "resource";
             let resource = "resource.rc";
+            println!("cargo:rerun-if-changed={}", resource);
+            println!("cargo:rerun-if-changed=libui.manifest");
 
             Command::new(cmd)
                 .args(&[
@@ -4,7 +4,7 @@
 
 extern crate libc;
 
-use libc::{c_char, c_double, c_int, c_void, intmax_t, size_t, uintmax_t};
+use libc::{c_char, c_double, c_int, c_uint, c_void, size_t, tm};
 
 pub mod platform {
     pub mod macos;
@@ -30,7 +30,9 @@ extern {
 
     pub fn uiQueueMain(f: extern "C" fn(data: *mut c_void), data: *mut c_void);
 
-    pub fn uiOnShouldQuit(f: extern "C" fn(data: *mut c_void), data: *mut c_void);
+    pub fn uiTimer(milliseconds: c_int, f: extern "C" fn(data: *mut c_void) -> c_int, data: *mut c_void);
+
+    pub fn uiOnShouldQuit(f: extern "C" fn(data: *mut c_void) -> c_int, data: *mut c_void);
 
     pub fn uiFreeText(text: *mut c_char);
 }
@@ -81,6 +83,22 @@ pub enum uiWindow {}
 extern {
     pub fn uiWindowTitle(w: *mut uiWindow) -> *mut c_char;
     pub fn uiWindowSetTitle(w: *mut uiWindow, title: *const c_char);
+    pub fn uiWindowPosition(w: *mut uiWindow, x: *mut c_int, y: *mut c_int);
+    pub fn uiWindowSetPosition(w: *mut uiWindow, x: c_int, y: c_int);
+    pub fn uiWindowOnPositionChanged(w: *mut uiWindow,
+                                     f: extern "C" fn(w: *mut uiWindow, data: *mut c_void),
+                                     data: *mut c_void);
+    pub fn uiWindowContentSize(w: *mut uiWindow, width: *mut c_int, height: *mut c_int);
+    pub fn uiWindowSetContentSize(w: *mut uiWindow, width: c_int, height: c_int);
+    pub fn uiWindowOnContentSizeChanged(w: *mut uiWindow,
+                                        f: extern "C" fn(w: *mut uiWindow, data: *mut c_void),
+                                        data: *mut c_void);
+    pub fn uiWindowFullscreen(w: *mut uiWindow) -> c_int;
+    pub fn uiWindowSetFullscreen(w: *mut uiWindow, fullscreen: c_int);
+    pub fn uiWindowBorderless(w: *mut uiWindow) -> c_int;
+    pub fn uiWindowSetBorderless(w: *mut uiWindow, borderless: c_int);
+    pub fn uiWindowResizeable(w: *mut uiWindow) -> c_int;
+    pub fn uiWindowSetResizeable(w: *mut uiWindow, resizeable: c_int);
     pub fn uiWindowOnClosing(w: *mut uiWindow,
                              f: extern "C" fn(w: *mut uiWindow, data: *mut c_void) -> c_int,
                              data: *mut c_void);
@@ -107,7 +125,8 @@ pub enum uiBox {}
 
 extern {
     pub fn uiBoxAppend(b: *mut uiBox, child: *mut uiControl, stretchy: c_int);
-    pub fn uiBoxDelete(b: *mut uiBox, index: uintmax_t);
+    pub fn uiBoxNumChildren(b: *mut uiBox) -> c_int;
+    pub fn uiBoxDelete(b: *mut uiBox, index: c_int);
     pub fn uiBoxPadded(b: *mut uiBox) -> c_int;
     pub fn uiBoxSetPadded(b: *mut uiBox, padded: c_int);
     pub fn uiNewHorizontalBox() -> *mut uiBox;
@@ -125,6 +144,8 @@ extern {
     pub fn uiEntryReadOnly(e: *mut uiEntry) -> c_int;
     pub fn uiEntrySetReadOnly(e: *mut uiEntry, readonly: c_int);
     pub fn uiNewEntry() -> *mut uiEntry;
+    pub fn uiNewPasswordEntry() -> *mut uiEntry;
+    pub fn uiNewSearchEntry() -> *mut uiEntry;
 }
 
 pub enum uiCheckbox {}
@@ -152,11 +173,16 @@ pub enum uiTab {}
 
 extern {
     pub fn uiTabAppend(t: *mut uiTab, name: *const c_char, c: *mut uiControl);
-    pub fn uiTabInsertAt(t: *mut uiTab, name: *const c_char, before: uintmax_t, c: *mut uiControl);
-    pub fn uiTabDelete(t: *mut uiTab, index: uintmax_t);
-    pub fn uiTabNumPages(t: *mut uiTab) -> uintmax_t;
-    pub fn uiTabMargined(t: *mut uiTab, page: uintmax_t) -> c_int;
-    pub fn uiTabSetMargined(t: *mut uiTab, page: uintmax_t, margined: c_int);
+    pub fn uiTabInsertAt(t: *mut uiTab, name: *const c_char, before: c_int, c: *mut uiControl);
+    pub fn uiTabDelete(t: *mut uiTab, index: c_int);
+    pub fn uiTabNumPages(t: *mut uiTab) -> c_int;
+    pub fn uiTabMargined(t: *mut uiTab, page: c_int) -> c_int;
+    pub fn uiTabSetMargined(t: *mut uiTab, page: c_int, margined: c_int);
+    pub fn uiTabSelected(t: *mut uiTab) -> c_int;
+    pub fn uiTabSetSelected(t: *mut uiTab, index: c_int);
+    pub fn uiTabOnSelected(t: *mut uiTab,
+                           f: extern "C" fn(t: *mut uiTab, data: *mut c_void),
+                           data: *mut c_void);
     pub fn uiNewTab() -> *mut uiTab;
 }
 
@@ -174,17 +200,18 @@ extern {
 pub enum uiSpinbox {}
 
 extern {
-    pub fn uiSpinboxValue(s: *mut uiSpinbox) -> intmax_t;
-    pub fn uiSpinboxSetValue(s: *mut uiSpinbox, value: intmax_t);
+    pub fn uiSpinboxValue(s: *mut uiSpinbox) -> c_int;
+    pub fn uiSpinboxSetValue(s: *mut uiSpinbox, value: c_int);
     pub fn uiSpinboxOnChanged(s: *mut uiSpinbox,
                               f: extern "C" fn(s: *mut uiSpinbox, data: *mut c_void),
                               data: *mut c_void);
-    pub fn uiNewSpinbox(min: intmax_t, max: intmax_t) -> *mut uiSpinbox;
+    pub fn uiNewSpinbox(min: c_int, max: c_int) -> *mut uiSpinbox;
 }
 
 pub enum uiProgressBar {}
 
 extern {
+    pub fn uiProgressBarValue(p: *mut uiProgressBar) -> c_int;
     pub fn uiProgressBarSetValue(p: *mut uiProgressBar, n: c_int);
     pub fn uiNewProgressBar() -> *mut uiProgressBar;
 }
@@ -192,26 +219,33 @@ extern {
 pub enum uiSlider {}
 
 extern {
-    pub fn uiSliderValue(s: *mut uiSlider) -> intmax_t;
-    pub fn uiSliderSetValue(s: *mut uiSlider, value: intmax_t);
+    pub fn uiSliderValue(s: *mut uiSlider) -> c_int;
+    pub fn uiSliderSetValue(s: *mut uiSlider, value: c_int);
     pub fn uiSliderOnChanged(s: *mut uiSlider,
                              f: extern "C" fn(s: *mut uiSlider, data: *mut c_void),
                              data: *mut c_void);
-    pub fn uiNewSlider(min: intmax_t, max: intmax_t) -> *mut uiSlider;
+    pub fn uiSliderOnReleased(s: *mut uiSlider,
+                              f: extern "C" fn(s: *mut uiSlider, data: *mut c_void),
+                              data: *mut c_void);
+    pub fn uiSliderHasToolTip(s: *mut uiSlider) -> c_int;
+    pub fn uiSliderSetHasToolTip(s: *mut uiSlider, hasToolTip: c_int);
+    pub fn uiSliderSetRange(s: *mut uiSlider, min: c_int, max: c_int);
+    pub fn uiNewSlider(min: c_int, max: c_int) -> *mut uiSlider;
 }
 
 pub enum uiSeparator {}
 
 extern {
     pub fn uiNewHorizontalSeparator() -> *mut uiSeparator;
+    pub fn uiNewVerticalSeparator() -> *mut uiSeparator;
 }
 
 pub enum uiCombobox {}
 
 extern {
     pub fn uiComboboxAppend(c: *mut uiCombobox, text: *const c_char);
-    pub fn uiComboboxSelected(c: *mut uiCombobox) -> intmax_t;
-    pub fn uiComboboxSetSelected(c: *mut uiCombobox, n: intmax_t);
+    pub fn uiComboboxSelected(c: *mut uiCombobox) -> c_int;
+    pub fn uiComboboxSetSelected(c: *mut uiCombobox, n: c_int);
     pub fn uiComboboxOnSelected(c: *mut uiCombobox,
                                 f: extern "C" fn(c: *mut uiCombobox, data: *mut c_void),
                                 data: *mut c_void);
@@ -224,18 +258,33 @@ pub enum uiEditableCombobox {}
 extern {
     pub fn uiNewEditableCombobox() -> *mut uiEditableCombobox;
     pub fn uiEditableComboboxAppend(c: *mut uiEditableCombobox, text: *const c_char);
+    pub fn uiEditableComboboxText(c: *mut uiEditableCombobox) -> *mut c_char;
+    pub fn uiEditableComboboxSetText(c: *mut uiEditableCombobox, text: *const c_char);
+    pub fn uiEditableComboboxOnChanged(c: *mut uiEditableCombobox,
+                                       f: extern "C" fn(c: *mut uiEditableCombobox, data: *mut c_void),
+                                       data: *mut c_void);
 }
 
 pub enum uiRadioButtons {}
 
 extern {
     pub fn uiRadioButtonsAppend(r: *mut uiRadioButtons, text: *const c_char);
+    pub fn uiRadioButtonsSelected(r: *mut uiRadioButtons) -> c_int;
+    pub fn uiRadioButtonsSetSelected(r: *mut uiRadioButtons, n: c_int);
+    pub fn uiRadioButtonsOnSelected(r: *mut uiRadioButtons,
+                                    f: extern "C" fn(r: *mut uiRadioButtons, data: *mut c_void),
+                                    data: *mut c_void);
     pub fn uiNewRadioButtons() -> *mut uiRadioButtons;
 }
 
 pub enum uiDateTimePicker {}
 
 extern {
+    pub fn uiDateTimePickerTime(d: *mut uiDateTimePicker, time: *mut tm);
+    pub fn uiDateTimePickerSetTime(d: *mut uiDateTimePicker, time: *const tm);
+    pub fn uiDateTimePickerOnChanged(d: *mut uiDateTimePicker,
+                                     f: extern "C" fn(d: *mut uiDateTimePicker, data: *mut c_void),
+                                     data: *mut c_void);
     pub fn uiNewDateTimePicker() -> *mut uiDateTimePicker;
     pub fn uiNewDatePicker() -> *mut uiDateTimePicker;
     pub fn uiNewTimePicker() -> *mut uiDateTimePicker;
@@ -284,6 +333,7 @@ extern {
 extern {
     pub fn uiOpenFile(parent: *mut uiWindow) -> *mut c_char;
     pub fn uiSaveFile(parent: *mut uiWindow) -> *mut c_char;
+    pub fn uiOpenFolder(parent: *mut uiWindow) -> *mut c_char;
     pub fn uiMsgBox(parent: *mut uiWindow, title: *const c_char, description: *const c_char);
     pub fn uiMsgBoxError(parent: *mut uiWindow, title: *const c_char, description: *const c_char);
 }
@@ -309,7 +359,7 @@ pub struct uiAreaHandler {
 }
 
 extern {
-    pub fn uiAreaSetSize(a: *mut uiArea, width: intmax_t, height: intmax_t);
+    pub fn uiAreaSetSize(a: *mut uiArea, width: c_int, height: c_int);
     pub fn uiAreaQueueRedrawAll(a: *mut uiArea);
     pub fn uiAreaScrollTo(a: *mut uiArea,
                           x: c_double,
@@ -317,7 +367,7 @@ extern {
                           width: c_double,
                           height: c_double);
     pub fn uiNewArea(ah: *mut uiAreaHandler) -> *mut uiArea;
-    pub fn uiNewScrollingArea(ah: *mut uiAreaHandler, width: intmax_t, height: intmax_t)
+    pub fn uiNewScrollingArea(ah: *mut uiAreaHandler, width: c_int, height: c_int)
                               -> *mut uiArea;
 }
 
@@ -505,38 +555,163 @@ extern "C" {
     pub fn uiDrawRestore(c: *mut uiDrawContext);
 }
 
-pub enum uiDrawFontFamilies {}
+pub enum uiAttribute {}
+
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum uiAttributeType {
+    Family = 0,
+    Size = 1,
+    Weight = 2,
+    Italic = 3,
+    Stretch = 4,
+    Color = 5,
+    Background = 6,
+    Underline = 7,
+    UnderlineColor = 8,
+    Features = 9,
+}
+
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum uiUnderline {
+    None = 0,
+    Single = 1,
+    Double = 2,
+    /// Wavy or dotted underlines used for spelling/grammar checkers.
+    Suggestion = 3,
+}
+
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum uiUnderlineColor {
+    Custom = 0,
+    Spelling = 1,
+    Grammar = 2,
+    /// For instance, the color used by smart replacements on macOS or in Microsoft Office.
+    Auxiliary = 3,
+}
+
+extern "C" {
+    pub fn uiFreeAttribute(a: *mut uiAttribute);
+    pub fn uiAttributeGetType(a: *const uiAttribute) -> uiAttributeType;
+
+    pub fn uiNewFamilyAttribute(family: *const c_char) -> *mut uiAttribute;
+    pub fn uiNewSizeAttribute(size: c_double) -> *mut uiAttribute;
+    pub fn uiNewWeightAttribute(weight: uiTextWeight) -> *mut uiAttribute;
+    pub fn uiNewItalicAttribute(italic: uiTextItalic) -> *mut uiAttribute;
+    pub fn uiNewStretchAttribute(stretch: uiTextStretch) -> *mut uiAttribute;
+    pub fn uiNewColorAttribute(r: c_double, g: c_double, b: c_double, a: c_double)
+                               -> *mut uiAttribute;
+    pub fn uiNewBackgroundAttribute(r: c_double, g: c_double, b: c_double, a: c_double)
+                                    -> *mut uiAttribute;
+    pub fn uiNewUnderlineAttribute(u: uiUnderline) -> *mut uiAttribute;
+    pub fn uiNewUnderlineColorAttribute(u: uiUnderlineColor,
+                                        r: c_double,
+                                        g: c_double,
+                                        b: c_double,
+                                        a: c_double)
+                                        -> *mut uiAttribute;
+}
+
+pub enum uiOpenTypeFeatures {}
+
+extern "C" {
+    pub fn uiNewOpenTypeFeatures() -> *mut uiOpenTypeFeatures;
+    pub fn uiFreeOpenTypeFeatures(otf: *mut uiOpenTypeFeatures);
+    pub fn uiOpenTypeFeaturesClone(otf: *const uiOpenTypeFeatures) -> *mut uiOpenTypeFeatures;
+    pub fn uiOpenTypeFeaturesAdd(otf: *mut uiOpenTypeFeatures,
+                                 a: c_char,
+                                 b: c_char,
+                                 c: c_char,
+                                 d: c_char,
+                                 value: u32);
+    pub fn uiOpenTypeFeaturesRemove(otf: *mut uiOpenTypeFeatures,
+                                    a: c_char,
+                                    b: c_char,
+                                    c: c_char,
+                                    d: c_char);
+    pub fn uiOpenTypeFeaturesGet(otf: *const uiOpenTypeFeatures,
+                                 a: c_char,
+                                 b: c_char,
+                                 c: c_char,
+                                 d: c_char,
+                                 value: *mut u32)
+                                 -> c_int;
+    /// Copies the features; the attribute does not take ownership of them.
+    pub fn uiNewFeaturesAttribute(otf: *const uiOpenTypeFeatures) -> *mut uiAttribute;
+}
+
+pub enum uiAttributedString {}
 
 extern "C" {
-    pub fn uiDrawListFontFamilies() -> *mut uiDrawFontFamilies;
-    pub fn uiDrawFontFamiliesNumFamilies(ff: *mut uiDrawFontFamilies) -> uintmax_t;
-    pub fn uiDrawFontFamiliesFamily(ff: *mut uiDrawFontFamilies, n: uintmax_t) -> *mut c_char;
-    pub fn uiDrawFreeFontFamilies(ff: *mut uiDrawFontFamilies);
+    pub fn uiNewAttributedString(initialString: *const c_char) -> *mut uiAttributedString;
+    pub fn uiFreeAttributedString(s: *mut uiAttributedString);
+    pub fn uiAttributedStringString(s: *const uiAttributedString) -> *const c_char;
+    pub fn uiAttributedStringLen(s: *const uiAttributedString) -> size_t;
+    pub fn uiAttributedStringAppendUnattributed(s: *mut uiAttributedString, str: *const c_char);
+    pub fn uiAttributedStringInsertAtUnattributed(s: *mut uiAttributedString,
+                                                  str: *const c_char,
+                                                  at: size_t);
+    pub fn uiAttributedStringDelete(s: *mut uiAttributedString, start: size_t, end: size_t);
+    /// Takes ownership of the attribute.
+    pub fn uiAttributedStringSetAttribute(s: *mut uiAttributedString,
+                                          a: *mut uiAttribute,
+                                          start: size_t,
+                                          end: size_t);
+    pub fn uiAttributedStringNumGraphemes(s: *mut uiAttributedString) -> size_t;
+    pub fn uiAttributedStringByteIndexToGrapheme(s: *mut uiAttributedString, pos: size_t)
+                                                 -> size_t;
+    pub fn uiAttributedStringGraphemeToByteIndex(s: *mut uiAttributedString, pos: size_t)
+                                                 -> size_t;
 }
 
 pub enum uiDrawTextLayout {}
-pub enum uiDrawTextFont {}
 
 #[repr(u32)]
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum uiDrawTextWeight {
-    Thin = 0,
-    UltraLight = 1,
-    Light = 2,
-    Book = 3,
-    Normal = 4,
-    Medium = 5,
-    SemiBold = 6,
-    Bold = 7,
-    /// [sic]
-    UtraBold = 8,
-    Heavy = 9,
-    UltraHeavy = 10,
+pub enum uiDrawTextAlign {
+    Left = 0,
+    Center = 1,
+    Right = 2,
 }
 
+#[repr(C)]
+pub struct uiDrawTextLayoutParams {
+    pub String: *mut uiAttributedString,
+    pub DefaultFont: *mut uiFontDescriptor,
+    pub Width: c_double,
+    pub Align: uiDrawTextAlign,
+}
+
+extern "C" {
+    pub fn uiDrawNewTextLayout(params: *mut uiDrawTextLayoutParams) -> *mut uiDrawTextLayout;
+    pub fn uiDrawFreeTextLayout(tl: *mut uiDrawTextLayout);
+    pub fn uiDrawText(c: *mut uiDrawContext, tl: *mut uiDrawTextLayout, x: c_double, y: c_double);
+    pub fn uiDrawTextLayoutExtents(tl: *mut uiDrawTextLayout,
+                                   width: *mut c_double,
+                                   height: *mut c_double);
+}
+
+pub type uiTextWeight = c_uint;
+
+pub const uiTextWeightMinimum: uiTextWeight = 0;
+pub const uiTextWeightThin: uiTextWeight = 100;
+pub const uiTextWeightUltraLight: uiTextWeight = 200;
+pub const uiTextWeightLight: uiTextWeight = 300;
+pub const uiTextWeightBook: uiTextWeight = 350;
+pub const uiTextWeightNormal: uiTextWeight = 400;
+pub const uiTextWeightMedium: uiTextWeight = 500;
+pub const uiTextWeightSemiBold: uiTextWeight = 600;
+pub const uiTextWeightBold: uiTextWeight = 700;
+pub const uiTextWeightUltraBold: uiTextWeight = 800;
+pub const uiTextWeightHeavy: uiTextWeight = 900;
+pub const uiTextWeightUltraHeavy: uiTextWeight = 950;
+pub const uiTextWeightMaximum: uiTextWeight = 1000;
+
 #[repr(u32)]
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum uiDrawTextItalic {
+pub enum uiTextItalic {
     Normal = 0,
     Oblique = 1,
     Italic = 2,
@@ -544,7 +719,7 @@ pub enum uiDrawTextItalic {
 
 #[repr(u32)]
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum uiDrawTextStretch {
+pub enum uiTextStretch {
     UltraCondensed = 0,
     ExtraCondensed = 1,
     Condensed = 2,
@@ -558,57 +733,12 @@ pub enum uiDrawTextStretch {
 
 #[repr(C)]
 #[derive(Clone)]
-pub struct uiDrawTextFontDescriptor {
-    pub Family: *const c_char,
+pub struct uiFontDescriptor {
+    pub Family: *mut c_char,
     pub Size: c_double,
-    pub Weight: uiDrawTextWeight,
-    pub Italic: uiDrawTextItalic,
-    pub Stretch: uiDrawTextStretch,
-}
-
-#[repr(C)]
-#[derive(Copy, Clone, Debug)]
-pub struct uiDrawTextFontMetrics {
-    pub Ascent: c_double,
-    pub Descent: c_double,
-    pub Leading: c_double,
-    pub UnderlinePos: c_double,
-    pub UnderlineThickness: c_double,
-}
-
-extern "C" {
-    pub fn uiDrawLoadClosestFont(desc: *const uiDrawTextFontDescriptor) -> *mut uiDrawTextFont;
-    pub fn uiDrawFreeTextFont(font: *mut uiDrawTextFont);
-    pub fn uiDrawTextFontHandle(font: *mut uiDrawTextFont) -> usize;
-    pub fn uiDrawTextFontDescribe(font: *mut uiDrawTextFont,
-                                  desc: *mut uiDrawTextFontDescriptor);
-    pub fn uiDrawTextFontGetMetrics(font: *mut uiDrawTextFont,
-                                    metrics: *mut uiDrawTextFontMetrics);
-}
-
-extern "C" {
-    pub fn uiDrawNewTextLayout(text: *const c_char,
-                               defaultFont: *mut uiDrawTextFont,
-                               width: c_double)
-                               -> *mut uiDrawTextLayout;
-    pub fn uiDrawFreeTextLayout(layout: *mut uiDrawTextLayout);
-    pub fn uiDrawTextLayoutSetWidth(layout: *mut uiDrawTextLayout, width: c_double);
-    pub fn uiDrawTextLayoutExtents(layout: *mut uiDrawTextLayout,
-                                   width: *mut c_double,
-                                   height: *mut c_double);
-
-    pub fn uiDrawTextLayoutSetColor(layout: *mut uiDrawTextLayout,
-                                    startChar: intmax_t,
-                                    endChar: intmax_t,
-                                    r: c_double,
-                                    g: c_double,
-                                    b: c_double,
-                                    a: c_double);
-
-    pub fn uiDrawText(c: *mut uiDrawContext,
-                      x: c_double,
-                      y: c_double,
-                      layout: *mut uiDrawTextLayout);
+    pub Weight: uiTextWeight,
+    pub Italic: uiTextItalic,
+    pub Stretch: uiTextStretch,
 }
 
 pub type uiModifiers = c_int;
@@ -637,53 +767,52 @@ pub struct uiAreaMouseEvent {
     pub Held1To64: u64,
 }
 
-#[repr(u32)]
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub enum uiExtKey {
-    Escape = 1,
-    /// Equivalent to "Help" on Apple keyboards.
-    Insert = 2,
-    Delete = 3,
-    Home = 4,
-    End = 5,
-    PageUp = 6,
-    PageDown = 7,
-    Up = 8,
-    Down = 9,
-    Left = 10,
-    Right = 11,
-    // F1..F12 are guaranteed to be consecutive.
-    F1 = 12,
-    F2 = 13,
-    F3 = 14,
-    F4 = 15,
-    F5 = 16,
-    F6 = 17,
-    F7 = 18,
-    F8 = 19,
-    F9 = 20,
-    F10 = 21,
-    F11 = 22,
-    F12 = 23,
-    // Numpad keys; independent of Num Lock state.
-    // N0..N9 are guaranteed to be consecutive.
-    N0 = 24,
-    N1 = 25,
-    N2 = 26,
-    N3 = 27,
-    N4 = 28,
-    N5 = 29,
-    N6 = 30,
-    N7 = 31,
-    N8 = 32,
-    N9 = 33,
-    NDot = 34,
-    NEnter = 35,
-    NAdd = 36,
-    NSubtract = 37,
-    NMultiply = 38,
-    NDivide = 39,
-}
+/// The non-character key of a key event, or 0 for character keys.
+pub type uiExtKey = c_int;
+
+pub const uiExtKeyEscape: uiExtKey = 1;
+/// Equivalent to "Help" on Apple keyboards.
+pub const uiExtKeyInsert: uiExtKey = 2;
+pub const uiExtKeyDelete: uiExtKey = 3;
+pub const uiExtKeyHome: uiExtKey = 4;
+pub const uiExtKeyEnd: uiExtKey = 5;
+pub const uiExtKeyPageUp: uiExtKey = 6;
+pub const uiExtKeyPageDown: uiExtKey = 7;
+pub const uiExtKeyUp: uiExtKey = 8;
+pub const uiExtKeyDown: uiExtKey = 9;
+pub const uiExtKeyLeft: uiExtKey = 10;
+pub const uiExtKeyRight: uiExtKey = 11;
+// F1..F12 are guaranteed to be consecutive.
+pub const uiExtKeyF1: uiExtKey = 12;
+pub const uiExtKeyF2: uiExtKey = 13;
+pub const uiExtKeyF3: uiExtKey = 14;
+pub const uiExtKeyF4: uiExtKey = 15;
+pub const uiExtKeyF5: uiExtKey = 16;
+pub const uiExtKeyF6: uiExtKey = 17;
+pub const uiExtKeyF7: uiExtKey = 18;
+pub const uiExtKeyF8: uiExtKey = 19;
+pub const uiExtKeyF9: uiExtKey = 20;
+pub const uiExtKeyF10: uiExtKey = 21;
+pub const uiExtKeyF11: uiExtKey = 22;
+pub const uiExtKeyF12: uiExtKey = 23;
+// Numpad keys; independent of Num Lock state.
+// N0..N9 are guaranteed to be consecutive.
+pub const uiExtKeyN0: uiExtKey = 24;
+pub const uiExtKeyN1: uiExtKey = 25;
+pub const uiExtKeyN2: uiExtKey = 26;
+pub const uiExtKeyN3: uiExtKey = 27;
+pub const uiExtKeyN4: uiExtKey = 28;
+pub const uiExtKeyN5: uiExtKey = 29;
+pub const uiExtKeyN6: uiExtKey = 30;
+pub const uiExtKeyN7: uiExtKey = 31;
+pub const uiExtKeyN8: uiExtKey = 32;
+pub const uiExtKeyN9: uiExtKey = 33;
+pub const uiExtKeyNDot: uiExtKey = 34;
+pub const uiExtKeyNEnter: uiExtKey = 35;
+pub const uiExtKeyNAdd: uiExtKey = 36;
+pub const uiExtKeyNSubtract: uiExtKey = 37;
+pub const uiExtKeyNMultiply: uiExtKey = 38;
+pub const uiExtKeyNDivide: uiExtKey = 39;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -700,11 +829,12 @@ pub struct uiAreaKeyEvent {
 pub enum uiFontButton {}
 
 extern {
-    pub fn uiFontButtonFont(b: *mut uiFontButton) -> *mut uiDrawTextFont;
+    pub fn uiFontButtonFont(b: *mut uiFontButton, desc: *mut uiFontDescriptor);
     pub fn uiFontButtonOnChanged(b: *mut uiFontButton,
                                  f: extern "C" fn(this: *mut uiFontButton, data: *mut c_void),
                                  data: *mut c_void);
     pub fn uiNewFontButton() -> *mut uiFontButton;
+    pub fn uiFreeFontButtonFont(desc: *mut uiFontDescriptor);
 }
 
 pub enum uiColorButton {}
@@ -739,7 +869,7 @@ pub struct uiImageData {
     pub data: *mut c_void,
 }
 
-extern {
+extern "C" {
     pub fn uiNewPixmapImage(width: c_int, height: c_int) -> *mut uiPixmapImage;
     pub fn uiFreePixmapImage(img: *mut uiPixmapImage);
     pub fn uiPixmapImageGetFormat(img: *mut uiPixmapImage) -> uiPixmap32Format;
@@ -780,13 +910,158 @@ pub enum uiAt {
     uiAtBottom,
 }
 
+pub enum uiForm {}
+
+extern "C" {
+    pub fn uiFormAppend(f: *mut uiForm, label: *const c_char, c: *mut uiControl, stretchy: c_int);
+    pub fn uiFormNumChildren(f: *mut uiForm) -> c_int;
+    pub fn uiFormDelete(f: *mut uiForm, index: c_int);
+    pub fn uiFormPadded(f: *mut uiForm) -> c_int;
+    pub fn uiFormSetPadded(f: *mut uiForm, padded: c_int);
+    pub fn uiNewForm() -> *mut uiForm;
+}
+
 pub enum uiGrid {}
 
-extern {
-    pub fn uiGridAppend(g: *mut uiGrid, c: *mut uiControl, left: c_int, height: c_int, xspan: c_int, yspan: c_int, hexpand: c_int, halign: uiAlign, vexpand: c_int, valign: uiAlign);
-    pub fn uiGridInsertAt(g: *mut uiGrid, c: *mut uiControl, existing: *mut uiControl, at: uiAt, left: c_int, height: c_int, xspan: c_int, yspan: c_int, hexpand: c_int, halign: uiAlign, vexpand: c_int, valign: uiAlign);
+extern "C" {
+    pub fn uiGridAppend(g: *mut uiGrid, c: *mut uiControl, left: c_int, top: c_int, xspan: c_int, yspan: c_int, hexpand: c_int, halign: uiAlign, vexpand: c_int, valign: uiAlign);
+    pub fn uiGridInsertAt(g: *mut uiGrid, c: *mut uiControl, existing: *mut uiControl, at: uiAt, left: c_int, top: c_int, xspan: c_int, yspan: c_int, hexpand: c_int, halign: uiAlign, vexpand: c_int, valign: uiAlign);
     pub fn uiGridPadded(g: *mut uiGrid) -> c_int;
     pub fn uiGridSetPadded(g: *mut uiGrid, padded: c_int);
     pub fn uiNewGrid() -> *mut uiGrid;
 }
 
+
+pub enum uiImage {}
+
+extern "C" {
+    pub fn uiNewImage(width: c_double, height: c_double) -> *mut uiImage;
+    pub fn uiFreeImage(i: *mut uiImage);
+    pub fn uiImageAppend(i: *mut uiImage,
+                         pixels: *mut c_void,
+                         pixelWidth: c_int,
+                         pixelHeight: c_int,
+                         byteStride: c_int);
+}
+
+pub enum uiTableValue {}
+
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum uiTableValueType {
+    String = 0,
+    Image = 1,
+    Int = 2,
+    Color = 3,
+}
+
+extern "C" {
+    pub fn uiFreeTableValue(v: *mut uiTableValue);
+    pub fn uiTableValueGetType(v: *const uiTableValue) -> uiTableValueType;
+    pub fn uiNewTableValueString(str: *const c_char) -> *mut uiTableValue;
+    pub fn uiTableValueString(v: *const uiTableValue) -> *const c_char;
+    pub fn uiNewTableValueImage(img: *mut uiImage) -> *mut uiTableValue;
+    pub fn uiTableValueImage(v: *const uiTableValue) -> *mut uiImage;
+    pub fn uiNewTableValueInt(i: c_int) -> *mut uiTableValue;
+    pub fn uiTableValueInt(v: *const uiTableValue) -> c_int;
+    pub fn uiNewTableValueColor(r: c_double, g: c_double, b: c_double, a: c_double) -> *mut uiTableValue;
+    pub fn uiTableValueColor(v: *const uiTableValue, r: *mut c_double, g: *mut c_double, b: *mut c_double, a: *mut c_double);
+}
+
+pub enum uiTableModel {}
+
+#[repr(C)]
+pub struct uiTableModelHandler {
+    pub NumColumns: extern "C" fn(mh: *mut uiTableModelHandler, m: *mut uiTableModel) -> c_int,
+    pub ColumnType: extern "C" fn(mh: *mut uiTableModelHandler, m: *mut uiTableModel, column: c_int) -> uiTableValueType,
+    pub NumRows: extern "C" fn(mh: *mut uiTableModelHandler, m: *mut uiTableModel) -> c_int,
+    /// The returned value is owned by the caller.
+    pub CellValue: extern "C" fn(mh: *mut uiTableModelHandler, m: *mut uiTableModel, row: c_int, column: c_int) -> *mut uiTableValue,
+    pub SetCellValue: extern "C" fn(mh: *mut uiTableModelHandler, m: *mut uiTableModel, row: c_int, column: c_int, value: *const uiTableValue),
+}
+
+extern "C" {
+    pub fn uiNewTableModel(mh: *mut uiTableModelHandler) -> *mut uiTableModel;
+    pub fn uiFreeTableModel(m: *mut uiTableModel);
+    pub fn uiTableModelRowInserted(m: *mut uiTableModel, newIndex: c_int);
+    pub fn uiTableModelRowChanged(m: *mut uiTableModel, index: c_int);
+    pub fn uiTableModelRowDeleted(m: *mut uiTableModel, oldIndex: c_int);
+}
+
+pub enum uiTable {}
+
+#[repr(C)]
+pub struct uiTableParams {
+    pub Model: *mut uiTableModel,
+    pub RowBackgroundColorModelColumn: c_int,
+}
+
+pub const uiTableModelColumnNeverEditable: c_int = -1;
+pub const uiTableModelColumnAlwaysEditable: c_int = -2;
+
+#[repr(C)]
+pub struct uiTableTextColumnOptionalParams {
+    pub ColorModelColumn: c_int,
+}
+
+extern {
+    pub fn uiTableAppendTextColumn(t: *mut uiTable,
+                                   name: *const c_char,
+                                   textModelColumn: c_int,
+                                   textEditableModelColumn: c_int,
+                                   textParams: *mut uiTableTextColumnOptionalParams);
+    pub fn uiTableAppendImageColumn(t: *mut uiTable,
+                                    name: *const c_char,
+                                    imageModelColumn: c_int);
+    pub fn uiTableAppendCheckboxColumn(t: *mut uiTable,
+                                       name: *const c_char,
+                                       checkboxModelColumn: c_int,
+                                       checkboxEditableModelColumn: c_int);
+    pub fn uiTableAppendImageTextColumn(t: *mut uiTable,
+                                        name: *const c_char,
+                                        imageModelColumn: c_int,
+                                        textModelColumn: c_int,
+                                        textEditableModelColumn: c_int,
+                                        textParams: *mut uiTableTextColumnOptionalParams);
+    pub fn uiTableAppendCheckboxTextColumn(t: *mut uiTable,
+                                           name: *const c_char,
+                                           checkboxModelColumn: c_int,
+                                           checkboxEditableModelColumn: c_int,
+                                           textModelColumn: c_int,
+                                           textEditableModelColumn: c_int,
+                                           textParams: *mut uiTableTextColumnOptionalParams);
+    pub fn uiTableAppendProgressBarColumn(t: *mut uiTable,
+                                          name: *const c_char,
+                                          progressModelColumn: c_int);
+    pub fn uiTableAppendButtonColumn(t: *mut uiTable,
+                                     name: *const c_char,
+                                     buttonModelColumn: c_int,
+                                     buttonClickableModelColumn: c_int);
+    pub fn uiNewTable(params: *mut uiTableParams) -> *mut uiTable;
+}
+
+#[repr(u32)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum uiTableSelectionMode {
+    None = 0,
+    ZeroOrOne = 1,
+    One = 2,
+    ZeroOrMany = 3,
+}
+
+#[repr(C)]
+pub struct uiTableSelection {
+    pub NumRows: c_int,
+    pub Rows: *mut c_int,
+}
+
+extern {
+    pub fn uiTableGetSelectionMode(t: *mut uiTable) -> uiTableSelectionMode;
+    pub fn uiTableSetSelectionMode(t: *mut uiTable, mode: uiTableSelectionMode);
+    pub fn uiTableOnSelectionChanged(t: *mut uiTable,
+                                     f: extern "C" fn(t: *mut uiTable, data: *mut c_void),
+                                     data: *mut c_void);
+    pub fn uiTableGetSelection(t: *mut uiTable) -> *mut uiTableSelection;
+    pub fn uiTableSetSelection(t: *mut uiTable, sel: *mut uiTableSelection);
+    pub fn uiFreeTableSelection(s: *mut uiTableSelection);
+}
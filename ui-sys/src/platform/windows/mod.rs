@@ -2,7 +2,7 @@
 
 #![allow(non_camel_case_types, non_snake_case)]
 
-use libc::{c_char, c_int, c_long, c_ulong, c_uint, c_ushort, c_void, intmax_t, size_t, wchar_t};
+use libc::{c_char, c_int, c_long, c_ulong, c_uint, c_ushort, c_void, size_t, wchar_t};
 use uiControl;
 
 #[repr(C)]
@@ -13,7 +13,7 @@ pub struct uiWindowsControl {
     pub visible: BOOL,
     pub SyncEnableState: extern "C" fn(*mut uiWindowsControl, c_int),
     pub SetParentHWND: extern "C" fn(*mut uiWindowsControl, HWND),
-    pub MinimumSize: extern "C" fn(*mut uiWindowsControl, *mut intmax_t, *mut intmax_t),
+    pub MinimumSize: extern "C" fn(*mut uiWindowsControl, *mut c_int, *mut c_int),
     pub MinimumSizeChanged: extern "C" fn(*mut uiWindowsControl),
     pub LayoutRect: extern "C" fn(*mut uiWindowsControl, *mut RECT),
     pub AssignControlIDZOrder: extern "C" fn(*mut uiWindowsControl, *mut LONG_PTR, *mut HWND),
@@ -23,8 +23,8 @@ extern {
     pub fn uiWindowsControlSyncEnableState(control: *mut uiWindowsControl, state: c_int);
     pub fn uiWindowsControlSetParentHWND(control: *mut uiWindowsControl, parent: HWND);
     pub fn uiWindowsControlMinimumSize(control: *mut uiWindowsControl,
-                                       width: *mut intmax_t,
-                                       height: *mut intmax_t);
+                                       width: *mut c_int,
+                                       height: *mut c_int);
     pub fn uiWindowsControlMinimumSizeChanged(control: *mut uiWindowsControl);
     pub fn uiWindowsControlLayoutRect(control: *mut uiWindowsControl, rect: *mut RECT);
     pub fn uiWindowsControlAssignControlIDZOrder(control: *mut uiWindowsControl,
@@ -57,13 +57,13 @@ extern {
     pub fn uiWindowsWindowText(hwnd: HWND) -> *mut c_char;
     pub fn uiWindowsSetWindowText(hwnd: HWND, text: *const c_char);
 
-    pub fn uiWindowsWindowTextWidth(hwnd: HWND) -> intmax_t;
+    pub fn uiWindowsWindowTextWidth(hwnd: HWND) -> c_int;
 
     pub fn uiWindowsEnsureMoveWindowDuringResize(hwnd: HWND,
-                                                 x: intmax_t,
-                                                 y: intmax_t,
-                                                 width: intmax_t,
-                                                 height: intmax_t);
+                                                 x: c_int,
+                                                 y: c_int,
+                                                 width: c_int,
+                                                 height: c_int);
 
     pub fn uiWindowsRegisterWM_COMMANDHandler(hwnd: HWND,
                                               handler: extern "C" fn(*mut uiControl,